@@ -1,8 +1,10 @@
 use std::{error::Error, fmt::{Display, Formatter, Result as FmtResult}, time::Duration};
 use indexmap::IndexMap;
-use rand::{rngs::SmallRng, SeedableRng};
-use rand_distr::{Distribution as _, Exp, Normal, Triangular, Uniform};
+use rand::{distributions::Open01, rngs::SmallRng, Rng, RngCore, SeedableRng};
+use rand_distr::{Distribution as _, Beta, Gamma, Normal};
 use serde::{Deserialize, Serialize};
+use tai_time::MonotonicTime;
+use statrs::function::{beta::{beta_reg, inv_beta_reg}, erf::{erfc, erfc_inv}, gamma::{gamma, gamma_lr}};
 
 #[derive(Debug, Clone, Serialize)]
 /// A short, lightweight identifier for an event. Very useful for understanding causal flow of events via log files.
@@ -23,17 +25,40 @@ impl EventId {
 /// An instantiated Distribution that can be sampled from via the `sample` method.
 /// Usually constructed via the `DistributionFactory::create` method, though the Constant variant can be constructed directly.
 pub enum Distribution {
-    Uniform(Uniform<f64>, SmallRng),
-    Triangular(Triangular<f64>, SmallRng),
+    Uniform { min: f64, max: f64, rng: StreamRng },
+    Triangular { min: f64, max: f64, mode: f64, rng: StreamRng },
     Constant(f64),
-    Normal(Normal<f64>, SmallRng),
-    TruncNormal { normal_dist: Normal<f64>, min: f64, max: f64, rng: SmallRng },
-    Exponential(Exp<f64>, SmallRng),
+    Normal(Normal<f64>, StreamRng),
+    TruncNormal { normal_dist: Normal<f64>, min: f64, max: f64, rng: StreamRng },
+    Exponential { mean: f64, rng: StreamRng },
+    /// Log-normal distribution, where `mu` and `sigma` are the mean and standard deviation of the underlying normal.
+    LogNormal { mu: f64, sigma: f64, rng: StreamRng },
+    Gamma { gamma_dist: Gamma<f64>, shape: f64, scale: f64, rng: StreamRng },
+    Weibull { shape: f64, scale: f64, rng: StreamRng },
+    Erlang { k: u32, mean: f64, rng: StreamRng },
+    Beta { beta_dist: Beta<f64>, alpha: f64, beta: f64, min: f64, max: f64, rng: StreamRng },
+    /// PERT distribution, represented as the equivalent Beta distribution scaled onto [min, max].
+    Pert { beta_dist: Beta<f64>, alpha: f64, beta: f64, min: f64, max: f64, rng: StreamRng },
+    /// Piecewise-linear CDF through the points `(cdf_x[i], cdf_p[i])`, sampled by inverse transform.
+    Empirical { cdf_x: Vec<f64>, cdf_p: Vec<f64>, rng: StreamRng },
+    /// Weighted discrete values in ascending order, with `cumulative` holding the normalised cumulative weights.
+    Discrete { values: Vec<f64>, cumulative: Vec<f64>, rng: StreamRng },
+    /// Mixture of `components`, with `cumulative` holding the normalised cumulative component weights.
+    Mixture { components: Vec<Distribution>, cumulative: Vec<f64>, rng: StreamRng },
+    Shifted { offset: f64, inner: Box<Distribution> },
+    Scaled { factor: f64, inner: Box<Distribution> },
+    Clamped { min: f64, max: f64, inner: Box<Distribution> },
+    Scheduled { schedule: Schedule, distributions: Vec<Distribution> },
+    /// Inter-arrival times in `unit`, with `rates` in arrivals per `unit`.
+    NonHomogeneousPoisson { schedule: Schedule, rates: Vec<f64>, max_rate: f64, unit: TimeUnit, rng: StreamRng },
+    /// `inner`, whose samples are durations in `unit`.
+    Timed { unit: TimeUnit, inner: Box<Distribution> },
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(tag = "type")]
+#[serde(tag = "type", remote = "Self")]
 /// Serialisable configuration for creating a Distribution instance. Uniquely defines the distribution to be created, excluding the random number generator.
+/// Also has a compact string form, e.g. `Tri(10, 20, 15)` - see `distribution_syntax`.
 pub enum DistributionConfig {
     Uniform { min: f64, max: f64},
     Triangular { min: f64, max: f64, mode: f64 },
@@ -41,12 +66,240 @@ pub enum DistributionConfig {
     Normal { mean: f64, std: f64 },
     TruncNormal { mean: f64, std: f64, min: Option<f64>, max: Option<f64> },
     Exponential { mean: f64 },
+    /// Log-normal distribution, parameterised by the mean and standard deviation of the samples themselves (not of their logarithm).
+    LogNormal { mean: f64, std: f64 },
+    Gamma { shape: f64, scale: f64 },
+    Weibull { shape: f64, scale: f64 },
+    /// Sum of `k` exponential stages, with `mean` being the mean of the total.
+    Erlang { k: u32, mean: f64 },
+    /// Beta distribution scaled from [0, 1] onto [min, max].
+    Beta { alpha: f64, beta: f64, min: f64, max: f64 },
+    Pert { min: f64, max: f64, mode: f64 },
+    /// Piecewise-linear CDF built from observed data, either raw samples or a histogram.
+    Empirical { data: EmpiricalData },
+    /// Discrete values, each sampled with probability proportional to its weight.
+    Discrete { data: DiscreteData },
+    /// Samples from one of `components`, chosen with probability proportional to its weight.
+    Mixture { components: Vec<(f64, DistributionConfig)> },
+    /// `inner` plus `offset`.
+    Shifted { offset: f64, inner: Box<DistributionConfig> },
+    /// `inner` multiplied by a positive `factor`.
+    Scaled { factor: f64, inner: Box<DistributionConfig> },
+    /// `inner`, with samples outside [min, max] moved to the nearest bound. Unlike TruncNormal, the probability mass
+    /// outside the bounds is kept as point masses at the bounds.
+    Clamped { min: Option<f64>, max: Option<f64>, inner: Box<DistributionConfig> },
+    /// Switches between distributions depending on simulation time. Each segment is a `(start, config)` pair, and
+    /// applies from its start until the start of the next segment. See `ScheduleIndex` for the meaning of `start`.
+    Scheduled { index: ScheduleIndex, segments: Vec<(f64, DistributionConfig)> },
+    /// Time in seconds until the next arrival of a non-homogeneous Poisson process, sampled by thinning. Each segment
+    /// is a `(start, rate)` pair, with rate in arrivals per second (or per `unit` when wrapped in Timed). Intended as
+    /// the `process_time_distr` of sources.
+    NonHomogeneousPoisson { index: ScheduleIndex, rates: Vec<(f64, f64)> },
+    /// `inner`, with samples declaring durations in `unit`, e.g. an exponential repair time with a mean of 2 hours.
+    /// This overrides the time unit of the component sampling it. Only allowed as the outermost distribution. For
+    /// NonHomogeneousPoisson, the rates become arrivals per `unit`.
+    Timed { unit: TimeUnit, inner: Box<DistributionConfig> },
 }
 
-/// Factory for creating Distribution instances based on a DistributionConfig. For random distributions, creates SmallRng instances seeded with an incrementing seed value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+/// Unit in which sampled durations are expressed. Components hold a default `time_unit` for distributions that do not
+/// declare their own via `DistributionConfig::Timed`.
+pub enum TimeUnit {
+    #[default]
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl TimeUnit {
+    /// Number of seconds in one unit.
+    pub fn secs(&self) -> f64 {
+        match self {
+            TimeUnit::Seconds => 1.,
+            TimeUnit::Minutes => 60.,
+            TimeUnit::Hours => 3600.,
+            TimeUnit::Days => 86400.,
+        }
+    }
+
+    /// Converts `value` in this unit into a Duration.
+    pub fn to_duration(&self, value: f64) -> Duration {
+        Duration::from_secs_f64(value * self.secs())
+    }
+
+    /// Converts a Duration into a value in this unit.
+    pub fn from_duration(&self, duration: Duration) -> f64 {
+        duration.as_secs_f64() / self.secs()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+/// How segment starts of a time-varying distribution map onto simulation time.
+///
+/// `HourOfDay` and `DayOfWeek` are cyclic, with starts in hours (0 to 24) and days (0 to 7, Monday = 0)
+/// respectively. `Piecewise` starts are in seconds of `MonotonicTime` (i.e. since 1970-01-01 00:00:00), or
+/// seconds into each cycle if a `period` in seconds is given. For cyclic indices, times before the first start belong
+/// to the last segment of the previous cycle.
+pub enum ScheduleIndex {
+    HourOfDay,
+    DayOfWeek,
+    Piecewise { period: Option<f64> },
+}
+
+#[derive(Debug, Clone)]
+/// Segment starts of a time-varying distribution, converted to seconds.
+pub struct Schedule {
+    pub index: ScheduleIndex,
+    pub starts: Vec<f64>,
+}
+
+impl Schedule {
+    /// Creates a schedule from segment starts, in the units of `index`.
+    pub fn new(index: ScheduleIndex, starts: Vec<f64>) -> Result<Self, DistributionParametersError> {
+        let (unit, cycle) = match index {
+            ScheduleIndex::HourOfDay => (3600., Some(86400.)),
+            ScheduleIndex::DayOfWeek => (86400., Some(604800.)),
+            ScheduleIndex::Piecewise { period } => (1., period),
+        };
+        let starts: Vec<f64> = starts.into_iter().map(|s| s * unit).collect();
+        if starts.is_empty() {
+            return Err(DistributionParametersError {
+                msg: "Schedule requires at least one segment".to_string()
+            })
+        }
+        if starts.iter().any(|s| !s.is_finite()) || starts.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(DistributionParametersError {
+                msg: "Schedule segment starts must be finite and strictly increasing".to_string()
+            })
+        }
+        if let Some(cycle) = cycle
+            && (!(cycle > 0. && cycle.is_finite()) || starts[0] < 0. || *starts.last().unwrap() >= cycle) {
+            return Err(DistributionParametersError {
+                msg: format!("Schedule period must be positive, and segment starts must lie within [0, {})", cycle / unit)
+            })
+        }
+        Ok(Schedule { index, starts })
+    }
+
+    fn cycle(&self) -> Option<f64> {
+        match self.index {
+            ScheduleIndex::HourOfDay => Some(86400.),
+            ScheduleIndex::DayOfWeek => Some(604800.),
+            ScheduleIndex::Piecewise { period } => period,
+        }
+    }
+
+    /// Position of `time` within the schedule, in seconds.
+    fn position(&self, time: MonotonicTime) -> f64 {
+        let secs = time.as_secs() as f64 + time.subsec_nanos() as f64 * 1e-9;
+        match self.index {
+            // 1970-01-01 was a Thursday, three days after the start of the week
+            ScheduleIndex::DayOfWeek => (secs + 3. * 86400.).rem_euclid(604800.),
+            _ => match self.cycle() {
+                Some(cycle) => secs.rem_euclid(cycle),
+                None => secs,
+            },
+        }
+    }
+
+    /// Time from `time` until the next segment start, or None if there are no further starts. If `inclusive`, a start
+    /// at exactly `time` counts as the next start.
+    pub fn time_to_next_start(&self, time: MonotonicTime, inclusive: bool) -> Option<Duration> {
+        // Tolerance for the rounding error of `position`
+        const EPSILON: f64 = 1e-6;
+        let position = self.position(time);
+        let next = self.starts.iter()
+            .find(|&&s| if inclusive { s >= position - EPSILON } else { s > position + EPSILON })
+            .cloned()
+            .or_else(|| self.cycle().map(|cycle| self.starts[0] + cycle))?;
+        Some(Duration::from_secs_f64((next - position).max(0.)))
+    }
+
+    /// Index of the segment active at `time`.
+    pub fn segment_at(&self, time: MonotonicTime) -> usize {
+        match self.starts.partition_point(|&s| s <= self.position(time)) {
+            0 if self.cycle().is_some() => self.starts.len() - 1,
+            0 => 0,
+            j => j - 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Source of data points for `DistributionConfig::Empirical`.
+///
+/// CSV files are expected to have a header row. `SamplesCsv` reads observations from the first column, and
+/// `HistogramCsv` reads bins from the columns `lower, upper, count`, where consecutive bins must be contiguous.
+pub enum EmpiricalData {
+    Samples(Vec<f64>),
+    Histogram { bin_edges: Vec<f64>, counts: Vec<f64> },
+    SamplesCsv { path: String },
+    HistogramCsv { path: String },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Source of data points for `DistributionConfig::Discrete`.
+///
+/// CSV files are expected to have a header row, followed by rows of `value, weight`.
+pub enum DiscreteData {
+    Points { values: Vec<f64>, weights: Vec<f64> },
+    Csv { path: String },
+}
+
+/// Factory for creating Distribution instances based on a DistributionConfig. For random distributions, creates StreamRng instances seeded with an incrementing seed value.
+///
+/// For variance reduction, an antithetic factory (see `new_antithetic` and `with_antithetic`) creates distributions that draw `1 - U` wherever the
+/// equivalent non-antithetic distribution draws `U`. Constructing the same model with `new(seed)` and
+/// `new_antithetic(seed)` gives a pair of negatively correlated replications. For common random numbers across
+/// scenarios, construct each scenario from `replay` of the same factory, and prefer `create_named` so that
+/// components present in only some scenarios do not shift the streams of the others.
 pub struct DistributionFactory {
     pub base_seed: u64,
     pub next_seed: u64,
+    antithetic: bool,
+}
+
+#[derive(Debug, Clone)]
+/// Random number generator used by Distribution instances. When `antithetic` is set, every output is bitwise
+/// inverted, which turns each uniform `U` drawn from it into `1 - U`.
+///
+/// Distributions sampled by inversion (Uniform, Triangular, Exponential, Normal, TruncNormal, LogNormal, Weibull,
+/// Erlang, Empirical and Discrete) are monotone in `U`, so antithetic pairs of these are negatively correlated. Gamma,
+/// Beta and PERT are sampled by rejection, for which antithetic streams are valid but give little variance reduction.
+pub struct StreamRng {
+    rng: SmallRng,
+    antithetic: bool,
+}
+
+impl StreamRng {
+    pub fn new(seed: u64, antithetic: bool) -> Self {
+        StreamRng { rng: SmallRng::seed_from_u64(seed), antithetic }
+    }
+}
+
+impl RngCore for StreamRng {
+    fn next_u32(&mut self) -> u32 {
+        let x = self.rng.next_u32();
+        if self.antithetic { !x } else { x }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let x = self.rng.next_u64();
+        if self.antithetic { !x } else { x }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+        if self.antithetic {
+            dest.iter_mut().for_each(|b| *b = !*b);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -67,38 +320,96 @@ impl DistributionFactory {
         DistributionFactory {
             base_seed,
             next_seed: base_seed,
+            antithetic: false,
+        }
+    }
+
+    /// Creates a factory whose distributions mirror those of `DistributionFactory::new(base_seed)`, drawing `1 - U`
+    /// for every `U`.
+    pub fn new_antithetic(base_seed: u64) -> Self {
+        DistributionFactory {
+            antithetic: true,
+            ..DistributionFactory::new(base_seed)
         }
     }
 
+    /// Sets whether the factory creates antithetic distributions.
+    pub fn with_antithetic(mut self, antithetic: bool) -> Self {
+        self.antithetic = antithetic;
+        self
+    }
+
+    pub fn is_antithetic(&self) -> bool {
+        self.antithetic
+    }
+
+    /// A fresh factory with the same base seed and antithetic setting, which replays the identical stream layout when
+    /// the same sequence of distributions is created. Use one per scenario for common random numbers.
+    pub fn replay(&self) -> Self {
+        DistributionFactory {
+            base_seed: self.base_seed,
+            next_seed: self.base_seed,
+            antithetic: self.antithetic,
+        }
+    }
+
+    /// As `replay`, but with the antithetic setting flipped, giving the mirror replication of this factory.
+    pub fn antithetic_counterpart(&self) -> Self {
+        DistributionFactory {
+            antithetic: !self.antithetic,
+            ..self.replay()
+        }
+    }
+
+    /// Creates a distribution seeded from the factory's incrementing seed. The stream a distribution receives therefore
+    /// depends on how many distributions were created before it - use `create_named` where that is undesirable.
     pub fn create(&mut self, config: DistributionConfig) -> Result<Distribution, DistributionParametersError> {
-        let result = match config {
+        let result = self.create_seeded(config, self.next_seed);
+        self.next_seed += 1;
+        result
+    }
+
+    /// Creates a distribution whose seed is derived from `base_seed` and a stable hash of `stream_name` (e.g.
+    /// `"Conveyor/process_time"`), so the same name always receives the same random stream, regardless of
+    /// construction order. Distinct components should use distinct stream names.
+    pub fn create_named(&mut self, stream_name: &str, config: DistributionConfig) -> Result<Distribution, DistributionParametersError> {
+        self.create_seeded(config, self.named_seed(stream_name))
+    }
+
+    /// The seed used by `create_named` for the given stream name.
+    pub fn named_seed(&self, stream_name: &str) -> u64 {
+        self.base_seed.wrapping_add(stable_hash(stream_name))
+    }
+
+    fn create_seeded(&self, config: DistributionConfig, seed: u64) -> Result<Distribution, DistributionParametersError> {
+        match config {
             DistributionConfig::Uniform { min, max } => {
-                let rng = SmallRng::seed_from_u64(self.next_seed);
-                Ok(Distribution::Uniform(Uniform::new(min, max), rng))
+                if min >= max || !min.is_finite() || !max.is_finite() {
+                    return Err(DistributionParametersError {
+                        msg: "Uniform requires finite min and max, with min < max".to_string()
+                    })
+                }
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::Uniform { min, max, rng })
             },
             DistributionConfig::Triangular { min, max, mode } => {
-                let triangle_dist = Triangular::new(min, max, mode);
-                match triangle_dist {
-                    Ok(dist) => {
-                        let rng = SmallRng::seed_from_u64(self.next_seed);
-                        Ok(Distribution::Triangular(dist, rng))
-                    },
-                    Err(e) => {
-                        Err(DistributionParametersError {
-                            msg: e.to_string()
-                        })
-                    }
+                if min >= max || !(min..=max).contains(&mode) || !min.is_finite() || !max.is_finite() {
+                    return Err(DistributionParametersError {
+                        msg: "Triangular requires finite min and max, with min < max and min <= mode <= max".to_string()
+                    })
                 }
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::Triangular { min, max, mode, rng })
             },
             DistributionConfig::Constant(x) => Ok(Distribution::Constant(x)),
             DistributionConfig::Normal { mean , std } => {
                 match Normal::new(mean, std) {
                     Ok(dist) => {
-                        let rng = SmallRng::seed_from_u64(self.next_seed);
-                        return Ok(Distribution::Normal(dist, rng))
+                        let rng = StreamRng::new(seed, self.antithetic);
+                        Ok(Distribution::Normal(dist, rng))
                     },
                     Err(e) => {
-                        return Err(DistributionParametersError {
+                        Err(DistributionParametersError {
                             msg: e.to_string()
                         })
                     }
@@ -117,65 +428,822 @@ impl DistributionFactory {
                             })
                         }
 
-                        let rng = SmallRng::seed_from_u64(self.next_seed);
-                        return Ok(Distribution::TruncNormal { normal_dist: dist, min, max, rng })
+                        let (a, b) = ((min - mean) / std, (max - mean) / std);
+                        let mass = std_normal_mass(a, b);
+                        if mass.is_nan() || mass < TRUNC_NORMAL_MIN_MASS {
+                            return Err(DistributionParametersError {
+                                msg: format!("TruncNormal window [{}, {}] lies {:.1} to {:.1} standard deviations from the mean, and has negligible probability mass ({:e})", min, max, a, b, mass)
+                            })
+                        }
+
+                        let rng = StreamRng::new(seed, self.antithetic);
+                        Ok(Distribution::TruncNormal { normal_dist: dist, min, max, rng })
                     },
                     Err(e) => {
-                        return Err(DistributionParametersError {
+                        Err(DistributionParametersError {
                             msg: e.to_string()
                         })
                     }
                 }
             },
             DistributionConfig::Exponential { mean } => {
-                match Exp::new(1. / mean) {
+                if mean <= 0. || !mean.is_finite() {
+                    return Err(DistributionParametersError {
+                        msg: "Exponential mean must be positive and finite".to_string()
+                    })
+                }
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::Exponential { mean, rng })
+            },
+            DistributionConfig::LogNormal { mean, std } => {
+                if mean <= 0. || std <= 0. || !mean.is_finite() || !std.is_finite() {
+                    return Err(DistributionParametersError {
+                        msg: "LogNormal mean and standard deviation must both be positive and finite".to_string()
+                    })
+                }
+                let sigma = (1. + (std / mean).powi(2)).ln().sqrt();
+                let mu = mean.ln() - sigma * sigma / 2.;
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::LogNormal { mu, sigma, rng })
+            },
+            DistributionConfig::Gamma { shape, scale } => {
+                match Gamma::new(shape, scale) {
                     Ok(dist) => {
-                        let rng = SmallRng::seed_from_u64(self.next_seed);
-                        return Ok(Distribution::Exponential(dist, rng))
+                        let rng = StreamRng::new(seed, self.antithetic);
+                        Ok(Distribution::Gamma { gamma_dist: dist, shape, scale, rng })
                     },
                     Err(e) => {
-                        return Err(DistributionParametersError {
+                        Err(DistributionParametersError {
                             msg: e.to_string()
                         })
                     }
                 }
-            }
-        };
-
-        self.next_seed += 1;
+            },
+            DistributionConfig::Weibull { shape, scale } => {
+                if shape <= 0. || scale <= 0. || !shape.is_finite() || !scale.is_finite() {
+                    return Err(DistributionParametersError {
+                        msg: "Weibull shape and scale must both be positive and finite".to_string()
+                    })
+                }
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::Weibull { shape, scale, rng })
+            },
+            DistributionConfig::Erlang { k, mean } => {
+                if k == 0 || mean <= 0. || !mean.is_finite() {
+                    return Err(DistributionParametersError {
+                        msg: "Erlang requires k >= 1 and a positive, finite mean".to_string()
+                    })
+                }
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::Erlang { k, mean, rng })
+            },
+            DistributionConfig::Beta { alpha, beta, min, max } => {
+                if min >= max {
+                    return Err(DistributionParametersError {
+                        msg: "Minimum value cannot be greater than or equal maximum value".to_string()
+                    })
+                }
+                match Beta::new(alpha, beta) {
+                    Ok(dist) => {
+                        let rng = StreamRng::new(seed, self.antithetic);
+                        Ok(Distribution::Beta { beta_dist: dist, alpha, beta, min, max, rng })
+                    },
+                    Err(e) => {
+                        Err(DistributionParametersError {
+                            msg: e.to_string()
+                        })
+                    }
+                }
+            },
+            DistributionConfig::Pert { min, max, mode } => {
+                if min >= max || !(min..=max).contains(&mode) || !min.is_finite() || !max.is_finite() {
+                    return Err(DistributionParametersError {
+                        msg: "Pert requires finite min and max, with min < max and min <= mode <= max".to_string()
+                    })
+                }
+                let alpha = 1. + 4. * (mode - min) / (max - min);
+                let beta = 1. + 4. * (max - mode) / (max - min);
+                match Beta::new(alpha, beta) {
+                    Ok(dist) => {
+                        let rng = StreamRng::new(seed, self.antithetic);
+                        Ok(Distribution::Pert { beta_dist: dist, alpha, beta, min, max, rng })
+                    },
+                    Err(e) => {
+                        Err(DistributionParametersError {
+                            msg: e.to_string()
+                        })
+                    }
+                }
+            },
+            DistributionConfig::Empirical { data } => {
+                let (cdf_x, cdf_p) = empirical_cdf(data)?;
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::Empirical { cdf_x, cdf_p, rng })
+            },
+            DistributionConfig::Discrete { data } => {
+                let (values, weights) = match data {
+                    DiscreteData::Points { values, weights } => (values, weights),
+                    DiscreteData::Csv { path } => {
+                        let mut columns = read_csv_columns(&path, 2)?.into_iter();
+                        (columns.next().unwrap(), columns.next().unwrap())
+                    },
+                };
+                if values.is_empty() || values.len() != weights.len() {
+                    return Err(DistributionParametersError {
+                        msg: format!("Discrete requires the same non-zero number of values and weights, got {} values and {} weights", values.len(), weights.len())
+                    })
+                }
+                if values.iter().any(|x| !x.is_finite()) {
+                    return Err(DistributionParametersError {
+                        msg: "Discrete values must all be finite".to_string()
+                    })
+                }
+                // Sorted so that the cumulative weights form the CDF
+                let mut points: Vec<(f64, f64)> = values.into_iter().zip(weights).collect();
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let (values, weights): (Vec<f64>, Vec<f64>) = points.into_iter().unzip();
+                let cumulative = normalised_cumulative(&weights)?;
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::Discrete { values, cumulative, rng })
+            },
+            DistributionConfig::Mixture { components } => {
+                if components.is_empty() {
+                    return Err(DistributionParametersError {
+                        msg: "Mixture requires at least one component".to_string()
+                    })
+                }
+                let (weights, configs): (Vec<f64>, Vec<DistributionConfig>) = components.into_iter().unzip();
+                let cumulative = normalised_cumulative(&weights)?;
+                let components = configs.into_iter().enumerate()
+                    .map(|(i, config)| self.create_child(config, derive_seed(seed, i as u64)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::Mixture { components, cumulative, rng })
+            },
+            DistributionConfig::Shifted { offset, inner } => {
+                if !offset.is_finite() {
+                    return Err(DistributionParametersError {
+                        msg: "Shifted offset must be finite".to_string()
+                    })
+                }
+                let inner = Box::new(self.create_child(*inner, seed)?);
+                Ok(Distribution::Shifted { offset, inner })
+            },
+            DistributionConfig::Scaled { factor, inner } => {
+                if factor <= 0. || !factor.is_finite() {
+                    return Err(DistributionParametersError {
+                        msg: "Scaled factor must be positive and finite".to_string()
+                    })
+                }
+                let inner = Box::new(self.create_child(*inner, seed)?);
+                Ok(Distribution::Scaled { factor, inner })
+            },
+            DistributionConfig::Clamped { min, max, inner } => {
+                let min = min.unwrap_or(f64::NEG_INFINITY);
+                let max = max.unwrap_or(f64::INFINITY);
+                if min.is_nan() || max.is_nan() || min > max {
+                    return Err(DistributionParametersError {
+                        msg: "Clamped minimum value cannot be greater than maximum value".to_string()
+                    })
+                }
+                let inner = Box::new(self.create_child(*inner, seed)?);
+                Ok(Distribution::Clamped { min, max, inner })
+            },
+            DistributionConfig::Scheduled { index, segments } => {
+                let (starts, configs): (Vec<f64>, Vec<DistributionConfig>) = segments.into_iter().unzip();
+                let schedule = Schedule::new(index, starts)?;
+                let distributions = configs.into_iter().enumerate()
+                    .map(|(i, config)| self.create_child(config, derive_seed(seed, i as u64)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Distribution::Scheduled { schedule, distributions })
+            },
+            DistributionConfig::NonHomogeneousPoisson { index, rates } => {
+                let (starts, rates): (Vec<f64>, Vec<f64>) = rates.into_iter().unzip();
+                let schedule = Schedule::new(index, starts)?;
+                if rates.iter().any(|r| !r.is_finite() || *r < 0.) {
+                    return Err(DistributionParametersError {
+                        msg: "NonHomogeneousPoisson rates must be finite and non-negative".to_string()
+                    })
+                }
+                // Thinning only terminates if a positive rate recurs after any point in time
+                let recurring_rate = if schedule.cycle().is_some() { rates.iter().cloned().fold(0., f64::max) } else { *rates.last().unwrap() };
+                if recurring_rate <= 0. {
+                    return Err(DistributionParametersError {
+                        msg: "NonHomogeneousPoisson requires a positive rate in every cycle, or in the last segment if there is no period".to_string()
+                    })
+                }
+                let max_rate = rates.iter().cloned().fold(0., f64::max);
+                let rng = StreamRng::new(seed, self.antithetic);
+                Ok(Distribution::NonHomogeneousPoisson { schedule, rates, max_rate, unit: TimeUnit::Seconds, rng })
+            },
+            DistributionConfig::Timed { unit, inner } => {
+                let inner = match self.create_child(*inner, seed)? {
+                    Distribution::NonHomogeneousPoisson { schedule, rates, max_rate, rng, .. } => {
+                        Distribution::NonHomogeneousPoisson { schedule, rates, max_rate, unit, rng }
+                    },
+                    inner => inner,
+                };
+                Ok(Distribution::Timed { unit, inner: Box::new(inner) })
+            },
+        }
+    }
 
-        result
+    /// Creates a distribution nested inside another, where a time unit cannot be declared.
+    fn create_child(&self, config: DistributionConfig, seed: u64) -> Result<Distribution, DistributionParametersError> {
+        if let DistributionConfig::Timed { .. } = config {
+            return Err(DistributionParametersError {
+                msg: "Timed can only be the outermost distribution".to_string()
+            })
+        }
+        self.create_seeded(config, seed)
     }
 }
 
+/// Smallest probability mass that a TruncNormal window may carry, about 6 standard deviations into one tail. The
+/// untruncated distribution would practically never produce a value in a window with less mass, which usually means
+/// the window or the mean is misconfigured, so `DistributionFactory::create` rejects it.
+pub const TRUNC_NORMAL_MIN_MASS: f64 = 1e-9;
+
+/// Inverse CDF of the standard normal distribution.
+fn std_normal_quantile(p: f64) -> f64 {
+    -std::f64::consts::SQRT_2 * erfc_inv(2. * p)
+}
+
+/// Derives an independent seed for the `index`-th child of a composite distribution, via the SplitMix64 finaliser.
+fn derive_seed(seed: u64, index: u64) -> u64 {
+    let mut z = seed.wrapping_add((index + 1).wrapping_mul(0x9e3779b97f4a7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// 64-bit FNV-1a hash. Unlike `std::hash::DefaultHasher`, the output is guaranteed not to change between Rust
+/// versions or platforms, so named random streams remain reproducible.
+fn stable_hash(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
 impl Distribution {
+    /// Samples the distribution. Time-varying distributions are sampled as they apply at `MonotonicTime::EPOCH` - use
+    /// `sample_at` for them.
     pub fn sample(&mut self) -> f64 {
         match self {
-            Distribution::Uniform(dist, rng) => {
-                dist.sample(rng)
-            },
-            Distribution::Triangular(dist, rng) => {
-                dist.sample(rng)
-            },
             Distribution::Constant(value) => {
                 *value
             },
-            Distribution::Normal(dist, rng) => {
-                dist.sample(rng)
+            Distribution::Gamma { gamma_dist, rng, .. } => {
+                gamma_dist.sample(rng)
+            },
+            Distribution::Erlang { k, mean, rng } => {
+                // Sum of k exponential stages, each sampled by inversion
+                -*mean / *k as f64 * (0..*k).map(|_| rng.sample::<f64, _>(Open01).ln()).sum::<f64>()
+            },
+            Distribution::Beta { beta_dist, min, max, rng, .. } | Distribution::Pert { beta_dist, min, max, rng, .. } => {
+                *min + (*max - *min) * beta_dist.sample(rng)
+            },
+            Distribution::Mixture { components, cumulative, rng } => {
+                let u: f64 = rng.sample(Open01);
+                let i = cumulative.partition_point(|&c| c < u).min(components.len() - 1);
+                components[i].sample()
+            },
+            Distribution::Shifted { offset, inner } => {
+                inner.sample() + *offset
+            },
+            Distribution::Scaled { factor, inner } => {
+                inner.sample() * *factor
+            },
+            Distribution::Clamped { min, max, inner } => {
+                inner.sample().clamp(*min, *max)
+            },
+            Distribution::Scheduled { .. } | Distribution::NonHomogeneousPoisson { .. } => {
+                self.sample_at(MonotonicTime::EPOCH)
+            },
+            Distribution::Timed { inner, .. } => {
+                inner.sample()
+            },
+            Distribution::Uniform { rng, .. }
+            | Distribution::Triangular { rng, .. }
+            | Distribution::Normal(_, rng)
+            | Distribution::TruncNormal { rng, .. }
+            | Distribution::Exponential { rng, .. }
+            | Distribution::LogNormal { rng, .. }
+            | Distribution::Weibull { rng, .. }
+            | Distribution::Empirical { rng, .. }
+            | Distribution::Discrete { rng, .. } => {
+                let u: f64 = rng.sample(Open01);
+                self.quantile(u)
+            },
+        }
+    }
+
+    /// Samples the distribution as it applies at `time`. Only differs from `sample` for time-varying distributions
+    /// (Scheduled and NonHomogeneousPoisson), and combinators containing them. Processes sample their distributions
+    /// with this method.
+    pub fn sample_at(&mut self, time: MonotonicTime) -> f64 {
+        match self {
+            Distribution::Scheduled { schedule, distributions } => {
+                distributions[schedule.segment_at(time)].sample_at(time)
             },
-            Distribution::TruncNormal { normal_dist, min, max, rng } => {
+            Distribution::NonHomogeneousPoisson { schedule, rates, max_rate, unit, rng } => {
+                // Thinning: propose arrivals at the maximum rate, accepting each with probability rate(t) / max_rate
+                let mut elapsed = 0.;
                 loop {
-                    let x = normal_dist.sample(rng);
-                    if x >= *min && x <= *max {
-                        break x;
+                    elapsed -= rng.sample::<f64, _>(Open01).ln() / *max_rate;
+                    let candidate = time + unit.to_duration(elapsed);
+                    let u: f64 = rng.sample(Open01);
+                    if u * *max_rate <= rates[schedule.segment_at(candidate)] {
+                        break elapsed;
                     }
                 }
             },
-            Distribution::Exponential(dist, rng) => {
-                dist.sample(rng)
+            Distribution::Mixture { components, cumulative, rng } => {
+                let u: f64 = rng.sample(Open01);
+                let i = cumulative.partition_point(|&c| c < u).min(components.len() - 1);
+                components[i].sample_at(time)
+            },
+            Distribution::Shifted { offset, inner } => inner.sample_at(time) + *offset,
+            Distribution::Scaled { factor, inner } => inner.sample_at(time) * *factor,
+            Distribution::Clamped { min, max, inner } => inner.sample_at(time).clamp(*min, *max),
+            Distribution::Timed { inner, .. } => inner.sample_at(time),
+            _ => self.sample(),
+        }
+    }
+
+    /// The time unit declared by the distribution itself, which takes precedence over the unit of the component
+    /// sampling it. Only Timed and NonHomogeneousPoisson distributions declare a unit.
+    pub fn time_unit(&self) -> Option<TimeUnit> {
+        match self {
+            Distribution::Timed { unit, .. } | Distribution::NonHomogeneousPoisson { unit, .. } => Some(*unit),
+            _ => None,
+        }
+    }
+
+    /// Whether samples depend on the time they are drawn at, i.e. the distribution is Scheduled or
+    /// NonHomogeneousPoisson, or a combinator containing one.
+    pub fn is_time_varying(&self) -> bool {
+        match self {
+            Distribution::Scheduled { .. } | Distribution::NonHomogeneousPoisson { .. } => true,
+            Distribution::Mixture { components, .. } => components.iter().any(Distribution::is_time_varying),
+            Distribution::Shifted { inner, .. }
+            | Distribution::Scaled { inner, .. }
+            | Distribution::Clamped { inner, .. }
+            | Distribution::Timed { inner, .. } => inner.is_time_varying(),
+            _ => false,
+        }
+    }
+
+    /// Samples a duration as it applies at `time`, in the distribution's own time unit if it declares one, otherwise
+    /// in `default_unit` (usually the `time_unit` of the component).
+    pub fn sample_duration_at(&mut self, time: MonotonicTime, default_unit: TimeUnit) -> Duration {
+        let unit = self.time_unit().unwrap_or(default_unit);
+        unit.to_duration(self.sample_at(time))
+    }
+
+    /// As `sample_duration_at`, for distributions which are not time-varying.
+    pub fn sample_duration(&mut self, default_unit: TimeUnit) -> Duration {
+        let unit = self.time_unit().unwrap_or(default_unit);
+        unit.to_duration(self.sample())
+    }
+
+    /// The distribution in effect at `time`. This is `self` except for Scheduled distributions, where it is the
+    /// distribution of the active segment, and Timed distributions, where it is the inner distribution in effect.
+    pub fn at(&self, time: MonotonicTime) -> &Distribution {
+        match self {
+            Distribution::Scheduled { schedule, distributions } => distributions[schedule.segment_at(time)].at(time),
+            Distribution::Timed { inner, .. } => inner.at(time),
+            _ => self,
+        }
+    }
+
+    /// The mean of the distribution. For time-varying distributions, this and the other summary methods describe
+    /// the first segment; use `at` to select the segment for a given time. For NonHomogeneousPoisson, this is
+    /// the distribution of inter-arrival times at the first segment's rate.
+    pub fn mean(&self) -> f64 {
+        match self {
+            Distribution::Uniform { min, max, .. } => (min + max) / 2.,
+            Distribution::Triangular { min, max, mode, .. } => (min + max + mode) / 3.,
+            Distribution::Constant(value) => *value,
+            Distribution::Normal(dist, _) => dist.mean(),
+            Distribution::TruncNormal { normal_dist, min, max, .. } => {
+                let (mu, sigma) = (normal_dist.mean(), normal_dist.std_dev());
+                let (a, b) = ((min - mu) / sigma, (max - mu) / sigma);
+                mu + sigma * (std_normal_pdf(a) - std_normal_pdf(b)) / std_normal_mass(a, b)
+            },
+            Distribution::Exponential { mean, .. } => *mean,
+            Distribution::LogNormal { mu, sigma, .. } => (mu + sigma * sigma / 2.).exp(),
+            Distribution::Gamma { shape, scale, .. } => shape * scale,
+            Distribution::Weibull { shape, scale, .. } => scale * gamma(1. + 1. / shape),
+            Distribution::Erlang { mean, .. } => *mean,
+            Distribution::Beta { alpha, beta, min, max, .. } | Distribution::Pert { alpha, beta, min, max, .. } => {
+                min + (max - min) * alpha / (alpha + beta)
+            },
+            Distribution::Empirical { cdf_x, cdf_p, .. } => {
+                (1..cdf_x.len()).map(|j| (cdf_p[j] - cdf_p[j - 1]) * (cdf_x[j - 1] + cdf_x[j]) / 2.).sum()
+            },
+            Distribution::Discrete { values, cumulative, .. } => {
+                discrete_probabilities(cumulative).zip(values).map(|(p, x)| p * x).sum()
+            },
+            Distribution::Mixture { components, cumulative, .. } => {
+                discrete_probabilities(cumulative).zip(components).map(|(p, c)| p * c.mean()).sum()
+            },
+            Distribution::Shifted { offset, inner } => inner.mean() + offset,
+            Distribution::Scaled { factor, inner } => inner.mean() * factor,
+            Distribution::Clamped { .. } => quantile_expectation(self, |x| x),
+            Distribution::Scheduled { distributions, .. } => distributions[0].mean(),
+            Distribution::NonHomogeneousPoisson { rates, .. } => 1. / rates[0],
+            Distribution::Timed { inner, .. } => inner.mean(),
+        }
+    }
+
+    /// The variance of the distribution.
+    pub fn variance(&self) -> f64 {
+        match self {
+            Distribution::Uniform { min, max, .. } => (max - min).powi(2) / 12.,
+            Distribution::Triangular { min: a, max: b, mode: c, .. } => (a * a + b * b + c * c - a * b - a * c - b * c) / 18.,
+            Distribution::Constant(_) => 0.,
+            Distribution::Normal(dist, _) => dist.std_dev().powi(2),
+            Distribution::TruncNormal { normal_dist, min, max, .. } => {
+                let (mu, sigma) = (normal_dist.mean(), normal_dist.std_dev());
+                let (a, b) = ((min - mu) / sigma, (max - mu) / sigma);
+                let z = std_normal_mass(a, b);
+                let (pdf_a, pdf_b) = (std_normal_pdf(a), std_normal_pdf(b));
+                // a * pdf(a) tends to 0 for unbounded sides, but evaluates to NaN if a is infinite
+                let a_pdf_a = if pdf_a == 0. { 0. } else { a * pdf_a };
+                let b_pdf_b = if pdf_b == 0. { 0. } else { b * pdf_b };
+                sigma * sigma * (1. + (a_pdf_a - b_pdf_b) / z - ((pdf_a - pdf_b) / z).powi(2))
+            },
+            Distribution::Exponential { mean, .. } => mean * mean,
+            Distribution::LogNormal { mu, sigma, .. } => ((sigma * sigma).exp() - 1.) * (2. * mu + sigma * sigma).exp(),
+            Distribution::Gamma { shape, scale, .. } => shape * scale * scale,
+            Distribution::Weibull { shape, scale, .. } => scale * scale * (gamma(1. + 2. / shape) - gamma(1. + 1. / shape).powi(2)),
+            Distribution::Erlang { k, mean, .. } => mean * mean / *k as f64,
+            Distribution::Beta { alpha, beta, min, max, .. } | Distribution::Pert { alpha, beta, min, max, .. } => {
+                (max - min).powi(2) * alpha * beta / ((alpha + beta).powi(2) * (alpha + beta + 1.))
+            },
+            Distribution::Empirical { cdf_x, cdf_p, .. } => {
+                // Each segment is uniform on [x_{j-1}, x_j]
+                let second_moment: f64 = (1..cdf_x.len()).map(|j| {
+                    let (a, b) = (cdf_x[j - 1], cdf_x[j]);
+                    (cdf_p[j] - cdf_p[j - 1]) * (a * a + a * b + b * b) / 3.
+                }).sum();
+                (second_moment - self.mean().powi(2)).max(0.)
+            },
+            Distribution::Discrete { values, cumulative, .. } => {
+                let mean = self.mean();
+                discrete_probabilities(cumulative).zip(values).map(|(p, x)| p * (x - mean).powi(2)).sum()
+            },
+            Distribution::Mixture { components, cumulative, .. } => {
+                // Law of total variance
+                let mean = self.mean();
+                discrete_probabilities(cumulative).zip(components).map(|(p, c)| p * (c.variance() + (c.mean() - mean).powi(2))).sum()
+            },
+            Distribution::Shifted { inner, .. } => inner.variance(),
+            Distribution::Scaled { factor, inner } => inner.variance() * factor * factor,
+            Distribution::Clamped { .. } => {
+                let mean = self.mean();
+                quantile_expectation(self, |x| (x - mean).powi(2))
+            },
+            Distribution::Scheduled { distributions, .. } => distributions[0].variance(),
+            Distribution::NonHomogeneousPoisson { rates, .. } => 1. / (rates[0] * rates[0]),
+            Distribution::Timed { inner, .. } => inner.variance(),
+        }
+    }
+
+    /// The cumulative distribution function, P(X <= x).
+    pub fn cdf(&self, x: f64) -> f64 {
+        match self {
+            Distribution::Uniform { min, max, .. } => ((x - min) / (max - min)).clamp(0., 1.),
+            Distribution::Triangular { min, max, mode, .. } => {
+                if x <= *min {
+                    0.
+                } else if x >= *max {
+                    1.
+                } else if x <= *mode {
+                    (x - min).powi(2) / ((max - min) * (mode - min))
+                } else {
+                    1. - (max - x).powi(2) / ((max - min) * (max - mode))
+                }
+            },
+            Distribution::Constant(value) => if x < *value { 0. } else { 1. },
+            Distribution::Normal(dist, _) => std_normal_cdf((x - dist.mean()) / dist.std_dev()),
+            Distribution::TruncNormal { normal_dist, min, max, .. } => {
+                if x <= *min {
+                    0.
+                } else if x >= *max {
+                    1.
+                } else {
+                    let (mu, sigma) = (normal_dist.mean(), normal_dist.std_dev());
+                    let a = (min - mu) / sigma;
+                    (std_normal_mass(a, (x - mu) / sigma) / std_normal_mass(a, (max - mu) / sigma)).clamp(0., 1.)
+                }
+            },
+            Distribution::Exponential { mean, .. } => if x <= 0. { 0. } else { -(-x / mean).exp_m1() },
+            Distribution::LogNormal { mu, sigma, .. } => if x <= 0. { 0. } else { std_normal_cdf((x.ln() - mu) / sigma) },
+            Distribution::Gamma { shape, scale, .. } => if x <= 0. { 0. } else { gamma_lr(*shape, x / scale) },
+            Distribution::Weibull { shape, scale, .. } => if x <= 0. { 0. } else { -(-(x / scale).powf(*shape)).exp_m1() },
+            Distribution::Erlang { k, mean, .. } => if x <= 0. { 0. } else { gamma_lr(*k as f64, x * *k as f64 / mean) },
+            Distribution::Beta { alpha, beta, min, max, .. } | Distribution::Pert { alpha, beta, min, max, .. } => {
+                if x <= *min {
+                    0.
+                } else if x >= *max {
+                    1.
+                } else {
+                    beta_reg(*alpha, *beta, (x - min) / (max - min))
+                }
+            },
+            Distribution::Empirical { cdf_x, cdf_p, .. } => {
+                let j = cdf_x.partition_point(|&xj| xj <= x);
+                if j == 0 {
+                    0.
+                } else if j == cdf_x.len() {
+                    1.
+                } else {
+                    cdf_p[j - 1] + (x - cdf_x[j - 1]) / (cdf_x[j] - cdf_x[j - 1]) * (cdf_p[j] - cdf_p[j - 1])
+                }
+            },
+            Distribution::Discrete { values, cumulative, .. } => {
+                match values.partition_point(|&v| v <= x) {
+                    0 => 0.,
+                    i => cumulative[i - 1],
+                }
+            },
+            Distribution::Mixture { components, cumulative, .. } => {
+                discrete_probabilities(cumulative).zip(components).map(|(p, c)| p * c.cdf(x)).sum()
+            },
+            Distribution::Shifted { offset, inner } => inner.cdf(x - offset),
+            Distribution::Scaled { factor, inner } => inner.cdf(x / factor),
+            Distribution::Clamped { min, max, inner } => {
+                if x < *min {
+                    0.
+                } else if x >= *max {
+                    1.
+                } else {
+                    inner.cdf(x)
+                }
+            },
+            Distribution::Scheduled { distributions, .. } => distributions[0].cdf(x),
+            Distribution::NonHomogeneousPoisson { rates, .. } => if x <= 0. { 0. } else { -(-x * rates[0]).exp_m1() },
+            Distribution::Timed { inner, .. } => inner.cdf(x),
+        }
+    }
+
+    /// The quantile (inverse CDF) function, returning the smallest `x` such that P(X <= x) >= p, for p in [0, 1].
+    pub fn quantile(&self, p: f64) -> f64 {
+        let p = p.clamp(0., 1.);
+        match self {
+            Distribution::Uniform { min, max, .. } => min + p * (max - min),
+            Distribution::Triangular { min, max, mode, .. } => {
+                let split = (mode - min) / (max - min);
+                if p <= split {
+                    min + (p * (max - min) * (mode - min)).sqrt()
+                } else {
+                    max - ((1. - p) * (max - min) * (max - mode)).sqrt()
+                }
+            },
+            Distribution::Constant(value) => *value,
+            Distribution::Normal(dist, _) => dist.mean() + dist.std_dev() * std_normal_quantile(p),
+            Distribution::TruncNormal { normal_dist, min, max, .. } => {
+                let (mu, sigma) = (normal_dist.mean(), normal_dist.std_dev());
+                let (a, b) = ((min - mu) / sigma, (max - mu) / sigma);
+                // Work in whichever tail keeps the probabilities away from 1, to avoid cancellation
+                let z = if a > 0. {
+                    -std_normal_quantile(std_normal_cdf(-a) - p * (std_normal_cdf(-a) - std_normal_cdf(-b)))
+                } else {
+                    std_normal_quantile(std_normal_cdf(a) + p * (std_normal_cdf(b) - std_normal_cdf(a)))
+                };
+                (mu + sigma * z).clamp(*min, *max)
+            },
+            Distribution::Exponential { mean, .. } => -mean * (-p).ln_1p(),
+            Distribution::LogNormal { mu, sigma, .. } => (mu + sigma * std_normal_quantile(p)).exp(),
+            Distribution::Gamma { .. } | Distribution::Erlang { .. } => {
+                invert_cdf(|x| self.cdf(x), p, 0., self.mean() + self.variance().sqrt())
+            },
+            Distribution::Weibull { shape, scale, .. } => scale * (-(-p).ln_1p()).powf(1. / shape),
+            Distribution::Beta { alpha, beta, min, max, .. } | Distribution::Pert { alpha, beta, min, max, .. } => {
+                min + (max - min) * inv_beta_reg(*alpha, *beta, p)
+            },
+            Distribution::Empirical { cdf_x, cdf_p, .. } => {
+                let j = cdf_p.partition_point(|&cp| cp < p);
+                if j == 0 {
+                    cdf_x[0]
+                } else {
+                    let frac = (p - cdf_p[j - 1]) / (cdf_p[j] - cdf_p[j - 1]);
+                    cdf_x[j - 1] + frac * (cdf_x[j] - cdf_x[j - 1])
+                }
+            },
+            Distribution::Discrete { values, cumulative, .. } => {
+                let i = cumulative.partition_point(|&c| c < p);
+                values[i.min(values.len() - 1)]
+            },
+            Distribution::Mixture { components, .. } => {
+                // The mixture quantile lies between the smallest and largest component quantiles
+                let (lo, hi) = components.iter().map(|c| c.quantile(p))
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), q| (lo.min(q), hi.max(q)));
+                if lo == hi || !lo.is_finite() || !hi.is_finite() {
+                    hi
+                } else {
+                    bisect_cdf(|x| self.cdf(x), p, lo, hi)
+                }
+            },
+            Distribution::Shifted { offset, inner } => inner.quantile(p) + offset,
+            Distribution::Scaled { factor, inner } => inner.quantile(p) * factor,
+            Distribution::Clamped { min, max, inner } => inner.quantile(p).clamp(*min, *max),
+            Distribution::Scheduled { distributions, .. } => distributions[0].quantile(p),
+            Distribution::NonHomogeneousPoisson { rates, .. } => -(-p).ln_1p() / rates[0],
+            Distribution::Timed { inner, .. } => inner.quantile(p),
+        }
+    }
+}
+
+fn std_normal_pdf(z: f64) -> f64 {
+    (-z * z / 2.).exp() / (2. * std::f64::consts::PI).sqrt()
+}
+
+fn std_normal_cdf(z: f64) -> f64 {
+    erfc(-z / std::f64::consts::SQRT_2) / 2.
+}
+
+/// Probability that a standard normal lies in [a, b], computed in whichever tail avoids cancellation.
+pub(crate) fn std_normal_mass(a: f64, b: f64) -> f64 {
+    if a > 0. {
+        std_normal_cdf(-a) - std_normal_cdf(-b)
+    } else {
+        std_normal_cdf(b) - std_normal_cdf(a)
+    }
+}
+
+/// Approximates E[f(X)] as the integral of f(quantile(p)) over p in (0, 1), using the midpoint rule.
+fn quantile_expectation(distr: &Distribution, f: impl Fn(f64) -> f64) -> f64 {
+    const N: usize = 4096;
+    (0..N).map(|i| f(distr.quantile((i as f64 + 0.5) / N as f64))).sum::<f64>() / N as f64
+}
+
+/// Probability of each discrete value, from the cumulative weights.
+fn discrete_probabilities(cumulative: &[f64]) -> impl Iterator<Item = f64> + '_ {
+    cumulative.iter().scan(0., |prev, &c| {
+        let p = c - *prev;
+        *prev = c;
+        Some(p)
+    })
+}
+
+/// Solves `cdf(x) = p` by bisection, for a non-decreasing `cdf` that is 0 at `lower`. `upper_guess` is doubled until it
+/// brackets the solution.
+fn invert_cdf(cdf: impl Fn(f64) -> f64, p: f64, lower: f64, upper_guess: f64) -> f64 {
+    if p <= 0. {
+        return lower;
+    }
+    let (mut lo, mut hi) = (lower, upper_guess.max(lower + 1.));
+    while cdf(hi) < p {
+        lo = hi;
+        hi = lower + 2. * (hi - lower);
+        if !hi.is_finite() {
+            return f64::INFINITY;
+        }
+    }
+    bisect_cdf(cdf, p, lo, hi)
+}
+
+/// Finds the smallest `x` in [lo, hi] with `cdf(x) >= p` by bisection, where `cdf(hi) >= p`.
+fn bisect_cdf(cdf: impl Fn(f64) -> f64, p: f64, mut lo: f64, mut hi: f64) -> f64 {
+    for _ in 0..200 {
+        let mid = (lo + hi) / 2.;
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if cdf(mid) >= p { hi = mid } else { lo = mid }
+    }
+    hi
+}
+
+/// Builds the points of a piecewise-linear CDF from empirical data. For raw samples, the CDF passes linearly
+/// through the sorted observations from 0 at the minimum to 1 at the maximum.
+fn empirical_cdf(data: EmpiricalData) -> Result<(Vec<f64>, Vec<f64>), DistributionParametersError> {
+    match data {
+        EmpiricalData::Samples(values) => empirical_cdf_from_samples(values),
+        EmpiricalData::SamplesCsv { path } => {
+            let values = read_csv_columns(&path, 1)?.remove(0);
+            empirical_cdf_from_samples(values)
+        },
+        EmpiricalData::Histogram { bin_edges, counts } => empirical_cdf_from_histogram(bin_edges, counts),
+        EmpiricalData::HistogramCsv { path } => {
+            let mut columns = read_csv_columns(&path, 3)?.into_iter();
+            let (lowers, uppers, counts) = (columns.next().unwrap(), columns.next().unwrap(), columns.next().unwrap());
+            if lowers.is_empty() {
+                return Err(DistributionParametersError {
+                    msg: format!("Histogram file {} contains no bins", path)
+                })
+            }
+            if let Some(i) = (1..lowers.len()).find(|&i| lowers[i] != uppers[i - 1]) {
+                return Err(DistributionParametersError {
+                    msg: format!("Histogram bins in {} are not contiguous: bin {} ends at {} but bin {} starts at {}", path, i, uppers[i - 1], i + 1, lowers[i])
+                })
             }
+            let mut bin_edges = lowers;
+            bin_edges.push(*uppers.last().unwrap());
+            empirical_cdf_from_histogram(bin_edges, counts)
+        },
+    }
+}
+
+fn empirical_cdf_from_samples(mut values: Vec<f64>) -> Result<(Vec<f64>, Vec<f64>), DistributionParametersError> {
+    if values.len() < 2 {
+        return Err(DistributionParametersError {
+            msg: format!("Empirical requires at least 2 samples, got {}", values.len())
+        })
+    }
+    if values.iter().any(|x| !x.is_finite()) {
+        return Err(DistributionParametersError {
+            msg: "Empirical samples must all be finite".to_string()
+        })
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let n = values.len();
+    let cdf_p = (0..n).map(|i| i as f64 / (n - 1) as f64).collect();
+    Ok((values, cdf_p))
+}
+
+fn empirical_cdf_from_histogram(bin_edges: Vec<f64>, counts: Vec<f64>) -> Result<(Vec<f64>, Vec<f64>), DistributionParametersError> {
+    if counts.is_empty() || bin_edges.len() != counts.len() + 1 {
+        return Err(DistributionParametersError {
+            msg: format!("Histogram requires one more bin edge than counts, and at least one bin, got {} edges and {} counts", bin_edges.len(), counts.len())
+        })
+    }
+    if bin_edges.iter().any(|x| !x.is_finite()) || bin_edges.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(DistributionParametersError {
+            msg: "Histogram bin edges must be finite and strictly increasing".to_string()
+        })
+    }
+    let mut cdf_p = vec![0.];
+    cdf_p.extend(normalised_cumulative(&counts)?);
+    Ok((bin_edges, cdf_p))
+}
+
+/// Cumulative sum of weights, normalised so that the last element is 1.
+fn normalised_cumulative(weights: &[f64]) -> Result<Vec<f64>, DistributionParametersError> {
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.) {
+        return Err(DistributionParametersError {
+            msg: "Weights and counts must be finite and non-negative".to_string()
+        })
+    }
+    let total: f64 = weights.iter().sum();
+    if total <= 0. {
+        return Err(DistributionParametersError {
+            msg: "Weights and counts must have a positive total".to_string()
+        })
+    }
+    let mut acc = 0.;
+    let mut cumulative: Vec<f64> = weights.iter().map(|w| { acc += w; acc / total }).collect();
+    *cumulative.last_mut().unwrap() = 1.;
+    Ok(cumulative)
+}
+
+/// Reads the first `n_cols` columns of a CSV file with a header row as numbers.
+fn read_csv_columns(path: &str, n_cols: usize) -> Result<Vec<Vec<f64>>, DistributionParametersError> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_path(path).map_err(|e| DistributionParametersError {
+        msg: format!("Failed to open {}: {}", path, e)
+    })?;
+    let mut columns = vec![Vec::new(); n_cols];
+    for (i, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| DistributionParametersError {
+            msg: format!("Failed to read {}: {}", path, e)
+        })?;
+        for (j, column) in columns.iter_mut().enumerate() {
+            let field = record.get(j).ok_or_else(|| DistributionParametersError {
+                msg: format!("{} row {}: expected {} columns, found {}", path, i + 2, n_cols, record.len())
+            })?;
+            let value = field.parse::<f64>().map_err(|_| DistributionParametersError {
+                msg: format!("{} row {} column {}: cannot parse '{}' as a number", path, i + 2, j + 1, field)
+            })?;
+            column.push(value);
         }
     }
+    Ok(columns)
+}
+
+/// Parses a date-time in the form `2025-01-01 06:30:00.5`, as written in process logs. A trailing ` UTC` is allowed.
+pub fn parse_date_time(time: &str) -> Result<MonotonicTime, Box<dyn Error>> {
+    let invalid = || format!("Invalid date-time '{}'", time);
+    let (date, time_of_day) = time.trim_end_matches(" UTC").split_once(' ').ok_or_else(invalid)?;
+    let mut date = date.split('-');
+    let (year, month, day) = match (date.next(), date.next(), date.next(), date.next()) {
+        (Some(year), Some(month), Some(day), None) => (year.parse::<i32>()?, month.parse::<u8>()?, day.parse::<u8>()?),
+        _ => return Err(invalid().into()),
+    };
+    let (hms, fraction) = time_of_day.split_once('.').unwrap_or((time_of_day, ""));
+    let mut hms = hms.split(':').map(|x| x.parse::<u8>());
+    let (hour, min, sec) = match (hms.next(), hms.next(), hms.next(), hms.next()) {
+        (Some(Ok(hour)), Some(Ok(min)), Some(Ok(sec)), None) => (hour, min, sec),
+        _ => return Err(invalid().into()),
+    };
+    let nano = if fraction.is_empty() {
+        0
+    } else if fraction.len() <= 9 && fraction.chars().all(|c| c.is_ascii_digit()) {
+        format!("{:0<9}", fraction).parse::<u32>()?
+    } else {
+        return Err(invalid().into());
+    };
+    MonotonicTime::try_from_date_time(year, month, day, hour, min, sec, nano).map_err(|_| invalid().into())
 }
 
 impl Default for Distribution {
@@ -184,3 +1252,314 @@ impl Default for Distribution {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factory() -> DistributionFactory {
+        DistributionFactory::new(1)
+    }
+
+    #[test]
+    fn test_new_distributions_sample_within_support() {
+        let mut df = factory();
+        let configs = vec![
+            DistributionConfig::LogNormal { mean: 10., std: 2. },
+            DistributionConfig::Gamma { shape: 2., scale: 3. },
+            DistributionConfig::Weibull { shape: 1.5, scale: 10. },
+            DistributionConfig::Erlang { k: 3, mean: 6. },
+            DistributionConfig::Beta { alpha: 2., beta: 5., min: 10., max: 20. },
+            DistributionConfig::Pert { min: 1., max: 9., mode: 3. },
+        ];
+        for config in configs {
+            let mut distr = df.create(config.clone()).unwrap();
+            for _ in 0..1000 {
+                let x = distr.sample();
+                assert!(x.is_finite() && x >= 0., "{:?} sampled {}", config, x);
+                if let DistributionConfig::Beta { min, max, .. } | DistributionConfig::Pert { min, max, .. } = config {
+                    assert!(x >= min && x <= max);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_new_distributions_reject_invalid_parameters() {
+        let mut df = factory();
+        assert!(df.create(DistributionConfig::LogNormal { mean: -1., std: 2. }).is_err());
+        assert!(df.create(DistributionConfig::Gamma { shape: 0., scale: 3. }).is_err());
+        assert!(df.create(DistributionConfig::Weibull { shape: 1., scale: -1. }).is_err());
+        assert!(df.create(DistributionConfig::Erlang { k: 0, mean: 6. }).is_err());
+        assert!(df.create(DistributionConfig::Beta { alpha: 2., beta: 5., min: 20., max: 10. }).is_err());
+        assert!(df.create(DistributionConfig::Pert { min: 1., max: 9., mode: 10. }).is_err());
+    }
+
+    fn assert_close(actual: f64, expected: f64, tol: f64) {
+        assert!((actual - expected).abs() <= tol, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn test_moments_and_quantiles() {
+        let mut df = factory();
+        let configs = vec![
+            (DistributionConfig::Uniform { min: 2., max: 6. }, 4., 16. / 12.),
+            (DistributionConfig::Triangular { min: 0., max: 3., mode: 0. }, 1., 0.5),
+            (DistributionConfig::Exponential { mean: 5. }, 5., 25.),
+            (DistributionConfig::LogNormal { mean: 10., std: 2. }, 10., 4.),
+            (DistributionConfig::Gamma { shape: 2., scale: 3. }, 6., 18.),
+            (DistributionConfig::Weibull { shape: 1., scale: 4. }, 4., 16.),
+            (DistributionConfig::Erlang { k: 4, mean: 8. }, 8., 16.),
+            (DistributionConfig::Beta { alpha: 2., beta: 2., min: 0., max: 10. }, 5., 5.),
+            (DistributionConfig::Pert { min: 0., max: 6., mode: 3. }, 3., 9. / 7.),
+            (DistributionConfig::Discrete { data: DiscreteData::Points { values: vec![3., 1.], weights: vec![1., 1.] } }, 2., 1.),
+            (DistributionConfig::Empirical { data: EmpiricalData::Samples(vec![0., 2.]) }, 1., 1. / 3.),
+        ];
+        for (config, mean, variance) in configs {
+            let distr = df.create(config.clone()).unwrap();
+            assert_close(distr.mean(), mean, 1e-9);
+            assert_close(distr.variance(), variance, 1e-9);
+            for p in [0.05, 0.3, 0.5, 0.9] {
+                assert_close(distr.cdf(distr.quantile(p)), p, if let DistributionConfig::Discrete { .. } = config { 0.5 } else { 1e-9 });
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncated_normal_moments() {
+        let mut df = factory();
+        // Half-normal: mean sigma * sqrt(2 / pi), variance sigma^2 (1 - 2 / pi)
+        let distr = df.create(DistributionConfig::TruncNormal { mean: 0., std: 2., min: Some(0.), max: None }).unwrap();
+        let pi = std::f64::consts::PI;
+        assert_close(distr.mean(), 2. * (2. / pi).sqrt(), 1e-9);
+        assert_close(distr.variance(), 4. * (1. - 2. / pi), 1e-9);
+        assert_close(distr.quantile(0.5), 2. * std_normal_quantile(0.75), 1e-9);
+
+        // Window far in the tail is sampled without spinning
+        let mut distr = df.create(DistributionConfig::TruncNormal { mean: 30., std: 1., min: Some(35.), max: None }).unwrap();
+        for _ in 0..1000 {
+            assert!(distr.sample() >= 35.);
+        }
+        assert!(distr.mean() > 35. && distr.mean() < 35.5);
+        let sample_mean = (0..10000).map(|_| distr.sample()).sum::<f64>() / 10000.;
+        assert_close(sample_mean, distr.mean(), 0.01);
+
+        // Windows with negligible mass are rejected, rather than sampled with a clamped inverse CDF
+        assert!(df.create(DistributionConfig::TruncNormal { mean: 30., std: 1., min: Some(50.), max: None }).is_err());
+        assert!(df.create(DistributionConfig::TruncNormal { mean: 30., std: 1., min: Some(36.), max: Some(37.) }).is_err());
+        assert!(df.create(DistributionConfig::TruncNormal { mean: 30., std: 1., min: Some(30.), max: Some(30. + 1e-10) }).is_err());
+
+        // Symmetric window leaves the mean unchanged
+        let distr = df.create(DistributionConfig::TruncNormal { mean: 30., std: 10., min: Some(20.), max: Some(40.) }).unwrap();
+        assert_close(distr.mean(), 30., 1e-9);
+        assert!(distr.variance() < 100. / 3.);
+    }
+
+    #[test]
+    fn test_combinators() {
+        let mut df = factory();
+        let quick = DistributionConfig::Uniform { min: 1., max: 3. };
+        let replace = DistributionConfig::Uniform { min: 50., max: 70. };
+        let mut distr = df.create(DistributionConfig::Mixture { components: vec![(3., quick.clone()), (1., replace.clone())] }).unwrap();
+        assert_close(distr.mean(), 0.75 * 2. + 0.25 * 60., 1e-9);
+        assert_close(distr.quantile(0.5), 2. + 1. / 3., 1e-6);
+        let n = 4000;
+        let quick_count = (0..n).map(|_| distr.sample()).inspect(|x| assert!((1. ..=3.).contains(x) || (50. ..=70.).contains(x))).filter(|x| *x < 10.).count();
+        assert!((quick_count as f64 / n as f64 - 0.75).abs() < 0.05);
+
+        let mut distr = df.create(DistributionConfig::Shifted {
+            offset: 10.,
+            inner: Box::new(DistributionConfig::Scaled { factor: 2., inner: Box::new(quick.clone()) }),
+        }).unwrap();
+        assert_close(distr.mean(), 14., 1e-9);
+        assert_close(distr.variance(), 16. / 12., 1e-9);
+        assert!((12. ..=16.).contains(&distr.sample()));
+
+        let mut distr = df.create(DistributionConfig::Clamped { min: Some(1.5), max: None, inner: Box::new(quick) }).unwrap();
+        assert!((0..100).all(|_| distr.sample() >= 1.5));
+        assert_close(distr.cdf(1.4), 0., 1e-12);
+        assert_close(distr.cdf(1.5), 0.25, 1e-12);
+        // E[max(X, 1.5)] for X ~ U(1, 3) = 0.25 * 1.5 + 0.75 * 2.25
+        assert_close(distr.mean(), 2.0625, 1e-6);
+
+        assert!(df.create(DistributionConfig::Scaled { factor: -1., inner: Box::new(replace.clone()) }).is_err());
+        assert!(df.create(DistributionConfig::Mixture { components: vec![] }).is_err());
+        assert!(df.create(DistributionConfig::Clamped { min: Some(2.), max: Some(1.), inner: Box::new(replace) }).is_err());
+    }
+
+    #[test]
+    fn test_scheduled_by_hour_of_day() {
+        let mut df = factory();
+        let mut distr = df.create(DistributionConfig::Scheduled {
+            index: ScheduleIndex::HourOfDay,
+            segments: vec![(6., DistributionConfig::Constant(10.)), (18., DistributionConfig::Constant(20.))],
+        }).unwrap();
+        let day = MonotonicTime::try_from_date_time(2025, 1, 1, 0, 0, 0, 0).unwrap();
+        assert_eq!(distr.sample_at(day + Duration::from_secs(3 * 3600)), 20.);
+        assert_eq!(distr.sample_at(day + Duration::from_secs(6 * 3600)), 10.);
+        assert_eq!(distr.sample_at(day + Duration::from_secs(17 * 3600 + 3599)), 10.);
+        assert_eq!(distr.sample_at(day + Duration::from_secs(23 * 3600)), 20.);
+
+        // 2025-01-01 was a Wednesday
+        let mut distr = df.create(DistributionConfig::Scheduled {
+            index: ScheduleIndex::DayOfWeek,
+            segments: vec![(0., DistributionConfig::Constant(1.)), (5., DistributionConfig::Constant(2.))],
+        }).unwrap();
+        assert_eq!(distr.sample_at(day), 1.);
+        assert_eq!(distr.sample_at(day + Duration::from_secs(3 * 86400)), 2.);
+
+        assert!(df.create(DistributionConfig::Scheduled {
+            index: ScheduleIndex::HourOfDay,
+            segments: vec![(6., DistributionConfig::Constant(10.)), (25., DistributionConfig::Constant(20.))],
+        }).is_err());
+    }
+
+    #[test]
+    fn test_non_homogeneous_poisson_arrivals() {
+        let mut df = factory();
+        // 60 arrivals per hour from 08:00 to 12:00, none otherwise
+        let mut distr = df.create(DistributionConfig::NonHomogeneousPoisson {
+            index: ScheduleIndex::HourOfDay,
+            rates: vec![(8., 1. / 60.), (12., 0.)],
+        }).unwrap();
+        let start = MonotonicTime::try_from_date_time(2025, 1, 1, 0, 0, 0, 0).unwrap();
+        let mut time = start;
+        let mut arrivals = 0;
+        while time < start + Duration::from_secs(86400) {
+            time += Duration::from_secs_f64(distr.sample_at(time));
+            let hour = time.duration_since(start).as_secs() / 3600;
+            if hour < 24 {
+                assert!((8..12).contains(&hour));
+                arrivals += 1;
+            }
+        }
+        assert!((200..280).contains(&arrivals), "{} arrivals", arrivals);
+
+        assert!(df.create(DistributionConfig::NonHomogeneousPoisson {
+            index: ScheduleIndex::Piecewise { period: None },
+            rates: vec![(0., 1.), (100., 0.)],
+        }).is_err());
+    }
+
+    #[test]
+    fn test_time_units() {
+        let mut df = factory();
+        let mut minutes = df.create("Const(1.5, unit=min)".parse().unwrap()).unwrap();
+        assert_eq!(minutes.sample(), 1.5);
+        assert_eq!(minutes.mean(), 1.5);
+        // The distribution's own unit takes precedence over the component's
+        assert_eq!(minutes.sample_duration(TimeUnit::Hours), Duration::from_secs(90));
+        let mut plain = Distribution::Constant(1.5);
+        assert_eq!(plain.sample_duration(TimeUnit::Hours), Duration::from_secs(5400));
+        assert_eq!(plain.sample_duration(TimeUnit::default()), Duration::from_millis(1500));
+
+        // Rates become arrivals per hour, and samples are in hours
+        let mut arrivals = df.create("NHPP(Piecewise, 0: 2, unit=h)".parse().unwrap()).unwrap();
+        let start = MonotonicTime::EPOCH;
+        let n = 2000;
+        let total: Duration = (0..n).map(|_| arrivals.sample_duration_at(start, TimeUnit::Seconds)).sum();
+        assert_close(total.as_secs_f64() / n as f64, 1800., 120.);
+
+        assert!(df.create("Mixture(1: Exp(5, unit=min))".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_named_streams_independent_of_creation_order() {
+        let config = DistributionConfig::Exponential { mean: 10. };
+        let mut df1 = factory();
+        let mut a1 = df1.create_named("A/process_time", config.clone()).unwrap();
+        let mut b1 = df1.create_named("B/process_time", config.clone()).unwrap();
+
+        let mut df2 = factory();
+        df2.create(config.clone()).unwrap();
+        let mut b2 = df2.create_named("B/process_time", config.clone()).unwrap();
+        let mut a2 = df2.create_named("A/process_time", config.clone()).unwrap();
+
+        for _ in 0..10 {
+            let (x_a1, x_b1) = (a1.sample(), b1.sample());
+            assert_eq!(x_a1, a2.sample());
+            assert_eq!(x_b1, b2.sample());
+            assert_ne!(x_a1, x_b1);
+        }
+    }
+
+    #[test]
+    fn test_unnamed_streams_differ() {
+        let mut df = factory();
+        let mut a = df.create(DistributionConfig::Exponential { mean: 10. }).unwrap();
+        let mut b = df.create(DistributionConfig::Exponential { mean: 10. }).unwrap();
+        assert_ne!(a.sample(), b.sample());
+    }
+
+    #[test]
+    fn test_antithetic_streams_mirror_uniforms() {
+        let config = DistributionConfig::Uniform { min: 0., max: 1. };
+        let df = factory();
+        let mut u = df.replay().create(config.clone()).unwrap();
+        let mut v = df.antithetic_counterpart().create(config).unwrap();
+        for _ in 0..100 {
+            assert!((u.sample() + v.sample() - 1.).abs() < 1e-12);
+        }
+
+        // Pairs of exponential samples move in opposite directions
+        let config = DistributionConfig::Exponential { mean: 10. };
+        let mut x = DistributionFactory::new(7).create_named("X", config.clone()).unwrap();
+        let mut y = DistributionFactory::new_antithetic(7).create_named("X", config).unwrap();
+        let (mut prev_x, mut prev_y) = (x.sample(), y.sample());
+        for _ in 0..100 {
+            let (next_x, next_y) = (x.sample(), y.sample());
+            assert!((next_x - prev_x) * (next_y - prev_y) <= 0.);
+            (prev_x, prev_y) = (next_x, next_y);
+        }
+    }
+
+    #[test]
+    fn test_empirical_from_samples_and_histogram() {
+        let mut df = factory();
+        let mut distr = df.create(DistributionConfig::Empirical { data: EmpiricalData::Samples(vec![5., 1., 3.]) }).unwrap();
+        for _ in 0..1000 {
+            let x = distr.sample();
+            assert!((1. ..=5.).contains(&x));
+        }
+
+        // All of the mass lies in the second bin
+        let mut distr = df.create(DistributionConfig::Empirical {
+            data: EmpiricalData::Histogram { bin_edges: vec![0., 10., 20.], counts: vec![0., 4.] }
+        }).unwrap();
+        for _ in 0..1000 {
+            let x = distr.sample();
+            assert!((10. ..=20.).contains(&x));
+        }
+
+        assert!(df.create(DistributionConfig::Empirical { data: EmpiricalData::Samples(vec![1.]) }).is_err());
+        assert!(df.create(DistributionConfig::Empirical {
+            data: EmpiricalData::Histogram { bin_edges: vec![0., 10.], counts: vec![1., 2.] }
+        }).is_err());
+    }
+
+    #[test]
+    fn test_parse_date_time() {
+        let time = MonotonicTime::try_from_date_time(2025, 3, 4, 5, 6, 7, 500_000_000).unwrap();
+        assert_eq!(parse_date_time(&time.to_chrono_date_time(0).unwrap().to_string()).unwrap(), time);
+        assert_eq!(parse_date_time("2025-03-04 05:06:07").unwrap(), MonotonicTime::try_from_date_time(2025, 3, 4, 5, 6, 7, 0).unwrap());
+        assert!(parse_date_time("2025-03-04T05:06:07").is_err());
+        assert!(parse_date_time("2025-03-04 05:06").is_err());
+    }
+
+    #[test]
+    fn test_discrete_from_csv() {
+        let path = std::env::temp_dir().join("quokkasim_test_discrete.csv");
+        std::fs::write(&path, "value,weight\n1,0\n2,3\n3,1\n").unwrap();
+        let mut df = factory();
+        let mut distr = df.create(DistributionConfig::Discrete {
+            data: DiscreteData::Csv { path: path.to_string_lossy().to_string() }
+        }).unwrap();
+        let n = 4000;
+        let twos = (0..n).map(|_| distr.sample()).inspect(|x| assert!(*x == 2. || *x == 3.)).filter(|x| *x == 2.).count();
+        assert!((twos as f64 / n as f64 - 0.75).abs() < 0.05);
+
+        assert!(df.create(DistributionConfig::Discrete {
+            data: DiscreteData::Points { values: vec![1., 2.], weights: vec![1.] }
+        }).is_err());
+    }
+}
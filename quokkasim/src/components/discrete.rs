@@ -2,7 +2,9 @@ use serde::ser::SerializeStruct;
 use serde::Serialize;
 
 use crate::prelude::*;
+use std::cmp::Ordering;
 use std::collections::{VecDeque, HashMap};
+use std::sync::Arc;
 use std::time::Duration;
 use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
@@ -49,6 +51,7 @@ pub struct DiscreteStock<T> where T: Clone + Default + Send + 'static {
     // configuration
     pub low_capacity: u32,
     pub max_capacity: u32,
+    pub queue_discipline: QueueDiscipline<T>,
     
     // Runtime state
     pub resource: ItemDeque<T>,
@@ -69,6 +72,7 @@ impl<T: Clone + Default + Send + 'static> Default for DiscreteStock<T> {
 
             low_capacity: 0,
             max_capacity: 1,
+            queue_discipline: QueueDiscipline::default(),
 
             resource: ItemDeque::default(),
 
@@ -78,6 +82,74 @@ impl<T: Clone + Default + Send + 'static> Default for DiscreteStock<T> {
     }
 }
 
+/// Comparison used to rank items in a priority queue.
+pub type ItemComparator<T> = Arc<dyn Fn(&T, &T) -> Ordering + Send + Sync>;
+
+/// Random stream of a `QueueDiscipline::Random`, built with `QueueDiscipline::random`.
+#[derive(Clone)]
+pub struct RandomSelection {
+    /// Samples in [0, 1)
+    distr: Distribution,
+}
+
+/// Order in which a `DiscreteStock` gives up its items.
+#[derive(Clone, Default)]
+pub enum QueueDiscipline<T> {
+    /// First in, first out.
+    #[default]
+    Fifo,
+    /// Last in, first out.
+    Lifo,
+    /// Lowest-ranked item first, by the given comparison. Ties go to the earliest arrival.
+    Priority(ItemComparator<T>),
+    /// An item picked uniformly at random - see `QueueDiscipline::random`.
+    Random(RandomSelection),
+    /// Item with the shortest processing time first, as given by the function. Ties go to the earliest arrival.
+    ShortestProcessingTime(Arc<dyn Fn(&T) -> Duration + Send + Sync>),
+}
+
+impl<T> QueueDiscipline<T> {
+    /// Priority by a key taken from each item, lowest key first. Wrap the key in `std::cmp::Reverse` for highest first.
+    pub fn priority_by_key<K: Ord>(key: impl Fn(&T) -> K + Send + Sync + 'static) -> Self {
+        QueueDiscipline::Priority(Arc::new(move |a, b| key(a).cmp(&key(b))))
+    }
+
+    /// Priority by the items' own ordering, lowest first.
+    pub fn priority_by_ord() -> Self where T: Ord {
+        QueueDiscipline::Priority(Arc::new(|a: &T, b: &T| a.cmp(b)))
+    }
+
+    /// Random selection, with its stream seeded from the factory.
+    pub fn random(df: &mut DistributionFactory) -> Self {
+        QueueDiscipline::Random(RandomSelection { distr: df.create(DistributionConfig::Uniform { min: 0., max: 1. }).unwrap() })
+    }
+
+    /// Shortest processing time first, with each item's processing time given by the function.
+    pub fn shortest_processing_time(processing_time: impl Fn(&T) -> Duration + Send + Sync + 'static) -> Self {
+        QueueDiscipline::ShortestProcessingTime(Arc::new(processing_time))
+    }
+
+    /// Index of the next item to remove from the queue, or `None` if it's empty.
+    pub fn select(&mut self, items: &VecDeque<T>) -> Option<usize> {
+        if items.is_empty() {
+            return None;
+        }
+        match self {
+            QueueDiscipline::Fifo => Some(0),
+            QueueDiscipline::Lifo => Some(items.len() - 1),
+            QueueDiscipline::Priority(compare) => {
+                items.iter().enumerate().min_by(|(_, a), (_, b)| compare(a, b)).map(|(index, _)| index)
+            },
+            QueueDiscipline::Random(selection) => {
+                let index = (selection.distr.sample() * items.len() as f64) as usize;
+                Some(index.min(items.len() - 1))
+            },
+            QueueDiscipline::ShortestProcessingTime(processing_time) => {
+                items.iter().enumerate().min_by_key(|(_, item)| processing_time(item)).map(|(index, _)| index)
+            },
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ItemDeque<T>(VecDeque<T>);
@@ -124,16 +196,38 @@ pub trait HasUniqueKey<S> {
     fn get_key(&self) -> S;
 }
 
+impl HasUniqueKey<String> for String {
+    fn get_key(&self) -> String {
+        self.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ItemMap<S, T: HasUniqueKey<S>>(HashMap<S, T>);
 impl<S, T: HasUniqueKey<S>> Deref for ItemMap<S, T> { type Target = HashMap<S, T>; fn deref(&self) -> &Self::Target { &self.0 } }
 impl<S, T: HasUniqueKey<S>> DerefMut for ItemMap<S, T> { fn deref_mut(&mut self) -> &mut HashMap<S, T> { &mut self.0 } }
 
+impl<S, T: HasUniqueKey<S>> Default for ItemMap<S, T> {
+    fn default() -> Self {
+        ItemMap(HashMap::new())
+    }
+}
+
+impl<T: HasUniqueKey<String>> From<Vec<T>> for ItemMap<String, T> {
+    fn from(vec: Vec<T>) -> Self {
+        let mut map = ItemMap::default();
+        vec.into_iter().for_each(|item| map.add(item));
+        map
+    }
+}
+
 impl<T> ResourceAdd<T> for ItemMap<String, T>
 where
     T: HasUniqueKey<String>,
 {
+    /// Adds the item, unless an item with the same key is already held, which is kept instead.
     fn add(&mut self, item: T) {
-        self.insert(item.get_key(), item);
+        self.entry(item.get_key()).or_insert(item);
     }
 }
 
@@ -146,6 +240,21 @@ where
     }
 }
 
+impl<T> ResourceRemove<ItemRequest<T>, Option<T>> for ItemMap<String, T>
+where
+    T: HasUniqueKey<String>,
+{
+    fn remove(&mut self, request: ItemRequest<T>) -> Option<T> {
+        // Of several candidates, take the lowest key so the choice doesn't depend on the map's iteration order
+        let key = match request {
+            ItemRequest::Any => self.keys().min()?.clone(),
+            ItemRequest::Key(key) => key,
+            ItemRequest::Matching(predicate) => self.iter().filter(|(_, item)| predicate(item)).map(|(key, _)| key).min()?.clone(),
+        };
+        HashMap::<String, T>::remove(self, &key)
+    }
+}
+
 impl<T> ResourceTotal<u32> for ItemMap<String, T>
 where
     T: HasUniqueKey<String>,
@@ -155,6 +264,37 @@ where
     }
 }
 
+/// Test for whether an item satisfies a withdrawal request.
+pub type ItemPredicate<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// Which item to withdraw from a `KeyedDiscreteStock`.
+#[derive(Clone, Default)]
+pub enum ItemRequest<T> {
+    /// Any item.
+    #[default]
+    Any,
+    /// The item with this key, e.g. the truck with id `T07`.
+    Key(String),
+    /// Any item satisfying the predicate, e.g. an order for a given customer.
+    Matching(ItemPredicate<T>),
+}
+
+impl<T> ItemRequest<T> {
+    pub fn matching(predicate: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        ItemRequest::Matching(Arc::new(predicate))
+    }
+}
+
+impl<T> Debug for ItemRequest<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemRequest::Any => write!(f, "Any"),
+            ItemRequest::Key(key) => write!(f, "Key({:?})", key),
+            ItemRequest::Matching(_) => write!(f, "Matching"),
+        }
+    }
+}
+
 impl<T: Clone + Debug + Default + Send> Stock<ItemDeque<T>, T, (), Option<T>> for DiscreteStock<T> {
     type StockState = DiscreteStockState;
     type LogDetailsType = DiscreteStockLogType<T>;
@@ -200,7 +340,8 @@ impl<T: Clone + Debug + Default + Send> Stock<ItemDeque<T>, T, (), Option<T>> fo
     fn remove_impl(&mut self, payload: &mut ((), EventId), cx: &mut Context<Self>) -> impl Future<Output = Option<T>> {
         async move {
             self.prev_state = Some(self.get_state());
-            let removed = self.resource.pop_front();
+            let removed = self.queue_discipline.select(&self.resource)
+                .and_then(|index| VecDeque::remove(&mut self.resource, index));
             payload.1 = self.log(cx.time(), payload.1.clone(), DiscreteStockLogType::<T>::Remove(removed.clone()))
                 .await;
             removed
@@ -289,6 +430,7 @@ pub enum DiscreteStockLogType<T> {
     Add(T),
     Remove(Option<T>),
     StateChange(DiscreteStockState),
+    AddRejected { item: T, reason: &'static str },
 }
 
 
@@ -309,6 +451,7 @@ impl<T: Serialize> Serialize for DiscreteStockLog<T> {
                 let state_str: String = serde_json::to_string(state).unwrap();
                 ("StateChange".into(), Some(state_str), None)
             },
+            DiscreteStockLogType::AddRejected { item, reason } => ("AddRejected".into(), Some(serde_json::to_string(item).unwrap()), Some(*reason)),
         };
         state.serialize_field("log_type", &log_type)?;
         state.serialize_field("item", &item)?;
@@ -317,6 +460,147 @@ impl<T: Serialize> Serialize for DiscreteStockLog<T> {
     }
 }
 
+/// Stock of uniquely keyed items, from which processes withdraw particular items with an `ItemRequest`. Logs with
+/// `DiscreteStockLogger`.
+#[derive(WithMethods)]
+pub struct KeyedDiscreteStock<T> where T: HasUniqueKey<String> + Clone + Send + 'static {
+    // Identification
+    pub element_name: String,
+    pub element_code: String,
+    pub element_type: String,
+
+    // Ports
+    pub log_emitter: Output<DiscreteStockLog<T>>,
+    pub state_emitter: Output<EventId>,
+
+    // configuration
+    pub low_capacity: u32,
+    pub max_capacity: u32,
+
+    // Runtime state
+    pub resource: ItemMap<String, T>,
+
+    // Internals
+    prev_state: Option<DiscreteStockState>,
+    next_event_index: u64,
+}
+impl<T: HasUniqueKey<String> + Clone + Send + 'static> Default for KeyedDiscreteStock<T> {
+    fn default() -> Self {
+        KeyedDiscreteStock {
+            element_name: "KeyedDiscreteStock".into(),
+            element_code: "".into(),
+            element_type: "KeyedDiscreteStock".into(),
+
+            log_emitter: Output::new(),
+            state_emitter: Output::new(),
+
+            low_capacity: 0,
+            max_capacity: 1,
+
+            resource: ItemMap::default(),
+
+            prev_state: None,
+            next_event_index: 0,
+        }
+    }
+}
+
+impl<T: HasUniqueKey<String> + Clone + Debug + Send> Stock<ItemMap<String, T>, T, ItemRequest<T>, Option<T>> for KeyedDiscreteStock<T> {
+    type StockState = DiscreteStockState;
+    type LogDetailsType = DiscreteStockLogType<T>;
+    fn get_state(&mut self) -> Self::StockState {
+        let occupied = self.resource.total();
+        let empty = self.max_capacity.saturating_sub(occupied); // If occupied beyond capacity, just say no empty space
+        if self.resource.total() <= self.low_capacity {
+            DiscreteStockState::Empty { occupied, empty }
+        } else if self.resource.total() >= self.max_capacity {
+            DiscreteStockState::Full { occupied, empty }
+        } else {
+            DiscreteStockState::Normal { occupied, empty }
+        }
+    }
+    fn get_previous_state(&mut self) -> &Option<Self::StockState> {
+        &self.prev_state
+    }
+    fn set_previous_state(&mut self) {
+        self.prev_state = Some(self.get_state());
+    }
+    fn get_resource(&self) -> &ItemMap<String, T> {
+        &self.resource
+    }
+    fn add_impl(&mut self, payload: &mut (T, EventId), cx: &mut Context<Self>) -> impl Future<Output = ()> {
+        async move {
+            if self.resource.contains_key(&payload.0.get_key()) {
+                let details = DiscreteStockLogType::<T>::AddRejected { item: payload.0.clone(), reason: "Duplicate key" };
+                payload.1 = self.log(cx.time(), payload.1.clone(), details).await;
+            } else {
+                self.resource.add(payload.0.clone());
+                payload.1 = self.log(cx.time(), payload.1.clone(), DiscreteStockLogType::<T>::Add(payload.0.clone())).await;
+            }
+        }
+    }
+    fn post_add(&mut self, payload: &mut (T, EventId), cx: &mut Context<Self>) -> impl Future<Output = ()> + {
+        async move {
+            // Notify on every arrival, not just state changes, as it may be the item a downstream process is waiting
+            // for. Send 1ns in future to avoid infinite loops with processes
+            let current_state = self.get_state().clone();
+            let next_time = cx.time() + Duration::from_nanos(1);
+            cx.schedule_event(next_time, Self::emit_change, (current_state.clone(), payload.1.clone())).unwrap();
+            self.prev_state = Some(current_state);
+        }
+    }
+
+    fn remove_impl(&mut self, payload: &mut (ItemRequest<T>, EventId), cx: &mut Context<Self>) -> impl Future<Output = Option<T>> {
+        async move {
+            self.prev_state = Some(self.get_state());
+            let removed = self.resource.remove(payload.0.clone());
+            payload.1 = self.log(cx.time(), payload.1.clone(), DiscreteStockLogType::<T>::Remove(removed.clone()))
+                .await;
+            removed
+        }
+    }
+
+    fn post_remove(&mut self, payload: &mut (ItemRequest<T>, EventId), cx: &mut Context<Self>) -> impl Future<Output = ()> + {
+        async move {
+            let previous_state = self.prev_state.clone();
+            let current_state = self.get_state().clone();
+            if let Some(prev_state) = previous_state && !prev_state.is_same_state(&current_state) {
+                let next_time = cx.time() + Duration::from_nanos(1);
+                cx.schedule_event(next_time, Self::emit_change, (current_state.clone(), payload.1.clone())).unwrap();
+            }
+            self.prev_state = Some(self.get_state());
+        }
+    }
+
+    fn emit_change(&mut self, source_event_id: (Self::StockState, EventId), cx: &mut nexosim::model::Context<Self>) -> impl Future<Output = ()> {
+        async move {
+            let state = self.get_state().clone();
+            let nm = self.log(cx.time(), source_event_id.1, DiscreteStockLogType::<T>::StateChange(state)).await;
+            self.state_emitter.send(nm).await;
+        }
+    }
+
+    fn log(&mut self, now: MonotonicTime, source_event_id: EventId, details: Self::LogDetailsType) -> impl Future<Output = EventId> {
+        async move {
+            let new_event_id = EventId(format!("{}_{:06}", self.element_code, self.next_event_index));
+            let log = DiscreteStockLog {
+                time: now.to_chrono_date_time(0).unwrap().to_string(),
+                event_id: new_event_id.clone(),
+                source_event_id,
+                element_name: self.element_name.clone(),
+                element_type: self.element_type.clone(),
+                details
+            };
+            self.log_emitter.send(log).await;
+            self.next_event_index += 1;
+
+            new_event_id
+        }
+    }
+}
+
+impl<T: HasUniqueKey<String> + Clone + Send + 'static> Model for KeyedDiscreteStock<T> {}
+
 #[derive(WithMethods)]
 pub struct DiscreteProcess<
     ReceiveParameterType: Clone + Debug + Send + 'static,
@@ -332,19 +616,31 @@ pub struct DiscreteProcess<
     // Ports
     pub req_upstream: Requestor<(), DiscreteStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub req_downstream: Requestor<(), DiscreteStockState>,
     pub withdraw_upstream: Requestor<(ReceiveParameterType, EventId), ReceiveType>,
     pub push_downstream: Output<(SendType, EventId)>,
+    pub push_upstream: CountedOutput<(InternalResourceType, EventId)>,
+    pub push_loss: CountedOutput<(InternalResourceType, EventId)>,
     pub log_emitter: Output<DiscreteProcessLog<InternalResourceType>>,
 
     // Configuration
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
     pub process_quantity_distr: Distribution,
     pub delay_modes: DelayModes,
+    /// Sent with each withdrawal, e.g. an `ItemRequest` naming the item wanted from a `KeyedDiscreteStock`.
+    pub upstream_request: ReceiveParameterType,
+    pub env_stop_policy: EnvironmentStopPolicy,
 
     // Runtime state
     pub process_state: Option<(Duration, InternalResourceType)>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
 
     // Internals
     time_to_next_process_event: Option<Duration>,
@@ -353,7 +649,7 @@ pub struct DiscreteProcess<
     next_event_index: u64,
     previous_check_time: MonotonicTime,
 }
-impl<U: Clone + Debug + Send + 'static, V: Clone + Debug + Send + 'static, W: Clone + Debug + Send + 'static, X: Clone + Debug + Send + 'static> Default for DiscreteProcess<U, V, W, X> {
+impl<U: Clone + Debug + Default + Send + 'static, V: Clone + Debug + Send + 'static, W: Clone + Debug + Send + 'static, X: Clone + Debug + Send + 'static> Default for DiscreteProcess<U, V, W, X> {
     fn default() -> Self {
         DiscreteProcess {
             element_name: "DiscreteProcess".to_string(),
@@ -362,17 +658,27 @@ impl<U: Clone + Debug + Send + 'static, V: Clone + Debug + Send + 'static, W: Cl
 
             req_upstream: Requestor::new(),
             req_environment: Requestor::new(),
+            req_named_environment: Requestor::new(),
+            req_crew: Requestor::new(),
+            release_crew: Output::new(),
             req_downstream: Requestor::new(),
             withdraw_upstream: Requestor::new(),
             push_downstream: Output::new(),
+            push_upstream: CountedOutput::new(),
+            push_loss: CountedOutput::new(),
             log_emitter: Output::new(),
 
             process_time_distr: Default::default(),
+
+            time_unit: TimeUnit::default(),
             process_quantity_distr: Default::default(),
             delay_modes: Default::default(),
+            upstream_request: Default::default(),
+            env_stop_policy: Default::default(),
 
             process_state: None,
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
 
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -398,11 +704,15 @@ impl<
     } 
 }
 
-impl<T: Clone + Debug + Send + 'static> Process for DiscreteProcess<(), Option<T>, T, T> {
+impl<P: Clone + Debug + Send + 'static, T: Clone + Debug + Send + 'static> Process for DiscreteProcess<P, Option<T>, T, T> {
     type LogDetailsType = DiscreteProcessLogType<T>;
     
     fn pre_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> {
         async move {
+            if let Err(reason) = self.env_stop_policy.check_connected(&self.push_upstream, &self.push_loss) {
+                self.env_stop_policy = EnvironmentStopPolicy::Pause;
+                *source_event_id = self.log(cx.time(), source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason }).await;
+            }
             if let Some((scheduled_time, _)) = self.scheduled_event.as_ref() {
                 if *scheduled_time <= cx.time() {
                     self.scheduled_event = None;
@@ -418,16 +728,20 @@ impl<T: Clone + Debug + Send + 'static> Process for DiscreteProcess<(), Option<T
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.process_state.is_some() && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
-
-                // Decrement process time counter (if not delayed or env blocked)
-                if !(is_in_delay || is_env_blocked) {
-                    if let (Some((mut process_time_left, resource)), BasicEnvironmentState::Normal) = (self.process_state.take(), &self.env_state) {
-                        process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                let is_env_blocked = self.env_state.is_stopped();
+                let is_finishing = is_env_blocked && self.env_stop_policy == EnvironmentStopPolicy::FinishThenStop;
+
+                // Decrement process time counter (if not delayed or env blocked). A process finishing its current job
+                // carries on at the normal rate while the environment is stopped
+                if !is_in_delay && (!is_env_blocked || is_finishing) {
+                    if let Some((mut process_time_left, resource)) = self.process_state.take() {
+                        let progress = if is_finishing { duration_since_prev_check } else { self.env_state.progress(duration_since_prev_check) };
+                        process_time_left = process_time_left.saturating_sub(progress);
                         if process_time_left.is_zero() {
                             *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessFinish { resource: resource.clone() }).await;
+                            self.delay_modes.record_throughput(1.);
                             self.push_downstream.send((resource.clone(), source_event_id.clone())).await;
                         } else {
                             self.process_state = Some((process_time_left, resource));
@@ -435,44 +749,91 @@ impl<T: Clone + Debug + Send + 'static> Process for DiscreteProcess<(), Option<T
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => DiscreteProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => DiscreteProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => DiscreteProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => DiscreteProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
 
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                let is_stopping = new_env_state.is_stopped() && !self.env_state.is_stopped();
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        DiscreteProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        DiscreteProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
+                }
+
+                // Abandon any work in progress, if the stop policy calls for it
+                if is_stopping {
+                    match (self.env_stop_policy, self.process_state.take()) {
+                        (EnvironmentStopPolicy::AbortUpstream, Some((time_left, resource))) => {
+                            let upstream_room = match self.req_upstream.send(()).await.next() {
+                                Some(DiscreteStockState::Empty { empty, .. } | DiscreteStockState::Normal { empty, .. } | DiscreteStockState::Full { empty, .. }) => empty,
+                                None => 0,
+                            };
+                            if upstream_room > 0 {
+                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessAborted { resource: resource.clone() }).await;
+                                self.push_upstream.send((resource, source_event_id.clone())).await;
+                            } else {
+                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStopped { reason: "No room upstream to abort to, pausing instead".into() }).await;
+                                self.process_state = Some((time_left, resource));
+                            }
+                        },
+                        (EnvironmentStopPolicy::Scrap, Some((_, resource))) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessScrapped { resource: resource.clone() }).await;
+                            self.push_loss.send((resource, source_event_id.clone())).await;
+                        },
+                        (_, process_state) => {
+                            self.process_state = process_state;
+                        }
                     }
-                    _ => {}
                 }
             }
 
             // Update internal state
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
+            let is_env_stopped = self.env_state.is_stopped();
+            let has_active_delay = self.delay_modes.is_delayed() || is_env_stopped;
+            let is_finishing = is_env_stopped && !self.delay_modes.is_delayed() && self.env_stop_policy == EnvironmentStopPolicy::FinishThenStop;
             match (&self.process_state, has_active_delay) {
                 (None, false) => {
                     let us_state = self.req_upstream.send(()).await.next();
@@ -483,13 +844,13 @@ impl<T: Clone + Debug + Send + 'static> Process for DiscreteProcess<(), Option<T
                             Some(DiscreteStockState::Empty { .. } | DiscreteStockState::Normal { .. }),
                         ) => {
                             *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::WithdrawRequest).await;
-                            let received = self.withdraw_upstream.send(((), source_event_id.clone())).await.next().unwrap();
+                            let received = self.withdraw_upstream.send((self.upstream_request.clone(), source_event_id.clone())).await.next().unwrap();
                             match received {
                                 Some(received_resource) => {
-                                    let process_duration_secs = self.process_time_distr.sample();
-                                    self.process_state = Some((Duration::from_secs_f64(process_duration_secs), received_resource.clone()));
+                                    let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
+                                    self.process_state = Some((process_duration, received_resource.clone()));
                                     *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStart { resource: received_resource }).await;
-                                    self.time_to_next_process_event = Some(Duration::from_secs_f64(process_duration_secs));
+                                    self.time_to_next_process_event = self.env_state.time_to_progress(process_duration);
                                 },
                                 None => {
                                     *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream did not provide resource" }).await;
@@ -516,25 +877,25 @@ impl<T: Clone + Debug + Send + 'static> Process for DiscreteProcess<(), Option<T
                     }
                 },
                 (Some((time, _)), false) => {
+                    self.time_to_next_process_event = self.env_state.time_to_progress(*time);
+                }
+                (Some((time, _)), true) if is_finishing => {
                     self.time_to_next_process_event = Some(*time);
                 }
                 (_, true) => {
-                    self.time_to_next_process_event = self.delay_modes.active_delay().map(|(_, delay_state)| *delay_state);
+                    self.time_to_next_process_event = None;
                 },
             }
             
-            // Set time of next delay
-            if self.process_state.is_some() || has_active_delay || !is_env_stopped {
-                self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
-            } else {
-                self.time_to_next_delay_event = None;
-            }
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, self.process_state.is_some()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
 
     fn post_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> {
         async move {
-            match self.time_to_next_process_event {
+            let time_to_next_event = [self.time_to_next_delay_event, self.time_to_next_process_event].into_iter().flatten().min();
+            match time_to_next_event {
                 None => {},
                 Some(time_until_next) => {
                     if time_until_next.is_zero() {
@@ -585,13 +946,19 @@ impl<T: Clone + Debug + Send + 'static> Process for DiscreteProcess<(), Option<T
 #[derive(Debug, Clone)]
 pub enum DiscreteProcessLogType<T> {
     ProcessStart { resource: T },
-    ProcessContinue { reason: &'static str },
+    ProcessContinue { reason: String },
     ProcessFinish { resource: T },
     ProcessNonStart { reason: &'static str },
-    ProcessStopped { reason: &'static str },
+    ProcessStopped { reason: String },
     WithdrawRequest,
-    DelayStart { delay_name: String },
-    DelayEnd { delay_name: String },
+    DelayStart { delay_name: String, category: DelayCategory },
+    DelaySuspend { delay_name: String, category: DelayCategory },
+    DelayResume { delay_name: String, category: DelayCategory },
+    DelayEnd { delay_name: String, category: DelayCategory },
+    AwaitingMaintenance { delay_name: String },
+    MaintenanceStart { delay_name: String },
+    ProcessAborted { resource: T },
+    ProcessScrapped { resource: T },
 }
 
 
@@ -609,25 +976,45 @@ impl<T: Serialize> Serialize for DiscreteProcessLog<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: serde::Serializer {
-        let mut state = serializer.serialize_struct("DiscreteProcessLog", 8)?;
+        let mut state = serializer.serialize_struct("DiscreteProcessLog", 9)?;
         state.serialize_field("time", &self.time)?;
         state.serialize_field("event_id", &self.event_id)?;
         state.serialize_field("source_event_id", &self.source_event_id)?;
         state.serialize_field("element_name", &self.element_name)?;
         state.serialize_field("element_type", &self.element_type)?;
+        let mut delay_category: Option<String> = None;
         let (event_type, item, reason): (String, Option<String>, Option<&str>) = match &self.event {
             DiscreteProcessLogType::ProcessStart { resource } => ("ProcessStart".into(), Some(serde_json::to_string(resource).unwrap()), None),
-            DiscreteProcessLogType::ProcessContinue { reason } => ("ProcessContinue".into(), None, Some(reason)),
+            DiscreteProcessLogType::ProcessContinue { reason } => ("ProcessContinue".into(), None, Some(reason.as_str())),
             DiscreteProcessLogType::ProcessFinish { resource } => ("ProcessFinish".into(), Some(serde_json::to_string(resource).unwrap()), None),
             DiscreteProcessLogType::ProcessNonStart { reason } => ("ProcessNonStart".into(), None, Some(reason)),
-            DiscreteProcessLogType::ProcessStopped { reason } => ("ProcessStopped".into(), None, Some(reason)),
+            DiscreteProcessLogType::ProcessStopped { reason } => ("ProcessStopped".into(), None, Some(reason.as_str())),
             DiscreteProcessLogType::WithdrawRequest => ("WithdrawRequest".into(), None, None),
-            DiscreteProcessLogType::DelayStart { delay_name } => ("DelayStart".into(), Some(delay_name.clone()), None),
-            DiscreteProcessLogType::DelayEnd { delay_name } => ("DelayEnd".into(), Some(delay_name.clone()), None),
+            DiscreteProcessLogType::DelayStart { delay_name, category } => {
+                delay_category = Some(category.to_string());
+                ("DelayStart".into(), Some(delay_name.clone()), None)
+            },
+            DiscreteProcessLogType::DelaySuspend { delay_name, category } => {
+                delay_category = Some(category.to_string());
+                ("DelaySuspend".into(), Some(delay_name.clone()), None)
+            },
+            DiscreteProcessLogType::DelayResume { delay_name, category } => {
+                delay_category = Some(category.to_string());
+                ("DelayResume".into(), Some(delay_name.clone()), None)
+            },
+            DiscreteProcessLogType::DelayEnd { delay_name, category } => {
+                delay_category = Some(category.to_string());
+                ("DelayEnd".into(), Some(delay_name.clone()), None)
+            },
+            DiscreteProcessLogType::AwaitingMaintenance { delay_name } => ("AwaitingMaintenance".into(), Some(delay_name.clone()), None),
+            DiscreteProcessLogType::MaintenanceStart { delay_name } => ("MaintenanceStart".into(), Some(delay_name.clone()), None),
+            DiscreteProcessLogType::ProcessAborted { resource } => ("ProcessAborted".into(), Some(serde_json::to_string(resource).unwrap()), None),
+            DiscreteProcessLogType::ProcessScrapped { resource } => ("ProcessScrapped".into(), Some(serde_json::to_string(resource).unwrap()), None),
         };
         state.serialize_field("event_type", &event_type)?;
         state.serialize_field("item", &item)?;
         state.serialize_field("reason", &reason)?;
+        state.serialize_field("delay_category", &delay_category)?;
         state.end()
     }
 }
@@ -700,12 +1087,17 @@ pub struct DiscreteSource<
     // Ports
     pub req_upstream: Requestor<(), DiscreteStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub req_downstream: Requestor<(), DiscreteStockState>,
     pub push_downstream: Output<(SendType, EventId)>,
     pub log_emitter: Output<DiscreteProcessLog<InternalResourceType>>,
     
     // Configuration
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
     pub process_quantity_distr: Distribution,
     pub delay_modes: DelayModes,
     pub item_factory: FactoryType,
@@ -713,6 +1105,8 @@ pub struct DiscreteSource<
     // Runtime state
     pub process_state: Option<(Duration, InternalResourceType)>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
 
     // Internals
     time_to_next_process_event: Option<Duration>,
@@ -735,17 +1129,23 @@ impl<
 
             req_upstream: Requestor::new(),
             req_environment: Requestor::new(),
+            req_named_environment: Requestor::new(),
+            req_crew: Requestor::new(),
+            release_crew: Output::new(),
             req_downstream: Requestor::new(),
             push_downstream: Output::new(),
             log_emitter: Output::new(),
 
             process_time_distr: Default::default(),
+
+            time_unit: TimeUnit::default(),
             process_quantity_distr: Default::default(),
             delay_modes: DelayModes::default(),
             item_factory: FactoryType::default(),
             
             process_state: None,
             env_state: BasicEnvironmentState::Normal,            
+            env_limited_by: Vec::new(),
             
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -793,16 +1193,17 @@ impl<
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.process_state.is_some() && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
+                let is_env_blocked = self.env_state.is_stopped();
 
                 // Decrement process time counter (if not delayed or env blocked)
                 if !(is_in_delay || is_env_blocked) {
-                    if let (Some((mut process_time_left, resource)), BasicEnvironmentState::Normal) = (self.process_state.take(), &self.env_state) {
-                        process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                    if let Some((mut process_time_left, resource)) = self.process_state.take() {
+                        process_time_left = process_time_left.saturating_sub(self.env_state.progress(duration_since_prev_check));
                         if process_time_left.is_zero() {
                             *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessFinish { resource: resource.clone() }).await;
+                            self.delay_modes.record_throughput(1.);
                             self.push_downstream.send((resource.clone(), source_event_id.clone())).await;
                         } else {
                             self.process_state = Some((process_time_left, resource));
@@ -810,56 +1211,75 @@ impl<
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => DiscreteProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => DiscreteProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => DiscreteProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => DiscreteProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
 
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
-                    }
-                    _ => {}
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        DiscreteProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        DiscreteProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
                 }
             }
             
             // Update internal state
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
+            let is_env_stopped = self.env_state.is_stopped();
+            let has_active_delay = self.delay_modes.is_delayed() || is_env_stopped;
             match (&self.process_state, has_active_delay) {
                 (None, false) => {
                     let ds_state = self.req_downstream.send(()).await.next();
                     match &ds_state {
                         Some(DiscreteStockState::Empty { .. } | DiscreteStockState::Normal { .. }) => {
-                            let process_duration_secs = self.process_time_distr.sample();
+                            let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
 
                             let next_item = self.item_factory.create_item();
 
-                            self.process_state = Some((Duration::from_secs_f64(process_duration_secs), next_item.clone()));
+                            self.process_state = Some((process_duration, next_item.clone()));
                             *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStart { resource: next_item.clone() }).await;
-                            self.time_to_next_process_event = Some(Duration::from_secs_f64(process_duration_secs));
+                            self.time_to_next_process_event = self.env_state.time_to_progress(process_duration);
                         },
                         Some(DiscreteStockState::Full { .. }) => {
                             *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Downstream is full" }).await;
@@ -872,25 +1292,22 @@ impl<
                     }
                 },
                 (Some((time, _)), _) => {
-                    self.time_to_next_process_event = Some(*time);
+                    self.time_to_next_process_event = self.env_state.time_to_progress(*time);
                 },
                 (_, true) => {
-                    self.time_to_next_process_event = self.delay_modes.active_delay().map(|(_, delay_state)| *delay_state);
+                    self.time_to_next_process_event = None;
                 }
             }
             
-            // Set time of next delay
-            if self.process_state.is_some() || has_active_delay || !is_env_stopped {
-                self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
-            } else {
-                self.time_to_next_delay_event = None;
-            }
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, self.process_state.is_some()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
 
     fn post_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> {
         async move {
-            match self.time_to_next_process_event {
+            let time_to_next_event = [self.time_to_next_delay_event, self.time_to_next_process_event].into_iter().flatten().min();
+            match time_to_next_event {
                 None => {},
                 Some(time_until_next) => {
                     if time_until_next.is_zero() {
@@ -953,17 +1370,26 @@ pub struct DiscreteSink<
     // Ports
     pub req_upstream: Requestor<(), DiscreteStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub withdraw_upstream: Requestor<(RequestParameterType, EventId), RequestType>,
     pub log_emitter: Output<DiscreteProcessLog<InternalResourceType>>,
 
     // Configuration
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
     pub process_quantity_distr: Distribution,
     pub delay_modes: DelayModes,
+    /// Sent with each withdrawal, e.g. an `ItemRequest` naming the item wanted from a `KeyedDiscreteStock`.
+    pub upstream_request: RequestParameterType,
     
     // Runtime state
     pub process_state: Option<(Duration, InternalResourceType)>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
     
     // Internals
     time_to_next_process_event: Option<Duration>,
@@ -974,7 +1400,7 @@ pub struct DiscreteSink<
 }
 
 impl<
-    RequestParameterType: Clone + Debug + Send + 'static,
+    RequestParameterType: Clone + Debug + Default + Send + 'static,
     RequestType: Clone + Debug + Send + 'static,
     InternalResourceType: Clone + Debug + Send + 'static
 > Default for DiscreteSink<RequestParameterType, RequestType, InternalResourceType> {
@@ -986,15 +1412,22 @@ impl<
             
             req_upstream: Requestor::new(),
             req_environment: Requestor::new(),
+            req_named_environment: Requestor::new(),
+            req_crew: Requestor::new(),
+            release_crew: Output::new(),
             withdraw_upstream: Requestor::new(),
             log_emitter: Output::new(),
 
             process_time_distr: Default::default(),
+
+            time_unit: TimeUnit::default(),
             process_quantity_distr: Default::default(),
             delay_modes: Default::default(),
+            upstream_request: Default::default(),
             
             process_state: None,
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
 
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -1019,7 +1452,7 @@ impl<
     }
 }
 
-impl<T: Clone + Debug + Send + 'static> Process for DiscreteSink<(), Option<T>, T> {
+impl<P: Clone + Debug + Send + 'static, T: Clone + Debug + Send + 'static> Process for DiscreteSink<P, Option<T>, T> {
     type LogDetailsType = DiscreteProcessLogType<T>;
     
     fn pre_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> {
@@ -1039,74 +1472,94 @@ impl<T: Clone + Debug + Send + 'static> Process for DiscreteSink<(), Option<T>,
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.process_state.is_some() && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
+                let is_env_blocked = self.env_state.is_stopped();
 
                 // Decrement process time counter (if not delayed or env blocked)
                 if !(is_in_delay || is_env_blocked) {
-                    if let (Some((mut process_time_left, resource)), BasicEnvironmentState::Normal) = (self.process_state.take(), &self.env_state) {
+                    if let Some((mut process_time_left, resource)) = self.process_state.take() {
                         let duration_since_prev_check = cx.time().duration_since(self.previous_check_time);
-                        process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                        process_time_left = process_time_left.saturating_sub(self.env_state.progress(duration_since_prev_check));
                         if process_time_left.is_zero() {
                             *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessFinish { resource: resource.clone() }).await;
+                            self.delay_modes.record_throughput(1.);
                         } else {
                             self.process_state = Some((process_time_left, resource));
                         }
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => DiscreteProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => DiscreteProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => DiscreteProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => DiscreteProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
 
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
-                    }
-                    _ => {}
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        DiscreteProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        DiscreteProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
                 }
             }
 
             // Update internal state
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
+            let is_env_stopped = self.env_state.is_stopped();
+            let has_active_delay = self.delay_modes.is_delayed() || is_env_stopped;
             match (&self.process_state, has_active_delay) {
                 (None, false) => {
                     let us_state = self.req_upstream.send(()).await.next();
                     match &us_state {
                         Some(DiscreteStockState::Normal { .. } | DiscreteStockState::Full { .. }) => {
                             *source_event_id = self.log(cx.time(), source_event_id.clone(), DiscreteProcessLogType::WithdrawRequest).await;
-                            let moved = self.withdraw_upstream.send(((), source_event_id.clone())).await.next().unwrap();
+                            let moved = self.withdraw_upstream.send((self.upstream_request.clone(), source_event_id.clone())).await.next().unwrap();
                             match moved {
                                 Some(moved) => {
-                                    let process_duration_secs = self.process_time_distr.sample();
-                                    self.process_state = Some((Duration::from_secs_f64(process_duration_secs), moved.clone()));
+                                    let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
+                                    self.process_state = Some((process_duration, moved.clone()));
                                     *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStart { resource: moved }).await;
-                                    self.time_to_next_process_event = Some(Duration::from_secs_f64(process_duration_secs));
+                                    self.time_to_next_process_event = self.env_state.time_to_progress(process_duration);
                                 },
                                 None => {
                                     *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream did not provide resource" }).await;
@@ -1124,20 +1577,23 @@ impl<T: Clone + Debug + Send + 'static> Process for DiscreteSink<(), Option<T>,
                         }
                     }
                 },
-                (Some((time, _)), _) => {
-                    self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
+                (Some((time, _)), false) => {
+                    self.time_to_next_process_event = self.env_state.time_to_progress(*time);
                 },
                 (_, true) => {
                     self.time_to_next_process_event = None;
                 },
-
             }
+
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, self.process_state.is_some()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
 
     fn post_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> {
         async move {
-            match self.time_to_next_process_event {
+            let time_to_next_event = [self.time_to_next_delay_event, self.time_to_next_process_event].into_iter().flatten().min();
+            match time_to_next_event {
                 None => {},
                 Some(time_until_next) => {
                     if time_until_next.is_zero() {
@@ -1200,19 +1656,31 @@ pub struct DiscreteParallelProcess<
     // Ports
     pub req_upstream: Requestor<(), DiscreteStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub req_downstream: Requestor<(), DiscreteStockState>,
     pub withdraw_upstream: Requestor<(ReceiveParameterType, EventId), ReceiveType>,
     pub push_downstream: Output<(SendType, EventId)>,
+    pub push_upstream: CountedOutput<(InternalResourceType, EventId)>,
+    pub push_loss: CountedOutput<(InternalResourceType, EventId)>,
     pub log_emitter: Output<DiscreteProcessLog<SendType>>,
 
     // Configuration
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
     pub process_quantity_distr: Distribution,
     pub delay_modes: DelayModes,
+    /// Sent with each withdrawal, e.g. an `ItemRequest` naming the item wanted from a `KeyedDiscreteStock`.
+    pub upstream_request: ReceiveParameterType,
+    pub env_stop_policy: EnvironmentStopPolicy,
 
     // Runtime state
     pub processes_in_progress: Vec<(Duration, InternalResourceType)>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
     pub processes_complete: VecDeque<SendType>,
 
     // Internals
@@ -1224,7 +1692,7 @@ pub struct DiscreteParallelProcess<
 }
 
 impl<
-    ReceiveParameterType: Clone + Debug + Send + 'static,
+    ReceiveParameterType: Clone + Debug + Default + Send + 'static,
     ReceiveType: Clone + Debug + Send + 'static,
     InternalResourceType: Clone + Debug + Send + 'static,
     SendType: Clone + Debug + Send + 'static
@@ -1237,17 +1705,27 @@ impl<
             
             req_upstream: Requestor::new(),
             req_environment: Requestor::new(),
+            req_named_environment: Requestor::new(),
+            req_crew: Requestor::new(),
+            release_crew: Output::new(),
             req_downstream: Requestor::new(),
             withdraw_upstream: Requestor::new(),
             push_downstream: Output::new(),
+            push_upstream: CountedOutput::new(),
+            push_loss: CountedOutput::new(),
             log_emitter: Output::new(),
             
             process_time_distr: Default::default(),
+            
+            time_unit: TimeUnit::default(),
             process_quantity_distr: Default::default(),
             delay_modes: Default::default(),
+            upstream_request: Default::default(),
+            env_stop_policy: Default::default(),
 
             processes_in_progress: Vec::new(),
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
             processes_complete: VecDeque::new(),
             
             time_to_next_process_event: None,
@@ -1274,7 +1752,7 @@ impl<
     }
 }
 
-impl<U: Clone + Debug + Send + 'static> Process for DiscreteParallelProcess<(), Option<U>, U, U> {
+impl<P: Clone + Debug + Send + 'static, U: Clone + Debug + Send + 'static> Process for DiscreteParallelProcess<P, Option<U>, U, U> {
     type LogDetailsType = DiscreteProcessLogType<U>;
 
     fn update_state_impl(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> {
@@ -1286,33 +1764,31 @@ impl<U: Clone + Debug + Send + 'static> Process for DiscreteParallelProcess<(),
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.processes_in_progress.len().gt(&0) && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
-
-                // Decrement process time counter (if not delayed or env blocked)
-                if !(is_in_delay || is_env_blocked) {
-
-                    match &self.env_state {
-                        BasicEnvironmentState::Normal => {
-                            self.processes_in_progress.retain_mut(|(process_time_left, item)| {
-                                *process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
-                                if process_time_left.is_zero() {
-                                    self.processes_complete.push_back(item.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            });
-                        },
-                        BasicEnvironmentState::Stopped => {}
-                    }
+                let is_env_blocked = self.env_state.is_stopped();
+                let is_finishing = is_env_blocked && self.env_stop_policy == EnvironmentStopPolicy::FinishThenStop;
+
+                // Decrement process time counter (if not delayed or env blocked). Processes finishing their current
+                // jobs carry on at the normal rate while the environment is stopped
+                if !is_in_delay && (!is_env_blocked || is_finishing) {
+                    let progress = if is_finishing { duration_since_prev_check } else { self.env_state.progress(duration_since_prev_check) };
+                    self.processes_in_progress.retain_mut(|(process_time_left, item)| {
+                        *process_time_left = process_time_left.saturating_sub(progress);
+                        if process_time_left.is_zero() {
+                            self.processes_complete.push_back(item.clone());
+                            false
+                        } else {
+                            true
+                        }
+                    });
 
                     while let Some(item) = self.processes_complete.pop_front() {
                         let ds_state = self.req_downstream.send(()).await.next();
                         match &ds_state {
                             Some(DiscreteStockState::Empty { .. } | DiscreteStockState::Normal { .. }) => {
                                 *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessFinish { resource: item.clone() }).await;
+                                self.delay_modes.record_throughput(1.);
                                 self.push_downstream.send((item.clone(), source_event_id.clone())).await;
                             },
                             Some(DiscreteStockState::Full { .. }) => {
@@ -1327,97 +1803,147 @@ impl<U: Clone + Debug + Send + 'static> Process for DiscreteParallelProcess<(),
                     }
                 }
                 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => DiscreteProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => DiscreteProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => DiscreteProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => DiscreteProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
 
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                let is_stopping = new_env_state.is_stopped() && !self.env_state.is_stopped();
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        DiscreteProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        DiscreteProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
+                }
+
+                // Abandon all work in progress, if the stop policy calls for it
+                if is_stopping {
+                    match self.env_stop_policy {
+                        EnvironmentStopPolicy::AbortUpstream => {
+                            let mut upstream_room = match self.req_upstream.send(()).await.next() {
+                                Some(DiscreteStockState::Empty { empty, .. } | DiscreteStockState::Normal { empty, .. } | DiscreteStockState::Full { empty, .. }) => empty,
+                                None => 0,
+                            };
+                            // Items that do not fit upstream are paused instead
+                            for (time_left, item) in std::mem::take(&mut self.processes_in_progress) {
+                                if upstream_room > 0 {
+                                    upstream_room -= 1;
+                                    *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessAborted { resource: item.clone() }).await;
+                                    self.push_upstream.send((item, source_event_id.clone())).await;
+                                } else {
+                                    *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStopped { reason: "No room upstream to abort to, pausing instead".into() }).await;
+                                    self.processes_in_progress.push((time_left, item));
+                                }
+                            }
+                        },
+                        EnvironmentStopPolicy::Scrap => {
+                            for (_, item) in std::mem::take(&mut self.processes_in_progress) {
+                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessScrapped { resource: item.clone() }).await;
+                                self.push_loss.send((item, source_event_id.clone())).await;
+                            }
+                        },
+                        EnvironmentStopPolicy::Pause | EnvironmentStopPolicy::FinishThenStop => {},
                     }
-                    _ => {}
                 }
             }
 
             // Update internal state
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
-            match &self.env_state {
-                BasicEnvironmentState::Stopped => {
-                    self.time_to_next_process_event = None;
-                },
-                BasicEnvironmentState::Normal => {
-                    loop {
-                        let us_state = self.req_upstream.send(()).await.next();
-                        match &us_state {
-                            Some(DiscreteStockState::Empty { .. } | DiscreteStockState::Normal { .. }) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::WithdrawRequest).await;
-                                let item = self.withdraw_upstream.send(((), source_event_id.clone())).await.next().unwrap();
-                                if let Some(item) = item {
-                                    let process_duration = Duration::from_secs_f64(self.process_time_distr.sample());
+            let is_env_stopped = self.env_state.is_stopped();
+            if is_env_stopped {
+                // Only jobs already under way can progress, and only if they're being finished off
+                self.time_to_next_process_event = match self.env_stop_policy {
+                    EnvironmentStopPolicy::FinishThenStop if !self.delay_modes.is_delayed() => self.processes_in_progress.iter().map(|(time, _)| *time).min(),
+                    _ => None,
+                };
+            } else {
+                loop {
+                    let us_state = self.req_upstream.send(()).await.next();
+                    match &us_state {
+                        Some(DiscreteStockState::Empty { .. } | DiscreteStockState::Normal { .. }) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::WithdrawRequest).await;
+                            let item = self.withdraw_upstream.send((self.upstream_request.clone(), source_event_id.clone())).await.next().unwrap();
+                            if let Some(item) = item {
+                                let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
 
-                                    self.processes_in_progress.push((process_duration, item.clone()));
-                                    *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStart { resource: item }).await;
+                                self.processes_in_progress.push((process_duration, item.clone()));
+                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStart { resource: item }).await;
 
-                                } else {
-                                    break;
-                                }
-                            },
-                            Some(DiscreteStockState::Full { .. }) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is full" }).await;
-                                break;
-                            },
-                            None => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is not connected" }).await;
+                            } else {
                                 break;
                             }
+                        },
+                        Some(DiscreteStockState::Full { .. }) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is full" }).await;
+                            break;
+                        },
+                        None => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is not connected" }).await;
+                            break;
                         }
                     }
-                    
-                    self.time_to_next_process_event = if self.processes_in_progress.is_empty() {
-                        None
-                    } else {
-                        // Find the minimum time to next event
-                        let min_time = self.processes_in_progress.iter().map(|(time, _)| *time).min().unwrap();
-                        Some(min_time)
-                    };
                 }
+                
+                self.time_to_next_process_event = if self.processes_in_progress.is_empty() {
+                    None
+                } else {
+                    // Find the minimum time to next event
+                    let min_time = self.processes_in_progress.iter().map(|(time, _)| *time).min().unwrap();
+                    self.env_state.time_to_progress(min_time)
+                };
             }
             
-            // Set time of next delay
-            if self.processes_in_progress.len().gt(&0) || has_active_delay || !is_env_stopped {
-                self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
-            } else {
-                self.time_to_next_delay_event = None;
-            }
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, !self.processes_in_progress.is_empty()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
     
     fn pre_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> {
         async move {
+            if let Err(reason) = self.env_stop_policy.check_connected(&self.push_upstream, &self.push_loss) {
+                self.env_stop_policy = EnvironmentStopPolicy::Pause;
+                *source_event_id = self.log(cx.time(), source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason }).await;
+            }
             if let Some((scheduled_time, _)) = self.scheduled_event.as_ref() {
                 if *scheduled_time <= cx.time() {
                     self.scheduled_event = None;
@@ -1428,7 +1954,8 @@ impl<U: Clone + Debug + Send + 'static> Process for DiscreteParallelProcess<(),
 
     fn post_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> {
         async move {
-            match self.time_to_next_process_event {
+            let time_to_next_event = [self.time_to_next_delay_event, self.time_to_next_process_event].into_iter().flatten().min();
+            match time_to_next_event {
                 None => {},
                 Some(time_until_next) => {
                     if time_until_next.is_zero() {
@@ -1475,3 +2002,113 @@ impl<U: Clone + Debug + Send + 'static> Process for DiscreteParallelProcess<(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Removes every item from the queue in the order the discipline gives them up.
+    fn drain<T>(discipline: &mut QueueDiscipline<T>, items: Vec<T>) -> Vec<T> {
+        let mut items = VecDeque::from(items);
+        let mut removed = Vec::new();
+        while let Some(index) = discipline.select(&items) {
+            removed.extend(items.remove(index));
+        }
+        removed
+    }
+
+    #[test]
+    fn test_fifo_and_lifo() {
+        assert_eq!(drain(&mut QueueDiscipline::Fifo, vec![3, 1, 2]), vec![3, 1, 2]);
+        assert_eq!(drain(&mut QueueDiscipline::Lifo, vec![3, 1, 2]), vec![2, 1, 3]);
+        assert_eq!(QueueDiscipline::<u32>::Fifo.select(&VecDeque::new()), None);
+    }
+
+    #[test]
+    fn test_priority_ties_keep_arrival_order() {
+        let items = vec![("A", 2), ("B", 1), ("C", 2), ("D", 1)];
+        let mut discipline = QueueDiscipline::priority_by_key(|(_, priority): &(&str, u32)| *priority);
+        assert_eq!(drain(&mut discipline, items.clone()), vec![("B", 1), ("D", 1), ("A", 2), ("C", 2)]);
+
+        let mut discipline = QueueDiscipline::priority_by_key(|(_, priority): &(&str, u32)| std::cmp::Reverse(*priority));
+        assert_eq!(drain(&mut discipline, items), vec![("A", 2), ("C", 2), ("B", 1), ("D", 1)]);
+
+        assert_eq!(drain(&mut QueueDiscipline::priority_by_ord(), vec![3, 1, 2]), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_shortest_processing_time() {
+        let items = vec![("Long", 30), ("Short", 5), ("Medium", 10), ("Short again", 5)];
+        let mut discipline = QueueDiscipline::shortest_processing_time(|(_, secs): &(&str, u64)| Duration::from_secs(*secs));
+        let order: Vec<_> = drain(&mut discipline, items).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(order, vec!["Short", "Short again", "Medium", "Long"]);
+    }
+
+    #[test]
+    fn test_random_reproducible_with_seed() {
+        let items: Vec<u32> = (0..20).collect();
+        let order = |seed| drain(&mut QueueDiscipline::random(&mut DistributionFactory::new(seed)), items.clone());
+        assert_eq!(order(7), order(7));
+        assert_ne!(order(7), order(8));
+        assert_ne!(order(7), items);
+
+        let mut sorted = order(7);
+        sorted.sort();
+        assert_eq!(sorted, items);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Truck {
+        id: String,
+        payload: f64,
+    }
+
+    impl HasUniqueKey<String> for Truck {
+        fn get_key(&self) -> String {
+            self.id.clone()
+        }
+    }
+
+    fn trucks() -> ItemMap<String, Truck> {
+        ItemMap::from(vec![
+            Truck { id: "T07".into(), payload: 40. },
+            Truck { id: "T09".into(), payload: 80. },
+            Truck { id: "T01".into(), payload: 90. },
+        ])
+    }
+
+    #[test]
+    fn test_item_request_by_key() {
+        let mut items = trucks();
+        assert_eq!(items.remove(ItemRequest::Key("T09".into())), Some(Truck { id: "T09".into(), payload: 80. }));
+        assert_eq!(items.len(), 2);
+        // Any takes the lowest key
+        assert_eq!(items.remove(ItemRequest::Any).map(|truck| truck.id), Some("T01".into()));
+    }
+
+    #[test]
+    fn test_item_request_matching() {
+        let mut items = trucks();
+        let loaded = || ItemRequest::matching(|truck: &Truck| truck.payload > 60.);
+        assert_eq!(items.remove(loaded()).map(|truck| truck.id), Some("T01".into()));
+        assert_eq!(items.remove(loaded()).map(|truck| truck.id), Some("T09".into()));
+        assert_eq!(items.remove(loaded()), None);
+        assert_eq!(items.len(), 1);
+    }
+
+    #[test]
+    fn test_item_request_missing_key() {
+        let mut items = trucks();
+        assert_eq!(items.remove(ItemRequest::Key("T02".into())), None);
+        assert_eq!(items.len(), 3);
+        assert_eq!(ItemMap::<String, Truck>::default().remove(ItemRequest::Any), None);
+    }
+
+    #[test]
+    fn test_item_map_keeps_first_of_duplicate_keys() {
+        let mut items = trucks();
+        items.add(Truck { id: "T07".into(), payload: 0. });
+        assert_eq!(items.len(), 3);
+        assert_eq!(items["T07"].payload, 40.);
+    }
+}
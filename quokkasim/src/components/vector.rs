@@ -291,18 +291,28 @@ pub struct VectorProcess<
     pub req_upstream: Requestor<(), VectorStockState>,
     pub req_downstream: Requestor<(), VectorStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub withdraw_upstream: Requestor<(ReceiveParameterType, EventId), ReceiveType>,
     pub push_downstream: Output<(SendType, EventId)>,
+    pub push_upstream: CountedOutput<(InternalResourceType, EventId)>,
+    pub push_loss: CountedOutput<(InternalResourceType, EventId)>,
     pub log_emitter: Output<VectorProcessLog<InternalResourceType>>,
 
     // Configuration
     pub process_quantity_distr: Distribution,
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
     pub delay_modes: DelayModes,
+    pub env_stop_policy: EnvironmentStopPolicy,
 
     // Runtime State
     pub process_state: Option<(Duration, InternalResourceType)>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
     
     // Internals
     time_to_next_process_event: Option<Duration>,
@@ -326,16 +336,24 @@ impl<
             req_upstream: Requestor::default(),
             req_downstream: Requestor::default(),
             req_environment: Requestor::default(),
+            req_named_environment: Requestor::default(),
+            req_crew: Requestor::default(),
+            release_crew: Output::default(),
             withdraw_upstream: Requestor::default(),
             push_downstream: Output::default(),
+            push_upstream: CountedOutput::default(),
+            push_loss: CountedOutput::default(),
             log_emitter: Output::default(),
 
             process_state: None,
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
 
             process_quantity_distr: Distribution::default(),
             process_time_distr: Distribution::default(),
+            time_unit: TimeUnit::default(),
             delay_modes: DelayModes::default(),
+            env_stop_policy: EnvironmentStopPolicy::default(),
 
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -369,6 +387,10 @@ where
     
     fn pre_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> {
         async move {
+            if let Err(reason) = self.env_stop_policy.check_connected(&self.push_upstream, &self.push_loss) {
+                self.env_stop_policy = EnvironmentStopPolicy::Pause;
+                *source_event_id = self.log(cx.time(), source_event_id.clone(), VectorProcessLogType::ProcessFailure { reason }).await;
+            }
             if let Some((scheduled_time, _)) = self.scheduled_event.as_ref() {
                 if *scheduled_time <= cx.time() {
                     self.scheduled_event = None;
@@ -383,16 +405,20 @@ where
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.process_state.is_some() && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
+                let is_env_blocked = self.env_state.is_stopped();
+                let is_finishing = is_env_blocked && self.env_stop_policy == EnvironmentStopPolicy::FinishThenStop;
 
-                // Decrement process time counter (if not delayed or env blocked)
-                if !(is_in_delay || is_env_blocked) {
+                // Decrement process time counter (if not delayed or env blocked). A process finishing its current job
+                // carries on at the normal rate while the environment is stopped
+                if !is_in_delay && (!is_env_blocked || is_finishing) {
                     if let Some((mut process_time_left, resource)) = self.process_state.take() {
-                        process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                        let progress = if is_finishing { duration_since_prev_check } else { self.env_state.progress(duration_since_prev_check) };
+                        process_time_left = process_time_left.saturating_sub(progress);
                         if process_time_left.is_zero() {
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessSuccess { quantity: resource.total(), vector: resource.clone() }).await;
+                            self.delay_modes.record_throughput(resource.total());
                             self.push_downstream.send((resource.clone(), source_event_id.clone())).await;
                         } else {
                             self.process_state = Some((process_time_left, resource));
@@ -400,44 +426,91 @@ where
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => VectorProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => VectorProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => VectorProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => VectorProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
             
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                let is_stopping = new_env_state.is_stopped() && !self.env_state.is_stopped();
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        VectorProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        VectorProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        VectorProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
+                }
+
+                // Abandon any work in progress, if the stop policy calls for it
+                if is_stopping {
+                    match (self.env_stop_policy, self.process_state.take()) {
+                        (EnvironmentStopPolicy::AbortUpstream, Some((time_left, resource))) => {
+                            let upstream_room = match self.req_upstream.send(()).await.next() {
+                                Some(VectorStockState::Empty { empty, .. } | VectorStockState::Normal { empty, .. } | VectorStockState::Full { empty, .. }) => empty,
+                                None => 0.,
+                            };
+                            if resource.total() <= upstream_room {
+                                *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessAborted { quantity: resource.total(), vector: resource.clone() }).await;
+                                self.push_upstream.send((resource, source_event_id.clone())).await;
+                            } else {
+                                *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessStopped { reason: "No room upstream to abort to, pausing instead".into() }).await;
+                                self.process_state = Some((time_left, resource));
+                            }
+                        },
+                        (EnvironmentStopPolicy::Scrap, Some((_, resource))) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessScrapped { quantity: resource.total(), vector: resource.clone() }).await;
+                            self.push_loss.send((resource, source_event_id.clone())).await;
+                        },
+                        (_, process_state) => {
+                            self.process_state = process_state;
+                        }
                     }
-                    _ => {}
                 }
             }
 
             // Update internal state
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
+            let is_env_stopped = self.env_state.is_stopped();
+            let has_active_delay = self.delay_modes.is_delayed() || is_env_stopped;
+            let is_finishing = is_env_stopped && !self.delay_modes.is_delayed() && self.env_stop_policy == EnvironmentStopPolicy::FinishThenStop;
             match (&self.process_state, has_active_delay) {
                 (None, false) => {
                     let us_state = self.req_upstream.send(()).await.next();
@@ -447,13 +520,13 @@ where
                             Some(VectorStockState::Normal {..}) | Some(VectorStockState::Full {..}),
                             Some(VectorStockState::Empty {..}) | Some(VectorStockState::Normal {..}),
                         ) => {
-                            let process_quantity = self.process_quantity_distr.sample();
+                            let process_quantity = self.process_quantity_distr.sample_at(time);
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::WithdrawRequest).await;
                             let moved = self.withdraw_upstream.send((process_quantity, source_event_id.clone())).await.next().unwrap();
-                            let process_duration_secs = self.process_time_distr.sample();
-                            self.process_state = Some((Duration::from_secs_f64(process_duration_secs), moved.clone()));
+                            let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
+                            self.process_state = Some((process_duration, moved.clone()));
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessStart { quantity: process_quantity, vector: moved }).await;
-                            self.time_to_next_process_event = Some(Duration::from_secs_f64(process_duration_secs));
+                            self.time_to_next_process_event = self.env_state.time_to_progress(process_duration);
                         },
                         (Some(VectorStockState::Empty {..} ), _) => {
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessFailure { reason: "Upstream is empty" }).await;
@@ -474,19 +547,18 @@ where
                     }
                 },
                 (Some((time, _)), false) => {
+                    self.time_to_next_process_event = self.env_state.time_to_progress(*time);
+                },
+                (Some((time, _)), true) if is_finishing => {
                     self.time_to_next_process_event = Some(*time);
                 },
                 (_, true) => {
-                    self.time_to_next_process_event = self.delay_modes.active_delay().map(|(_, delay_state)| *delay_state);
+                    self.time_to_next_process_event = None;
                 }
             }
             
-            // Set time of next delay
-            if self.process_state.is_some() || has_active_delay || !is_env_stopped {
-                self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
-            } else {
-                self.time_to_next_delay_event = None;
-            }
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, self.process_state.is_some()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
 
@@ -575,10 +647,16 @@ pub enum VectorProcessLogType<T> {
     SplitFailure { reason: &'static str },
     WithdrawRequest,
     PushRequest,
-    DelayStart { delay_name: String },
-    DelayEnd { delay_name: String },
-    ProcessStopped { reason: &'static str },
-    ProcessContinue { reason: &'static str },
+    DelayStart { delay_name: String, category: DelayCategory },
+    DelaySuspend { delay_name: String, category: DelayCategory },
+    DelayResume { delay_name: String, category: DelayCategory },
+    DelayEnd { delay_name: String, category: DelayCategory },
+    AwaitingMaintenance { delay_name: String },
+    MaintenanceStart { delay_name: String },
+    ProcessStopped { reason: String },
+    ProcessContinue { reason: String },
+    ProcessAborted { quantity: f64, vector: T },
+    ProcessScrapped { quantity: f64, vector: T },
 }
 
 #[derive(Debug, Clone)]
@@ -596,13 +674,14 @@ impl<T> Serialize for VectorProcessLog<T> where T: Serialize + Send {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("VectorProcessLog", 10)?;
+        let mut state = serializer.serialize_struct("VectorProcessLog", 11)?;
         state.serialize_field("time", &self.time)?;
         state.serialize_field("event_id", &self.event_id)?;
         state.serialize_field("source_event_id", &self.source_event_id)?;
         state.serialize_field("element_name", &self.element_name)?;
         state.serialize_field("element_type", &self.element_type)?;
         let (event_type, total, inflows, outflows, reason): (&str, Option<f64>, Option<String>, Option<String>, Option<String>);
+        let mut delay_category: Option<String> = None;
         match &self.event {
             VectorProcessLogType::ProcessStart { quantity, vector } => {
                 event_type = "ProcessStart";
@@ -681,19 +760,51 @@ impl<T> Serialize for VectorProcessLog<T> where T: Serialize + Send {
                 outflows = None;
                 reason = None;
             },
-            VectorProcessLogType::DelayStart { delay_name } => {
+            VectorProcessLogType::DelayStart { delay_name, category } => {
                 event_type = "DelayStart";
                 total = None;
                 inflows = None;
                 outflows = None;
                 reason = Some(delay_name.clone());
+                delay_category = Some(category.to_string());
+            },
+            VectorProcessLogType::DelaySuspend { delay_name, category } => {
+                event_type = "DelaySuspend";
+                total = None;
+                inflows = None;
+                outflows = None;
+                reason = Some(delay_name.clone());
+                delay_category = Some(category.to_string());
             },
-            VectorProcessLogType::DelayEnd { delay_name } => {
+            VectorProcessLogType::DelayResume { delay_name, category } => {
+                event_type = "DelayResume";
+                total = None;
+                inflows = None;
+                outflows = None;
+                reason = Some(delay_name.clone());
+                delay_category = Some(category.to_string());
+            },
+            VectorProcessLogType::DelayEnd { delay_name, category } => {
                 event_type = "DelayEnd";
                 total = None;
                 inflows = None;
                 outflows = None;
                 reason = Some(delay_name.clone());
+                delay_category = Some(category.to_string());
+            },
+            VectorProcessLogType::AwaitingMaintenance { delay_name } => {
+                event_type = "AwaitingMaintenance";
+                total = None;
+                inflows = None;
+                outflows = None;
+                reason = Some(delay_name.clone());
+            },
+            VectorProcessLogType::MaintenanceStart { delay_name } => {
+                event_type = "MaintenanceStart";
+                total = None;
+                inflows = None;
+                outflows = None;
+                reason = Some(delay_name.clone());
             },
             VectorProcessLogType::ProcessStopped { reason: r } => {
                 event_type = "ProcessStopped";
@@ -709,12 +820,27 @@ impl<T> Serialize for VectorProcessLog<T> where T: Serialize + Send {
                 outflows = None;
                 reason = Some(r.to_string());
             },
+            VectorProcessLogType::ProcessAborted { quantity, vector } => {
+                event_type = "ProcessAborted";
+                total = Some(*quantity);
+                inflows = None;
+                outflows = Some(serde_json::to_string(&vec![vector]).map_err(|e| serde::ser::Error::custom(e.to_string()))?);
+                reason = None;
+            },
+            VectorProcessLogType::ProcessScrapped { quantity, vector } => {
+                event_type = "ProcessScrapped";
+                total = Some(*quantity);
+                inflows = None;
+                outflows = Some(serde_json::to_string(&vec![vector]).map_err(|e| serde::ser::Error::custom(e.to_string()))?);
+                reason = None;
+            },
         }
         state.serialize_field("event_type", &event_type)?;
         state.serialize_field("total", &total)?;
         state.serialize_field("inflows", &inflows)?;
         state.serialize_field("outflows", &outflows)?;
         state.serialize_field("reason", &reason)?;
+        state.serialize_field("delay_category", &delay_category)?;
         state.end()
     }
 }
@@ -740,6 +866,9 @@ pub struct VectorCombiner<
     pub req_upstreams: [Requestor<(), VectorStockState>; M],
     pub req_downstream: Requestor<(), VectorStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub withdraw_upstreams: [Requestor<(ReceiveParameterType, EventId), ReceiveType>; M],
     pub push_downstream: Output<(SendType, EventId)>,
     pub log_emitter: Output<VectorProcessLog<ReceiveType>>,
@@ -747,12 +876,16 @@ pub struct VectorCombiner<
     // Configuration
     pub process_quantity_distr: Distribution,
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
     pub delay_modes: DelayModes,
     pub split_ratios: [f64; M],
 
     // Runtime State
     pub process_state: Option<(Duration, InternalResourceType)>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
 
     // Internals
     time_to_next_process_event: Option<Duration>,
@@ -792,6 +925,9 @@ impl<
             req_upstreams: std::array::from_fn(|_| Requestor::default()),
             req_downstream: Requestor::default(),
             req_environment: Requestor::default(),
+            req_named_environment: Requestor::default(),
+            req_crew: Requestor::default(),
+            release_crew: Output::default(),
             withdraw_upstreams: std::array::from_fn(|_| Requestor::default()),
             push_downstream: Output::default(),
             log_emitter: Output::default(),
@@ -799,10 +935,12 @@ impl<
             delay_modes: DelayModes::default(),
             process_quantity_distr: Distribution::default(),
             process_time_distr: Distribution::default(),
+            time_unit: TimeUnit::default(),
             split_ratios: [1./(M as f64); M],
 
             process_state: None,
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
 
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -836,15 +974,15 @@ where
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.process_state.is_some() && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
+                let is_env_blocked = self.env_state.is_stopped();
 
                 // Decrement process time counter (if not in a delay)
                 if !(is_in_delay || is_env_blocked) {
                     if let Some((mut process_time_left, resources)) = self.process_state.take() {
                         
-                        process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                        process_time_left = process_time_left.saturating_sub(self.env_state.progress(duration_since_prev_check));
                         if process_time_left.is_zero() {
                             let mut total: T = Default::default();
 
@@ -853,6 +991,7 @@ where
                             }
 
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::CombineSuccess { quantity: resources.iter().map(|x| x.total()).sum(), vector: total.clone() }).await;
+                            self.delay_modes.record_throughput(resources.iter().map(|x| x.total()).sum());
                             self.push_downstream.send((total, source_event_id.clone())).await;
                         } else {
                             self.process_state = Some((process_time_left, resources));
@@ -860,44 +999,63 @@ where
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => VectorProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => VectorProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => VectorProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => VectorProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
 
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
-                    }
-                    _ => {}
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        VectorProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        VectorProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        VectorProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
                 }
             }
 
             // Update internal states
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
+            let is_env_stopped = self.env_state.is_stopped();
+            let has_active_delay = self.delay_modes.is_delayed() || is_env_stopped;
             match (&self.process_state, has_active_delay) {
                 (None, false) => {
                     let iterators = join_all(self.req_upstreams.iter_mut().map(|req| {
@@ -918,7 +1076,7 @@ where
                             Some(true),
                             Some(VectorStockState::Empty {..}) | Some(VectorStockState::Normal {..}),
                         ) => {
-                            let process_quantity = self.process_quantity_distr.sample();
+                            let process_quantity = self.process_quantity_distr.sample_at(time);
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::WithdrawRequest).await;
                             let withdraw_iterators = join_all(self.withdraw_upstreams.iter_mut().map(|req| {
                                 req.send((process_quantity, source_event_id.clone()))
@@ -928,10 +1086,10 @@ where
                                 .collect::<Vec<T>>()
                                 .try_into()
                                 .unwrap_or_else(|_| panic!("Failed to convert to array"));
-                            let process_duration_secs = self.process_time_distr.sample();
-                            self.process_state = Some((Duration::from_secs_f64(process_duration_secs), withdrawn.clone()));
+                            let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
+                            self.process_state = Some((process_duration, withdrawn.clone()));
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::CombineStart { quantity: process_quantity, vectors: withdrawn.into() }).await;
-                            self.time_to_next_process_event = Some(Duration::from_secs_f64(process_duration_secs));
+                            self.time_to_next_process_event = self.env_state.time_to_progress(process_duration);
                         },
                         (Some(false), _) => {
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessFailure { reason: "At least one upstream is empty" }).await;
@@ -952,19 +1110,15 @@ where
                     }
                 },
                 (Some((time, _)), false) => {
-                    self.time_to_next_process_event = Some(*time);
+                    self.time_to_next_process_event = self.env_state.time_to_progress(*time);
                 },
                 (_, true) => {
-                    self.time_to_next_process_event = self.delay_modes.active_delay().map(|(_, delay_state)| *delay_state);
+                    self.time_to_next_process_event = None;
                 }
             }
              
-            // Set time of next delay
-            if self.process_state.is_some() || has_active_delay || !is_env_stopped {
-                self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
-            } else {
-                self.time_to_next_delay_event = None;
-            }
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, self.process_state.is_some()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
 
@@ -1040,6 +1194,9 @@ pub struct VectorSplitter<
     pub req_upstream: Requestor<(), VectorStockState>,
     pub req_downstreams: [Requestor<(), VectorStockState>; N],
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub withdraw_upstream: Requestor<(ReceiveParameterType, EventId), ReceiveType>,
     pub push_downstreams: [Output<(SendType, EventId)>; N],
     pub log_emitter: Output<VectorProcessLog<ReceiveType>>,
@@ -1048,11 +1205,15 @@ pub struct VectorSplitter<
     pub split_ratios: [f64; N],
     pub process_quantity_distr: Distribution,
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
     pub delay_modes: DelayModes,
 
     // Runtime State
     pub process_state: Option<(Duration, InternalResourceType)>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
 
     // Internals
     time_to_next_process_event: Option<Duration>,
@@ -1078,6 +1239,9 @@ impl<
             req_upstream: Requestor::default(),
             req_downstreams: std::array::from_fn(|_| Requestor::default()),
             req_environment: Requestor::default(),
+            req_named_environment: Requestor::default(),
+            req_crew: Requestor::default(),
+            release_crew: Output::default(),
             withdraw_upstream: Requestor::default(),
             push_downstreams: std::array::from_fn(|_| Output::default()),
             log_emitter: Output::default(),
@@ -1085,10 +1249,12 @@ impl<
             split_ratios: [1./(N as f64); N],
             process_quantity_distr: Distribution::default(),
             process_time_distr: Distribution::default(),
+            time_unit: TimeUnit::default(),
             delay_modes: DelayModes::default(),
 
             process_state: None,
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
 
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -1138,14 +1304,14 @@ where
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.process_state.is_some() && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
+                let is_env_blocked = self.env_state.is_stopped();
 
                 // Decrement process time counter (if not in a delay)
                 if !(is_in_delay || is_env_blocked) {
                     if let Some((mut process_time_left, resource)) = self.process_state.take() {
-                        process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                        process_time_left = process_time_left.saturating_sub(self.env_state.progress(duration_since_prev_check));
 
                         if process_time_left.is_zero() {
         
@@ -1155,6 +1321,7 @@ where
                             }).collect::<Vec<_>>();
 
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::SplitSuccess { quantity: resource.total(), vectors: split_resources.clone() }).await;
+                            self.delay_modes.record_throughput(resource.total());
 
                             join_all(self.push_downstreams.iter_mut().zip(split_resources).map(|(push, resource)| {
                                 push.send((resource.clone(), source_event_id.clone()))
@@ -1165,44 +1332,63 @@ where
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => VectorProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => VectorProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => VectorProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => VectorProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
             
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
-                    }
-                    _ => {}
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        VectorProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        VectorProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        VectorProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
                 }
             }
 
             // Update internal states
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
+            let is_env_stopped = self.env_state.is_stopped();
+            let has_active_delay = self.delay_modes.is_delayed() || is_env_stopped;
             match (&self.process_state, has_active_delay) {
                 (None, false) => {
                     let us_state = self.req_upstream.send(()).await.next();
@@ -1223,13 +1409,13 @@ where
                             Some(VectorStockState::Full {..}) | Some(VectorStockState::Normal {..}),
                             Some(true),
                         ) => {
-                            let process_quantity = self.process_quantity_distr.sample();
+                            let process_quantity = self.process_quantity_distr.sample_at(time);
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::WithdrawRequest).await;
                             let withdrawn = self.withdraw_upstream.send((process_quantity, source_event_id.clone())).await.next().unwrap();
-                            let process_duration_secs = self.process_time_distr.sample();
-                            self.process_state = Some((Duration::from_secs_f64(process_duration_secs), withdrawn.clone()));
+                            let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
+                            self.process_state = Some((process_duration, withdrawn.clone()));
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::SplitStart { quantity: process_quantity, vector: withdrawn }).await;
-                            self.time_to_next_process_event = Some(Duration::from_secs_f64(process_duration_secs));
+                            self.time_to_next_process_event = self.env_state.time_to_progress(process_duration);
                         },
                         (_, Some(false)) => {
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessFailure { reason: "At least one downstream is full" }).await;
@@ -1250,19 +1436,15 @@ where
                     }
                 },
                 (Some((time, _)), false) => {
-                    self.time_to_next_process_event = Some(*time);
+                    self.time_to_next_process_event = self.env_state.time_to_progress(*time);
                 },
                 (_, true) => {
-                    self.time_to_next_process_event = self.delay_modes.active_delay().map(|(_, delay_state)| *delay_state);
+                    self.time_to_next_process_event = None;
                 }
             }
 
-            // Set time of next delay
-            if self.process_state.is_some() || has_active_delay || !is_env_stopped {
-                self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
-            } else {
-                self.time_to_next_delay_event = None;
-            }
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, self.process_state.is_some()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
 
@@ -1334,6 +1516,9 @@ pub struct VectorSource<
     // Ports
     pub req_downstream: Requestor<(), VectorStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub push_downstream: Output<(SendType, EventId)>,
     pub log_emitter: Output<VectorProcessLog<InternalResourceType>>,
 
@@ -1342,10 +1527,14 @@ pub struct VectorSource<
     pub delay_modes: DelayModes,
     pub process_quantity_distr: Distribution,
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
 
     // Runtime State
     pub process_state: Option<(Duration, InternalResourceType)>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
 
     // Internals
     time_to_next_process_event: Option<Duration>,
@@ -1364,16 +1553,21 @@ impl<InternalResourceType: Clone + Debug + Default + Send, SendType: Clone + Deb
 
             req_downstream: Requestor::default(),
             req_environment: Requestor::default(),
+            req_named_environment: Requestor::default(),
+            req_crew: Requestor::default(),
+            release_crew: Output::default(),
             push_downstream: Output::default(),
             log_emitter: Output::default(),
             
             delay_modes: DelayModes::default(),
             process_quantity_distr: Distribution::default(),
             process_time_distr: Distribution::default(),
+            time_unit: TimeUnit::default(),
             source_vector: InternalResourceType::default(),
 
             process_state: None,
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
 
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -1420,17 +1614,18 @@ where
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.process_state.is_some() && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
+                let is_env_blocked = self.env_state.is_stopped();
 
                 // Decrement process time counter (if not in a delay)
                 if !(is_in_delay || is_env_blocked) {
                     if let Some((mut process_time_left, resource)) = self.process_state.take() {
                         let duration_since_prev_check = cx.time().duration_since(self.previous_check_time);
-                        process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                        process_time_left = process_time_left.saturating_sub(self.env_state.progress(duration_since_prev_check));
                         if process_time_left.is_zero() {
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessSuccess { quantity: resource.total(), vector: resource.clone() }).await;
+                            self.delay_modes.record_throughput(resource.total());
                             self.push_downstream.send((resource.clone(), source_event_id.clone())).await;
                         } else {
                             self.process_state = Some((process_time_left, resource));
@@ -1438,44 +1633,63 @@ where
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if is_in_delay || is_in_process {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => VectorProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => VectorProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => VectorProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => VectorProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
             
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
-                    }
-                    _ => {}
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        VectorProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        VectorProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        VectorProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
                 }
             }
 
             // Update internal states
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
+            let is_env_stopped = self.env_state.is_stopped();
+            let has_active_delay = self.delay_modes.is_delayed() || is_env_stopped;
             match (&self.process_state, has_active_delay) {
                 (None, false) => {
                     let ds_state = self.req_downstream.send(()).await.next();
@@ -1485,17 +1699,17 @@ where
                             self.time_to_next_process_event = None;
                         },
                         Some(VectorStockState::Normal {..}) | Some(VectorStockState::Empty {..}) => {
-                            let process_quantity = self.process_quantity_distr.sample();
+                            let process_quantity = self.process_quantity_distr.sample_at(time);
                             if self.source_vector.total() <= 0. {
                                 panic!("Source vector has total 0 or negative ({}), cannot process!", self.source_vector.total());
                             }
                             let mut created = self.source_vector.clone();
                             
                             created.multiply(process_quantity / created.total());
-                            let process_duration_secs = self.process_time_distr.sample();
-                            self.process_state = Some((Duration::from_secs_f64(process_duration_secs), created.clone()));
+                            let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
+                            self.process_state = Some((process_duration, created.clone()));
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessStart { quantity: process_quantity, vector: created }).await;
-                            self.time_to_next_process_event = Some(Duration::from_secs_f64(process_duration_secs));
+                            self.time_to_next_process_event = self.env_state.time_to_progress(process_duration);
                         },
                         None => {
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessFailure { reason: "Downstream is not connected" }).await;
@@ -1504,19 +1718,15 @@ where
                     }
                 },
                 (Some((time, _)), false) => {
-                    self.time_to_next_process_event = Some(*time);
+                    self.time_to_next_process_event = self.env_state.time_to_progress(*time);
                 },
                 (_, true) => {
-                    self.time_to_next_process_event = self.delay_modes.active_delay().map(|(_, delay_state)| *delay_state);
+                    self.time_to_next_process_event = None;
                 }
             }
             
-            // Set time of next delay
-            if self.process_state.is_some() || has_active_delay || !is_env_stopped {
-                self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
-            } else {
-                self.time_to_next_delay_event = None;
-            }
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, self.process_state.is_some()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
 
@@ -1589,6 +1799,9 @@ pub struct VectorSink<
     // Ports
     pub req_upstream: Requestor<(), VectorStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub withdraw_upstream: Requestor<(ReceiveParameterType, EventId), ReceiveType>,
     pub log_emitter: Output<VectorProcessLog<InternalResourceType>>,
     
@@ -1596,10 +1809,14 @@ pub struct VectorSink<
     pub delay_modes: DelayModes,
     pub process_quantity_distr: Distribution,
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
 
     // Runtime State
     pub process_state: Option<(Duration, InternalResourceType)>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
 
     // Internals
     time_to_next_process_event: Option<Duration>,
@@ -1636,15 +1853,20 @@ impl<
             
             req_upstream: Requestor::default(),
             req_environment: Requestor::default(),
+            req_named_environment: Requestor::default(),
+            req_crew: Requestor::default(),
+            release_crew: Output::default(),
             withdraw_upstream: Requestor::default(),
             log_emitter: Output::default(),
 
             delay_modes: DelayModes::default(),
             process_quantity_distr: Distribution::default(),
             process_time_distr: Distribution::default(),
+            time_unit: TimeUnit::default(),
 
             process_state: None,
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
 
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -1679,73 +1901,93 @@ where
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.process_state.is_some() && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
+                let is_env_blocked = self.env_state.is_stopped();
 
                 // Decrement process time counter (if not delayed or env blocked)
                 if !(is_in_delay || is_env_blocked) {
                     if let Some((mut process_time_left, resource)) = self.process_state.take() {
                         let duration_since_prev_check = cx.time().duration_since(self.previous_check_time);
-                        process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                        process_time_left = process_time_left.saturating_sub(self.env_state.progress(duration_since_prev_check));
                         if process_time_left.is_zero() {
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessSuccess { quantity: resource.total(), vector: resource.clone() }).await;
+                            self.delay_modes.record_throughput(resource.total());
                         } else {
                             self.process_state = Some((process_time_left, resource));
                         }
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => VectorProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => VectorProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => VectorProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => VectorProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
             
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
-                    }
-                    _ => {}
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        VectorProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        VectorProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        VectorProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
                 }
             }
 
             // Update internal states
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
+            let is_env_stopped = self.env_state.is_stopped();
+            let has_active_delay = self.delay_modes.is_delayed() || is_env_stopped;
             match (&self.process_state, has_active_delay) {
                 (None, false) => {
                     let us_state = self.req_upstream.send(()).await.next();
                     match us_state {
                         Some(VectorStockState::Normal {..}) | Some(VectorStockState::Full {..}) => {
-                            let process_quantity = self.process_quantity_distr.sample();
+                            let process_quantity = self.process_quantity_distr.sample_at(time);
                             *source_event_id = self.log(time, source_event_id.clone(), VectorProcessLogType::WithdrawRequest).await;
                             let withdrawn = self.withdraw_upstream.send((process_quantity, source_event_id.clone())).await.next().unwrap();
-                            let process_duration_secs = self.process_time_distr.sample();
-                            self.process_state = Some((Duration::from_secs_f64(process_duration_secs), withdrawn.clone()));
+                            let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
+                            self.process_state = Some((process_duration, withdrawn.clone()));
                             self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessStart { quantity: process_quantity, vector: withdrawn }).await;
-                            self.time_to_next_process_event = Some(Duration::from_secs_f64(process_duration_secs));
+                            self.time_to_next_process_event = self.env_state.time_to_progress(process_duration);
                         },
                         Some(VectorStockState::Empty {..}) => {
                             self.log(time, source_event_id.clone(), VectorProcessLogType::ProcessFailure { reason: "Upstream is empty" }).await;
@@ -1758,33 +2000,21 @@ where
                     }
                 },
                 (Some((time, _)), false) => {
-                    self.time_to_next_process_event = Some(*time);
+                    self.time_to_next_process_event = self.env_state.time_to_progress(*time);
                 },
                 (_, true) => {
                     self.time_to_next_process_event = None;
-                    self.time_to_next_delay_event = self.delay_modes.active_delay().map(|(_, delay_state)| *delay_state);
                 }
             }
 
-            // Set time of next delay
-            if self.process_state.is_some() || has_active_delay || !is_env_stopped {
-                self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
-            } else {
-                self.time_to_next_delay_event = None;
-            }
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, self.process_state.is_some()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
 
     fn post_update_state(&mut self, source_event_id: &mut EventId, cx: &mut nexosim::model::Context<Self>) -> impl Future<Output = ()> + Send {
         async move {
-            let time_to_next_event = match (self.time_to_next_process_event, self.time_to_next_delay_event, self.delay_modes.active_delay()) {
-                (Some(process_time), Some(delay_time), Some(_)) => Some(process_time.min(delay_time)),
-                (Some(process_time), None, _) => Some(process_time),
-                (None, Some(delay_time), Some(_)) => Some(delay_time),
-                (None, Some(_), None) => None, // Time to next delay only decrements if a process is active
-                _ => None,
-            };
-            // let time_to_next_event = [self.time_to_next_delay_event, self.time_to_next_process_event].into_iter().flatten().min();
+            let time_to_next_event = [self.time_to_next_delay_event, self.time_to_next_process_event].into_iter().flatten().min();
             match time_to_next_event {
                 None => {},
                 Some(time_until_next) => {
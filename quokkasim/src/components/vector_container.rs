@@ -170,6 +170,9 @@ pub struct ContainerLoadingProcess<
     pub req_us_resource: Requestor<(), VectorStockState>,
     pub req_downstream: Requestor<(), DiscreteStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub withdraw_us_containers: Requestor<((), EventId), Option<ContainerType>>,
     pub withdraw_us_resource: Requestor<(f64, EventId), ResourceType>,
     pub push_downstream: Output<(ContainerType, EventId)>,
@@ -178,11 +181,15 @@ pub struct ContainerLoadingProcess<
     // Configuration
     pub max_process_count: usize,
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
     pub process_capacity_ratio_distr: Distribution,
     pub delay_modes: DelayModes,
 
     // Runtime State
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
     processes_in_progress: Vec<(Duration, ContainerType)>,
     /// Proportion of the container capacity that is loaded
     pub processes_complete: VecDeque<ContainerType>, 
@@ -209,6 +216,9 @@ impl<
             req_us_resource: Requestor::default(),
             req_downstream: Requestor::default(),
             req_environment: Requestor::default(),
+            req_named_environment: Requestor::default(),
+            req_crew: Requestor::default(),
+            release_crew: Output::default(),
             withdraw_us_containers: Requestor::default(),
             withdraw_us_resource: Requestor::default(),
             push_downstream: Output::default(),
@@ -216,12 +226,14 @@ impl<
 
             max_process_count: 1,
             process_time_distr: Default::default(),
+            time_unit: TimeUnit::default(),
             process_capacity_ratio_distr: Distribution::Constant(1.),
             delay_modes: Default::default(),
 
             processes_in_progress: Vec::new(),
             processes_complete: VecDeque::new(),
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
 
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -268,14 +280,15 @@ impl<
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.processes_in_progress.len().gt(&0) && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
+                let is_env_blocked = self.env_state.is_stopped();
 
                 // Decrement process time counter (if not delayed or env blocked)
                 if !(is_in_delay || is_env_blocked) {
+                    let progress = self.env_state.progress(duration_since_prev_check);
                     self.processes_in_progress.retain_mut(|(process_time_left, item)| {
-                        *process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                        *process_time_left = process_time_left.saturating_sub(progress);
                         if process_time_left.is_zero() {
                             self.processes_complete.push_back(item.clone());
                             false
@@ -289,6 +302,7 @@ impl<
                         match &ds_state {
                             Some(DiscreteStockState::Empty { .. } | DiscreteStockState::Normal { .. }) => {
                                 *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessFinish { resource: item.clone() }).await;
+                                self.delay_modes.record_throughput(1.);
                                 self.push_downstream.send((item.clone(), source_event_id.clone())).await;
                             },
                             Some(DiscreteStockState::Full { .. }) => {
@@ -303,98 +317,118 @@ impl<
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => DiscreteProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => DiscreteProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => DiscreteProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => DiscreteProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
 
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
-                    }
-                    _ => {}
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        DiscreteProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        DiscreteProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
                 }
             }
 
             // Then check for any processes to start
 
-            match &self.env_state {
-                BasicEnvironmentState::Stopped => {
-                    self.time_to_next_process_event = None;
-                },
-                BasicEnvironmentState::Normal => {
-                    loop {
-                        let us_state = self.req_us_containers.send(()).await.next();
-                        let remaining_proc_count = self.max_process_count - self.processes_in_progress.len() - self.processes_complete.len();
-                        match (&us_state, remaining_proc_count) {
-                            (Some(DiscreteStockState::Empty { .. } | DiscreteStockState::Normal { .. }), 1..) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::WithdrawRequest).await;
-                                let container = self.withdraw_us_containers.send(((), source_event_id.clone())).await.next().unwrap();
-                                if let Some(mut item) = container {
-                                    let proportion = self.process_capacity_ratio_distr.sample();
-                                    let quantity = (item.get_capacity() - item.get_resource().map_or(0., |x| x.total())) * proportion;
-                                    let resource = self.withdraw_us_resource.send((quantity, source_event_id.clone())).await.next().unwrap();
-                                    let process_duration = Duration::from_secs_f64(self.process_time_distr.sample());
-                                    item.add(resource);
-                                    self.processes_in_progress.push((process_duration, item.clone()));
-                                    *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStart { resource: item }).await;
-
-                                } else {
-                                    break;
-                                }
-                            },
-                            (_, 0) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "No remaining process slots" }).await;
-                                break;
-                            },
-                            (Some(DiscreteStockState::Full { .. }), _) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is full" }).await;
-                                break;
-                            },
-                            (None, _) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is not connected" }).await;
+            if self.env_state.is_stopped() {
+                self.time_to_next_process_event = None;
+            } else {
+                loop {
+                    let us_state = self.req_us_containers.send(()).await.next();
+                    let remaining_proc_count = self.max_process_count - self.processes_in_progress.len() - self.processes_complete.len();
+                    match (&us_state, remaining_proc_count) {
+                        (Some(DiscreteStockState::Empty { .. } | DiscreteStockState::Normal { .. }), 1..) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::WithdrawRequest).await;
+                            let container = self.withdraw_us_containers.send(((), source_event_id.clone())).await.next().unwrap();
+                            if let Some(mut item) = container {
+                                let proportion = self.process_capacity_ratio_distr.sample_at(time);
+                                let quantity = (item.get_capacity() - item.get_resource().map_or(0., |x| x.total())) * proportion;
+                                let resource = self.withdraw_us_resource.send((quantity, source_event_id.clone())).await.next().unwrap();
+                                let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
+                                item.add(resource);
+                                self.processes_in_progress.push((process_duration, item.clone()));
+                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStart { resource: item }).await;
+
+                            } else {
                                 break;
                             }
+                        },
+                        (_, 0) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "No remaining process slots" }).await;
+                            break;
+                        },
+                        (Some(DiscreteStockState::Full { .. }), _) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is full" }).await;
+                            break;
+                        },
+                        (None, _) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is not connected" }).await;
+                            break;
                         }
                     }
-                    
-                    self.time_to_next_process_event = if self.processes_in_progress.is_empty() {
-                        None
-                    } else {
-                        // Find the minimum time to next event
-                        let min_time = self.processes_in_progress.iter().map(|(time, _)| *time).min().unwrap();
-                        Some(min_time)
-                    };
                 }
+                
+                self.time_to_next_process_event = if self.processes_in_progress.is_empty() {
+                    None
+                } else {
+                    // Find the minimum time to next event
+                    let min_time = self.processes_in_progress.iter().map(|(time, _)| *time).min().unwrap();
+                    self.env_state.time_to_progress(min_time)
+                };
             }
+
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(self.env_state.is_stopped(), !self.processes_in_progress.is_empty()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
     
     fn post_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model {
         async move {
-            match self.time_to_next_process_event {
+            let time_to_next_event = [self.time_to_next_delay_event, self.time_to_next_process_event].into_iter().flatten().min();
+            match time_to_next_event {
                 None => {},
                 Some(time_until_next) => {
                     if time_until_next.is_zero() {
@@ -455,6 +489,9 @@ pub struct ContainerUnloadingProcess<
     // Ports
     pub req_upstream: Requestor<(), DiscreteStockState>,
     pub req_environment: Requestor<(), BasicEnvironmentState>,
+    pub req_named_environment: Requestor<(), (String, BasicEnvironmentState)>,
+    pub req_crew: Requestor<(String, EventId), bool>,
+    pub release_crew: Output<(String, EventId)>,
     pub req_ds_containers: Requestor<(), DiscreteStockState>,
     pub req_ds_resource: Requestor<(), VectorStockState>,
     pub withdraw_upstream: Requestor<((), EventId), Option<ContainerType>>,
@@ -465,6 +502,8 @@ pub struct ContainerUnloadingProcess<
     // Configuration
     pub max_process_count: usize,
     pub process_time_distr: Distribution,
+    /// Unit of `process_time_distr` samples, unless the distribution declares its own.
+    pub time_unit: TimeUnit,
     /// Proportion of the held amount to be unloaded
     pub process_quantity_ratio_distr: Distribution,
     pub delay_modes: DelayModes,
@@ -473,6 +512,8 @@ pub struct ContainerUnloadingProcess<
     processes_in_progress: Vec<(Duration, ContainerType)>,
     pub processes_complete: VecDeque<ContainerType>,
     pub env_state: BasicEnvironmentState,
+    /// Environments currently stopping the process or reducing its rate.
+    pub env_limited_by: Vec<String>,
 
     // Internals
     time_to_next_process_event: Option<Duration>,
@@ -494,6 +535,9 @@ impl<
             
             req_upstream: Requestor::default(),
             req_environment: Requestor::default(),
+            req_named_environment: Requestor::default(),
+            req_crew: Requestor::default(),
+            release_crew: Output::default(),
             withdraw_upstream: Requestor::default(),
             req_ds_containers: Requestor::default(),
             req_ds_resource: Requestor::default(),
@@ -503,12 +547,14 @@ impl<
 
             max_process_count: 1,
             process_time_distr: Default::default(),
+            time_unit: TimeUnit::default(),
             process_quantity_ratio_distr: Distribution::Constant(1.),
             delay_modes: Default::default(),
 
             processes_in_progress: Vec::new(),
             processes_complete: VecDeque::new(),
             env_state: BasicEnvironmentState::Normal,
+            env_limited_by: Vec::new(),
 
             time_to_next_process_event: None,
             time_to_next_delay_event: None,
@@ -555,14 +601,15 @@ impl<
 
             // Update duration counters based on time since last check
             {
-                let is_in_delay = self.delay_modes.active_delay().is_some();
+                let is_in_delay = self.delay_modes.is_delayed();
                 let is_in_process = self.processes_in_progress.len().gt(&0) && !is_in_delay;
-                let is_env_blocked = matches!(self.env_state, BasicEnvironmentState::Stopped);
+                let is_env_blocked = self.env_state.is_stopped();
 
                 // Decrement process time counter (if not delayed or env blocked)
                 if !(is_in_delay || is_env_blocked) {
+                    let progress = self.env_state.progress(duration_since_prev_check);
                     self.processes_in_progress.retain_mut(|(process_time_left, item)| {
-                        *process_time_left = process_time_left.saturating_sub(duration_since_prev_check);
+                        *process_time_left = process_time_left.saturating_sub(progress);
                         if process_time_left.is_zero() {
                             self.processes_complete.push_back(item.clone());
                             false
@@ -577,8 +624,9 @@ impl<
                         match (&ds_containers_state, &ds_resource_state) {
                             (Some(DiscreteStockState::Empty { .. } | DiscreteStockState::Normal { .. }), Some(VectorStockState::Empty { .. } | VectorStockState::Normal { .. }) ) => {
                                 *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessFinish { resource: item.clone() }).await;
+                                self.delay_modes.record_throughput(1.);
                                 
-                                let proportion = self.process_quantity_ratio_distr.sample();
+                                let proportion = self.process_quantity_ratio_distr.sample_at(time);
                                 match item.take_resource() {
                                     Some(mut resource) => {
                                         if proportion >= 1. {
@@ -613,102 +661,114 @@ impl<
                     }
                 }
 
-                // Only case we don't update state here is if no delay is if we don't want the delay counters to decrement,
-                // which is only the case if we're not processing and not in a delay - i.e. time-until-delay counters only decrement
-                // when a process is active
-                if !is_env_blocked && (is_in_delay || is_in_process) {
-                    let delay_transition = self.delay_modes.update_state(duration_since_prev_check);
-                    if delay_transition.has_changed() {
-                        if let Some(delay_name) = &delay_transition.from {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayEnd { delay_name: delay_name.clone() }).await;
-                        }
-                        if let Some(delay_name) = &delay_transition.to {
-                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::DelayStart { delay_name: delay_name.clone() }).await;
+                // Delay counters decrement by the elapsed time on their mode's clock - busy time only accrues if we're
+                // processing or in a delay, and available time while the environment isn't stopping us
+                let elapsed = ElapsedTime {
+                    calendar: duration_since_prev_check,
+                    available: if !is_env_blocked { duration_since_prev_check } else { Duration::ZERO },
+                    busy: if !is_env_blocked && (is_in_delay || is_in_process) { duration_since_prev_check } else { Duration::ZERO },
+                };
+                self.delay_modes.update_state_at(time, elapsed);
+                for (delay_name, delay_event) in self.delay_modes.take_delay_events() {
+                    let category = self.delay_modes.category(&delay_name);
+                    let log_type = match delay_event {
+                        DelayEvent::Start => DiscreteProcessLogType::DelayStart { delay_name, category },
+                        DelayEvent::Suspend => DiscreteProcessLogType::DelaySuspend { delay_name, category },
+                        DelayEvent::Resume => DiscreteProcessLogType::DelayResume { delay_name, category },
+                        DelayEvent::End => DiscreteProcessLogType::DelayEnd { delay_name, category },
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                }
+                for delay_name in self.delay_modes.release_crews() {
+                    self.release_crew.send((format!("{}/{}", self.element_name, delay_name), source_event_id.clone())).await;
+                }
+                for delay_name in self.delay_modes.awaiting_crew() {
+                    // Without a crew connected, the fix starts straight away
+                    let requester = format!("{}/{}", self.element_name, delay_name);
+                    let is_assigned = self.req_crew.send((requester, source_event_id.clone())).await.next().unwrap_or(true);
+                    if is_assigned {
+                        if self.delay_modes.assign_crew(&delay_name) {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::MaintenanceStart { delay_name }).await;
                         }
+                    } else if self.delay_modes.queue_for_crew(&delay_name) {
+                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::AwaitingMaintenance { delay_name }).await;
                     }
                 }
             }
 
             // Update cached environment state
             {
-                let new_env_state = match self.req_environment.send(()).await.next() {
-                    Some(x) => x,
-                    None => BasicEnvironmentState::Normal // Assume always normal operation if no environment state connected
-                };
-                match (&self.env_state, &new_env_state) {
-                    (BasicEnvironmentState::Normal, BasicEnvironmentState::Stopped) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStopped { reason: "Stopped by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Stopped;
-                    },
-                    (BasicEnvironmentState::Stopped, BasicEnvironmentState::Normal) => {
-                        *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment" }).await;
-                        self.env_state = BasicEnvironmentState::Normal;
-                    }
-                    _ => {}
+                // Stopped if any connected environment is stopped. Assume always normal operation if none are connected
+                let unnamed_states = self.req_environment.send(()).await.map(|state| ("environment".to_string(), state));
+                let (new_env_state, limited_by) = combine_environment_states(self.req_named_environment.send(()).await.chain(unnamed_states));
+                if new_env_state != self.env_state || limited_by != self.env_limited_by {
+                    let log_type = if new_env_state.is_stopped() {
+                        DiscreteProcessLogType::ProcessStopped { reason: format!("Stopped by {}", limited_by.join(", ")) }
+                    } else if let BasicEnvironmentState::Reduced(_) = new_env_state {
+                        DiscreteProcessLogType::ProcessContinue { reason: format!("Rate reduced by {}", limited_by.join(", ")) }
+                    } else {
+                        DiscreteProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }
+                    };
+                    *source_event_id = self.log(time, source_event_id.clone(), log_type).await;
+                    self.env_state = new_env_state;
+                    self.env_limited_by = limited_by;
                 }
             }
 
             // Then check for any processes to start
-            let is_env_stopped = matches!(self.env_state, BasicEnvironmentState::Stopped);
-            let has_active_delay = self.delay_modes.active_delay().is_some() || is_env_stopped;
-            match &self.env_state {
-                BasicEnvironmentState::Stopped => {
-                    self.time_to_next_process_event = None;
-                },
-                BasicEnvironmentState::Normal => {
-                    loop {
-                        let us_state = self.req_upstream.send(()).await.next();
-                        let remaining_proc_count = self.max_process_count - self.processes_in_progress.len() - self.processes_complete.len();
-                        match (&us_state, remaining_proc_count) {
-                            (Some(DiscreteStockState::Normal { .. } | DiscreteStockState::Full { .. }), 1..) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::WithdrawRequest).await;
-                                let container = self.withdraw_upstream.send(((), source_event_id.clone())).await.next().unwrap();
-                                if let Some(item) = container {
-                                    let process_duration = Duration::from_secs_f64(self.process_time_distr.sample());
-                                    self.processes_in_progress.push((process_duration, item.clone()));
-                                    *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStart { resource: item }).await;
-                                } else {
-                                    // Upstream state was not empty, but nothing was returned?
-                                    break;
-                                }
-                            },
-                            (_, 0) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "No remaining process slots" }).await;
-                                break;
-                            },
-                            (Some(DiscreteStockState::Empty { .. }), _) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is empty" }).await;
-                                break;
-                            },
-                            (None, _) => {
-                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is not connected" }).await;
+            let is_env_stopped = self.env_state.is_stopped();
+            if is_env_stopped {
+                self.time_to_next_process_event = None;
+            } else {
+                loop {
+                    let us_state = self.req_upstream.send(()).await.next();
+                    let remaining_proc_count = self.max_process_count - self.processes_in_progress.len() - self.processes_complete.len();
+                    match (&us_state, remaining_proc_count) {
+                        (Some(DiscreteStockState::Normal { .. } | DiscreteStockState::Full { .. }), 1..) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::WithdrawRequest).await;
+                            let container = self.withdraw_upstream.send(((), source_event_id.clone())).await.next().unwrap();
+                            if let Some(item) = container {
+                                let process_duration = self.process_time_distr.sample_duration_at(time, self.time_unit);
+                                self.processes_in_progress.push((process_duration, item.clone()));
+                                *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessStart { resource: item }).await;
+                            } else {
+                                // Upstream state was not empty, but nothing was returned?
                                 break;
                             }
+                        },
+                        (_, 0) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "No remaining process slots" }).await;
+                            break;
+                        },
+                        (Some(DiscreteStockState::Empty { .. }), _) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is empty" }).await;
+                            break;
+                        },
+                        (None, _) => {
+                            *source_event_id = self.log(time, source_event_id.clone(), DiscreteProcessLogType::ProcessNonStart { reason: "Upstream is not connected" }).await;
+                            break;
                         }
                     }
-                    
-                    self.time_to_next_process_event = if self.processes_in_progress.is_empty() {
-                        None
-                    } else {
-                        // Find the minimum time to next event
-                        let min_time = self.processes_in_progress.iter().map(|(time, _)| *time).min().unwrap();
-                        Some(min_time)
-                    };
                 }
+                
+                self.time_to_next_process_event = if self.processes_in_progress.is_empty() {
+                    None
+                } else {
+                    // Find the minimum time to next event
+                    let min_time = self.processes_in_progress.iter().map(|(time, _)| *time).min().unwrap();
+                    self.env_state.time_to_progress(min_time)
+                };
             }
 
-            // Set time of next delay
-            if self.processes_in_progress.len().gt(&0) || has_active_delay || !is_env_stopped {
-                self.time_to_next_delay_event = self.delay_modes.get_next_event().map(|(_, delay_state)| delay_state.as_duration());
-            } else {
-                self.time_to_next_delay_event = None;
-            }
+            // Set time of next delay, from the counters whose clocks run until then
+            self.time_to_next_delay_event = self.delay_modes.get_next_event_while(is_env_stopped, !self.processes_in_progress.is_empty()).map(|(_, delay_state)| delay_state.as_duration());
         }
     }
     
     fn post_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model {
         async move {
-            match self.time_to_next_process_event {
+            let time_to_next_event = [self.time_to_next_delay_event, self.time_to_next_process_event].into_iter().flatten().min();
+            match time_to_next_event {
                 None => {},
                 Some(time_until_next) => {
                     if time_until_next.is_zero() {
@@ -1,4 +1,4 @@
-use std::{error::Error, fmt::Debug, fs::File};
+use std::{collections::VecDeque, error::Error, fmt::{Debug, Display, Formatter, Result as FmtResult}, fs::File, str::FromStr, time::Duration};
 use csv::WriterBuilder;
 use crate::prelude::*;
 use serde::{ser::SerializeStruct, Serialize};
@@ -220,6 +220,8 @@ pub struct BasicEnvironment {
     pub element_name: String,
     pub element_code: String,
     pub state: BasicEnvironmentState,
+    /// Recorded state changes replayed over the simulation, e.g. from historical weather records.
+    pub time_series: EnvironmentTimeSeries,
     pub next_event_index: u64,
     pub log_emitter: Output<BasicEnvironmentLog>,
     pub emit_change: Output<EventId>,
@@ -228,6 +230,14 @@ pub struct BasicEnvironment {
 impl Model for BasicEnvironment {
     fn init(mut self, cx: &mut Context<Self>) -> impl Future<Output = InitializedModel<Self>> + Send {
         async move {
+            // Changes up to the start time give the initial state, and the rest are replayed when they fall due
+            for (time, state) in std::mem::take(&mut self.time_series.changes) {
+                if time <= cx.time() {
+                    self.state = state;
+                } else {
+                    cx.schedule_event(time, BasicEnvironment::set_state, (state, EventId::from_scheduler())).unwrap();
+                }
+            }
             self.log(cx.time(), EventId::from_init(), self.state.clone()).await;
             self.into()
         }
@@ -240,6 +250,7 @@ impl Default for BasicEnvironment {
             element_name: String::new(),
             element_code: String::new(),
             state: BasicEnvironmentState::Normal,
+            time_series: EnvironmentTimeSeries::default(),
             next_event_index: 0,
             log_emitter: Output::default(),
             emit_change: Output::default(),
@@ -253,6 +264,11 @@ impl BasicEnvironment {
         self
     }
 
+    pub fn with_time_series(mut self, time_series: EnvironmentTimeSeries) -> Self {
+        self.time_series = time_series;
+        self
+    }
+
     pub fn set_state(&mut self, payload: (BasicEnvironmentState, EventId), cx: &mut Context<Self>) -> impl Future<Output = ()> {
         async move {
             let (state, mut event_id) = payload;
@@ -270,6 +286,14 @@ impl BasicEnvironment {
         }
     }
 
+    /// State along with the environment's name, for processes gated by several environments to report which one
+    /// stopped them.
+    pub fn get_named_state_async(&mut self) -> impl Future<Output = (String, BasicEnvironmentState)> + {
+        async move {
+            (self.element_name.clone(), self.state.clone())
+        }
+    }
+
     fn log(&mut self, now: MonotonicTime, source_event_id: EventId, event: BasicEnvironmentState) -> impl Future<Output = EventId> + Send {
         async move {
             let new_event_id = EventId(format!("{}_{:06}", self.element_code, self.next_event_index));
@@ -292,9 +316,216 @@ impl BasicEnvironment {
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub enum BasicEnvironmentState {
     Normal,
+    /// Processes keep running at the given fraction of their normal rate, e.g. `Reduced(0.6)` for haulage in rain.
+    /// Process times are stretched by the inverse of the factor.
+    Reduced(f64),
     Stopped,
 }
 
+impl BasicEnvironmentState {
+    /// Fraction of the normal process rate, between 0 and 1.
+    pub fn rate_factor(&self) -> f64 {
+        match self {
+            BasicEnvironmentState::Normal => 1.,
+            BasicEnvironmentState::Reduced(factor) => factor.clamp(0., 1.),
+            BasicEnvironmentState::Stopped => 0.,
+        }
+    }
+
+    /// A reduced state with a factor of zero stops processes, the same as `Stopped`.
+    pub fn is_stopped(&self) -> bool {
+        self.rate_factor() <= 0.
+    }
+
+    /// Process time completed over `elapsed`, at this state's rate.
+    pub fn progress(&self, elapsed: Duration) -> Duration {
+        match self {
+            BasicEnvironmentState::Normal => elapsed,
+            _ => elapsed.mul_f64(self.rate_factor()),
+        }
+    }
+
+    /// Time needed to complete `process_time_left` at this state's rate, or `None` if stopped.
+    pub fn time_to_progress(&self, process_time_left: Duration) -> Option<Duration> {
+        match self {
+            BasicEnvironmentState::Normal => Some(process_time_left),
+            _ if self.is_stopped() => None,
+            _ => {
+                // Round up, so that the process is complete by the time it is checked
+                let time = process_time_left.div_f64(self.rate_factor());
+                if self.progress(time) < process_time_left {
+                    Some(time + Duration::from_nanos(1))
+                } else {
+                    Some(time)
+                }
+            },
+        }
+    }
+}
+
+/// Parses `Normal`, `Stopped`, `Reduced(0.6)`, or a bare rate factor, where 1 is `Normal` and 0 is `Stopped`.
+impl FromStr for BasicEnvironmentState {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || format!("Invalid environment state '{}'", s);
+        let factor = match s {
+            "Normal" => return Ok(BasicEnvironmentState::Normal),
+            "Stopped" => return Ok(BasicEnvironmentState::Stopped),
+            _ => match s.strip_prefix("Reduced(").and_then(|x| x.strip_suffix(')')) {
+                Some(factor) => factor.trim().parse::<f64>().map_err(|_| invalid())?,
+                None => s.parse::<f64>().map_err(|_| invalid())?,
+            },
+        };
+        if !(0. ..=1.).contains(&factor) {
+            return Err(format!("Rate factor of '{}' must be between 0 and 1", s).into());
+        }
+        Ok(if factor == 1. {
+            BasicEnvironmentState::Normal
+        } else if factor == 0. {
+            BasicEnvironmentState::Stopped
+        } else {
+            BasicEnvironmentState::Reduced(factor)
+        })
+    }
+}
+
+impl Display for BasicEnvironmentState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            BasicEnvironmentState::Normal => write!(f, "Normal"),
+            BasicEnvironmentState::Reduced(factor) => write!(f, "Reduced({})", factor),
+            BasicEnvironmentState::Stopped => write!(f, "Stopped"),
+        }
+    }
+}
+
+/// What a process does with its work in progress when its environment stops.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum EnvironmentStopPolicy {
+    /// Freeze the remaining process time, and carry on when the environment resumes.
+    #[default]
+    Pause,
+    /// Finish the work in progress at the normal rate, but start nothing new until the environment resumes.
+    FinishThenStop,
+    /// Abandon the work in progress, returning it to the upstream stock.
+    AbortUpstream,
+    /// Abandon the work in progress, sending it to the loss stream, e.g. loads abandoned for blast clearance.
+    Scrap,
+}
+
+impl EnvironmentStopPolicy {
+    /// Checks that the policy does not abandon work in progress to an output port that is not connected, where it
+    /// would be lost without a trace. Processes check this when they update, logging the error as the reason they
+    /// fall back to `Pause`.
+    pub fn check_connected<T: Clone + Send + 'static>(&self, push_upstream: &CountedOutput<T>, push_loss: &CountedOutput<T>) -> Result<(), &'static str> {
+        match self {
+            EnvironmentStopPolicy::AbortUpstream if !push_upstream.is_connected() => Err("Stop policy AbortUpstream needs push_upstream connected, pausing instead"),
+            EnvironmentStopPolicy::Scrap if !push_loss.is_connected() => Err("Stop policy Scrap needs push_loss connected, pausing instead"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An `Output` port that counts its connections, so that a model can check on initialisation that it is connected.
+#[derive(Clone)]
+pub struct CountedOutput<T: Clone + Send + 'static> {
+    output: Output<T>,
+    connections: usize,
+}
+
+impl<T: Clone + Send + 'static> CountedOutput<T> {
+    pub fn new() -> Self {
+        CountedOutput { output: Output::new(), connections: 0 }
+    }
+
+    pub fn connect<M, F, S>(&mut self, input: F, address: impl Into<Address<M>>)
+    where
+        M: Model,
+        F: for<'a> InputFn<'a, M, T, S> + Clone,
+        S: Send + 'static,
+    {
+        self.output.connect(input, address);
+        self.connections += 1;
+    }
+
+    pub fn connect_sink<S: EventSink<T>>(&mut self, sink: &S) {
+        self.output.connect_sink(sink);
+        self.connections += 1;
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connections > 0
+    }
+
+    pub async fn send(&mut self, arg: T) {
+        self.output.send(arg).await;
+    }
+}
+
+impl<T: Clone + Send + 'static> Default for CountedOutput<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Combines the states of the environments gating a process. The process is stopped if any environment is stopped,
+/// and otherwise runs at the product of their rate factors. Also returns the names of the environments limiting the
+/// process - those stopping it, or else those reducing its rate.
+pub fn combine_environment_states(states: impl IntoIterator<Item = (String, BasicEnvironmentState)>) -> (BasicEnvironmentState, Vec<String>) {
+    let mut factor = 1.;
+    let mut stopped_by = Vec::new();
+    let mut reduced_by = Vec::new();
+    for (name, state) in states {
+        if state.is_stopped() {
+            stopped_by.push(name);
+        } else if state != BasicEnvironmentState::Normal {
+            factor *= state.rate_factor();
+            reduced_by.push(name);
+        }
+    }
+    if !stopped_by.is_empty() {
+        (BasicEnvironmentState::Stopped, stopped_by)
+    } else if !reduced_by.is_empty() {
+        (BasicEnvironmentState::Reduced(factor), reduced_by)
+    } else {
+        (BasicEnvironmentState::Normal, reduced_by)
+    }
+}
+
+/// Timestamped environment states for a `BasicEnvironment` to replay, in time order.
+#[derive(Debug, Clone, Default)]
+pub struct EnvironmentTimeSeries {
+    pub changes: Vec<(MonotonicTime, BasicEnvironmentState)>,
+}
+
+impl EnvironmentTimeSeries {
+    pub fn new(mut changes: Vec<(MonotonicTime, BasicEnvironmentState)>) -> Self {
+        changes.sort_by_key(|(time, _)| *time);
+        EnvironmentTimeSeries { changes }
+    }
+
+    /// Reads a CSV file with a header row, and columns of timestamp (e.g. `2025-01-01 06:00:00`) and state. The state
+    /// is either named (`Normal`, `Stopped`, `Reduced(0.6)`) or a rate factor between 0 and 1.
+    pub fn from_csv(path: &str) -> Result<Self, Box<dyn Error>> {
+        let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_path(path)
+            .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        let mut changes = Vec::new();
+        for (i, record) in reader.records().enumerate() {
+            let record = record.map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let (time, state) = match (record.get(0), record.get(1)) {
+                (Some(time), Some(state)) => (time, state),
+                _ => return Err(format!("{} row {}: expected 2 columns, found {}", path, i + 2, record.len()).into()),
+            };
+            let time = parse_date_time(time).map_err(|e| format!("{} row {}: {}", path, i + 2, e))?;
+            let state = state.parse::<BasicEnvironmentState>().map_err(|e| format!("{} row {}: {}", path, i + 2, e))?;
+            changes.push((time, state));
+        }
+        Ok(EnvironmentTimeSeries::new(changes))
+    }
+}
+
 #[derive(Clone)]
 pub struct BasicEnvironmentLog {
     pub time: String,
@@ -316,10 +547,7 @@ impl Serialize for BasicEnvironmentLog {
         state.serialize_field("source_event_id", &self.source_event_id)?;
         state.serialize_field("element_name", &self.element_name)?;
         state.serialize_field("element_type", &self.element_type)?;
-        state.serialize_field("event", match &self.event {
-            BasicEnvironmentState::Normal => "Normal",
-            BasicEnvironmentState::Stopped => "Stopped",
-        })?;
+        state.serialize_field("event", &self.event.to_string())?;
         state.end()
     }
 }
@@ -360,130 +588,596 @@ impl Logger for BasicEnvironmentLogger {
     }
 }
 
-pub trait Process {
-    type LogDetailsType;
+/// A shared pool of maintenance crew members. Delays which require a crew wait for one to be assigned before their
+/// fix starts. Crew members are assigned to requesters in the order they first asked.
+#[derive(WithMethods)]
+pub struct MaintenanceCrew {
+    pub element_name: String,
+    pub element_code: String,
+    pub crew_size: usize,
+    pub next_event_index: u64,
+    pub log_emitter: Output<MaintenanceCrewLog>,
+    pub emit_change: Output<EventId>,
+    assigned: Vec<String>,
+    queue: VecDeque<String>,
+}
 
-    fn pre_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model;
+impl Model for MaintenanceCrew {}
 
-    fn update_state_impl(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model;
+impl Default for MaintenanceCrew {
+    fn default() -> Self {
+        MaintenanceCrew {
+            element_name: String::new(),
+            element_code: String::new(),
+            crew_size: 1,
+            next_event_index: 0,
+            log_emitter: Output::default(),
+            emit_change: Output::default(),
+            assigned: Vec::new(),
+            queue: VecDeque::new(),
+        }
+    }
+}
 
-    fn update_state(&mut self, mut source_event_id: EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model {
+impl MaintenanceCrew {
+    /// Requests a crew member for the named requester, returning whether one has been assigned. Requesters that
+    /// can't be served yet join the queue, and should ask again when the crew emits a change.
+    pub fn request(&mut self, payload: (String, EventId), cx: &mut Context<Self>) -> impl Future<Output = bool> {
         async move {
-            self.pre_update_state(&mut source_event_id, cx).await;
-            self.update_state_impl(&mut source_event_id, cx).await;
-            self.post_update_state(&mut source_event_id, cx).await;
+            let (requester, mut event_id) = payload;
+            if self.assigned.contains(&requester) {
+                return true;
+            }
+            if !self.queue.contains(&requester) {
+                self.queue.push_back(requester.clone());
+                event_id = self.log(cx.time(), event_id, MaintenanceCrewLogType::Requested { requester: requester.clone() }).await;
+            }
+            if self.assigned.len() < self.crew_size && self.queue.front() == Some(&requester) {
+                self.queue.pop_front();
+                self.assigned.push(requester.clone());
+                self.log(cx.time(), event_id, MaintenanceCrewLogType::Assigned { requester }).await;
+                true
+            } else {
+                false
+            }
         }
     }
 
-    fn post_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model;
+    /// Returns the crew member assigned to the named requester, or withdraws its request if still queued.
+    pub fn release(&mut self, payload: (String, EventId), cx: &mut Context<Self>) -> impl Future<Output = ()> {
+        async move {
+            let (requester, mut event_id) = payload;
+            if let Some(index) = self.assigned.iter().position(|x| *x == requester) {
+                self.assigned.remove(index);
+                event_id = self.log(cx.time(), event_id, MaintenanceCrewLogType::Released { requester }).await;
+                self.emit_change.send(event_id).await;
+            } else {
+                self.queue.retain(|x| *x != requester);
+            }
+        }
+    }
 
-    fn log(&mut self, now: MonotonicTime, source_event: EventId, details: Self::LogDetailsType) -> impl Future<Output = EventId>;
+    fn log(&mut self, now: MonotonicTime, source_event_id: EventId, event: MaintenanceCrewLogType) -> impl Future<Output = EventId> + Send {
+        async move {
+            let new_event_id = EventId(format!("{}_{:06}", self.element_code, self.next_event_index));
+            let log = MaintenanceCrewLog {
+                time: now.to_string(),
+                event_id: new_event_id.clone(),
+                source_event_id,
+                element_name: self.element_name.clone(),
+                element_type: "MaintenanceCrew".to_string(),
+                event,
+                available: self.crew_size.saturating_sub(self.assigned.len()),
+                queue_length: self.queue.len(),
+            };
+            self.log_emitter.send(log).await;
+            self.next_event_index += 1;
 
+            new_event_id
+        }
+    }
 }
 
-pub trait Logger {
-    type RecordType: Serialize + Send + 'static;
-    fn get_name(&self) -> &String;
-    fn get_buffer(self) -> EventQueue<Self::RecordType>;
-    fn write_csv(self, dir: String) -> Result<(), Box<dyn Error>>
+#[derive(Debug, Clone)]
+pub enum MaintenanceCrewLogType {
+    Requested { requester: String },
+    Assigned { requester: String },
+    Released { requester: String },
+}
+
+#[derive(Clone)]
+pub struct MaintenanceCrewLog {
+    pub time: String,
+    pub event_id: EventId,
+    pub source_event_id: EventId,
+    pub element_name: String,
+    pub element_type: String,
+    pub event: MaintenanceCrewLogType,
+    pub available: usize,
+    pub queue_length: usize,
+}
+
+impl Serialize for MaintenanceCrewLog {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
-        Self: Sized,
+        S: serde::Serializer,
     {
-        let file = File::create(format!("{}/{}.csv", dir, self.get_name()))?;
-        let mut writer = WriterBuilder::new().has_headers(true).from_writer(file);
-        self.get_buffer().into_reader().for_each(|log| {
-            writer
-                .serialize(log)
-                .expect("Failed to write log record to CSV file");
-        });
-        writer.flush()?;
-        Ok(()) 
+        let mut state = serializer.serialize_struct("MaintenanceCrewLog", 9)?;
+        state.serialize_field("time", &self.time)?;
+        state.serialize_field("event_id", &self.event_id)?;
+        state.serialize_field("source_event_id", &self.source_event_id)?;
+        state.serialize_field("element_name", &self.element_name)?;
+        state.serialize_field("element_type", &self.element_type)?;
+        let (event, requester) = match &self.event {
+            MaintenanceCrewLogType::Requested { requester } => ("Requested", requester),
+            MaintenanceCrewLogType::Assigned { requester } => ("Assigned", requester),
+            MaintenanceCrewLogType::Released { requester } => ("Released", requester),
+        };
+        state.serialize_field("event", event)?;
+        state.serialize_field("requester", requester)?;
+        state.serialize_field("available", &self.available)?;
+        state.serialize_field("queue_length", &self.queue_length)?;
+        state.end()
     }
-    fn new(name: &str) -> Self;
 }
 
-pub trait CustomComponentConnection {
-    fn connect_components(a: &mut Self, b: &mut Self, n: Option<usize>) -> Result<(), Box<dyn ::std::error::Error>>;
+pub struct MaintenanceCrewLogger {
+    pub name: String,
+    pub buffer: EventQueue<MaintenanceCrewLog>,
 }
 
-pub trait CustomLoggerConnection {
-    type ComponentType;
-    fn connect_logger(a: &mut Self, b: &mut Self::ComponentType, n: Option<usize>) -> Result<(), Box<dyn ::std::error::Error>>;
+impl Logger for MaintenanceCrewLogger {
+    type RecordType = MaintenanceCrewLog;
+
+    fn new(name: &str) -> Self {
+        MaintenanceCrewLogger {
+            name: name.into(),
+            buffer: EventQueue::new(),
+        }
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn get_buffer(self) -> EventQueue<Self::RecordType> {
+        self.buffer
+    }
 }
 
-#[macro_export]
-macro_rules! register_component_arms {
-    ($component:ident, $sim_init:ident, $($variant:ident),* $(,)?) => {
-        match $component {
-            $(
-                ComponentModel::$variant(a, mb) => {
-                    let name = a.element_name.clone();
-                    let addr = mb.address();
-                    $sim_init = $sim_init.add_model(a, mb, name);
-                }
-            ),*
-            _ => {
-                panic!("Component type not implemented for registration");
-            }
+/// A shift of a daily roster, e.g. a 12-hour day shift from 06:00.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shift {
+    pub name: String,
+    /// Start of the shift, in hours after midnight.
+    pub start_hour: f64,
+    /// Length of the shift in hours, up to 24. Shifts may run past midnight.
+    pub duration_hours: f64,
+}
 
-        }
-    };
+/// An operating calendar which drives connected environments and processes into a non-working state outside of its
+/// shifts. Shifts repeat each day, except on non-working weekdays and holidays. Each shift starts with a crew
+/// changeover, during which no work is done.
+#[derive(WithMethods)]
+pub struct ShiftCalendar {
+    pub element_name: String,
+    pub element_code: String,
+    pub shifts: Vec<Shift>,
+    pub changeover: Duration,
+    /// Days of the week without shifts, Monday = 0.
+    pub non_working_weekdays: Vec<u32>,
+    /// Days without shifts, given as any time on the day.
+    pub holidays: Vec<MonotonicTime>,
+    pub state: BasicEnvironmentState,
+    pub next_event_index: u64,
+    pub log_emitter: Output<ShiftCalendarLog>,
+    pub emit_change: Output<EventId>,
+    pub emit_state: Output<(BasicEnvironmentState, EventId)>,
 }
 
-#[macro_export]
-macro_rules! get_address_arms {
-    ($component:ident, $($variant:ident),* $(,)?) => {
-        match $component {
-            $(
-                ComponentModel::$variant(_, mb) => ComponentModelAddress::$variant(mb.address()),
-            )*
-            _ => {
-                panic!("Component type not implemented for address retrieval");
-            }
+impl Model for ShiftCalendar {
+    fn init(mut self, cx: &mut Context<Self>) -> impl Future<Output = InitializedModel<Self>> + Send {
+        async move {
+            let source_event_id = EventId::from_init();
+            self.state = self.state_at(cx.time());
+            self.emit_state.send((self.state.clone(), source_event_id.clone())).await;
+            self.schedule_next_boundary(cx);
+            self.into()
         }
-    };
+    }
 }
 
-#[macro_export]
-macro_rules! connect_logger_arms {
-    (
-        $a:ident, $b:ident, $n:ident, $(
-            $logger_variant:ident => [ $($component_variant:ident),* $(,)? ]
-        ),* $(,)?
-    ) => {
-        match ($a, $b, $n) {
-            $($(
-                (ComponentLogger::$logger_variant(a), ComponentModel::$component_variant(b, _), _) => {
-                    b.log_emitter.connect_sink(&a.buffer);
-                    Ok(())
-                },
-            )*)*
-            ($a, $b, $n) => <ComponentLogger as CustomLoggerConnection>::connect_logger($a, $b, $n)
+impl Default for ShiftCalendar {
+    fn default() -> Self {
+        ShiftCalendar {
+            element_name: String::new(),
+            element_code: String::new(),
+            shifts: Vec::new(),
+            changeover: Duration::ZERO,
+            non_working_weekdays: Vec::new(),
+            holidays: Vec::new(),
+            state: BasicEnvironmentState::Stopped,
+            next_event_index: 0,
+            log_emitter: Output::default(),
+            emit_change: Output::default(),
+            emit_state: Output::default(),
         }
-    };
+    }
 }
-// Components and loggers enums must be defined together as logger enum connector method requires the components enum
-#[macro_export]
-macro_rules! define_model_enums {
-    (
-        $(#[$components_enum_meta:meta])*
-        pub enum $ComponentModel:ident {
-          $(
-            $(#[$components_var_meta:meta])*
-            $R:ident $( ( $RT:ty, $RT2:ty ) )?
-          ),* $(,)?
-        }
 
-        $(#[$components_address_enum_meta:meta])*
-        pub enum $ComponentModelAddress:ident {
-        }
+const SECS_PER_DAY: i64 = 86400;
 
-        $(#[$logger_enum_meta:meta])*
-        pub enum $ComponentLogger:ident {
-            $(
-                $(#[$logger_var_meta:meta])*
-                $U:ident $( ( $UT:ty ) )?
-            ),* $(,)?
+impl ShiftCalendar {
+    /// Adds a shift starting `start_hour` hours after midnight (from 0, and before 24), lasting `duration_hours`
+    /// (more than 0, up to 24).
+    pub fn with_shift(mut self, name: &str, start_hour: f64, duration_hours: f64) -> Result<Self, Box<dyn Error>> {
+        if !(0. ..24.).contains(&start_hour) {
+            return Err(format!("Shift '{}' must start from 0 to before 24 hours after midnight, not {}", name, start_hour).into());
+        }
+        if !(duration_hours > 0. && duration_hours <= 24.) {
+            return Err(format!("Shift '{}' must last more than 0 and up to 24 hours, not {}", name, duration_hours).into());
+        }
+        self.shifts.push(Shift { name: name.into(), start_hour, duration_hours });
+        Ok(self)
+    }
+
+    pub fn with_changeover(mut self, changeover: Duration) -> Self {
+        self.changeover = changeover;
+        self
+    }
+
+    pub fn with_non_working_weekdays(mut self, weekdays: Vec<u32>) -> Self {
+        self.non_working_weekdays = weekdays;
+        self
+    }
+
+    pub fn with_holiday(mut self, day: MonotonicTime) -> Self {
+        self.holidays.push(day);
+        self
+    }
+
+    pub fn get_state_async(&mut self) -> impl Future<Output = BasicEnvironmentState> + {
+        async move {
+            self.state.clone()
+        }
+    }
+
+    /// State along with the environment's name, for processes gated by several environments to report which one
+    /// stopped them.
+    pub fn get_named_state_async(&mut self) -> impl Future<Output = (String, BasicEnvironmentState)> + {
+        async move {
+            (self.element_name.clone(), self.state.clone())
+        }
+    }
+
+    fn is_working_day(&self, day: i64) -> bool {
+        // 1970-01-01 was a Thursday, three days after the start of the week
+        let weekday = (day + 3).rem_euclid(7) as u32;
+        !self.non_working_weekdays.contains(&weekday)
+            && !self.holidays.iter().any(|x| x.as_secs().div_euclid(SECS_PER_DAY) == day)
+    }
+
+    /// Boundaries of the shifts starting on the given day (days since 1970-01-01), in time order.
+    fn boundaries(&self, day: i64) -> Vec<(MonotonicTime, ShiftCalendarLogType)> {
+        let mut boundaries = Vec::new();
+        if !self.is_working_day(day) {
+            return boundaries;
+        }
+        let midnight = MonotonicTime::new(day * SECS_PER_DAY, 0).unwrap();
+        for shift in self.shifts.iter() {
+            let start = midnight + Duration::from_secs_f64(shift.start_hour * 3600.);
+            let end = start + Duration::from_secs_f64(shift.duration_hours * 3600.);
+            if !self.changeover.is_zero() {
+                boundaries.push((start, ShiftCalendarLogType::ChangeoverStart { shift: shift.name.clone() }));
+            }
+            boundaries.push(((start + self.changeover).min(end), ShiftCalendarLogType::ShiftStart { shift: shift.name.clone() }));
+            boundaries.push((end, ShiftCalendarLogType::ShiftEnd { shift: shift.name.clone() }));
+        }
+        // Shifts ending at the same time as the next starts are logged first
+        boundaries.sort_by_key(|(time, event)| (*time, !matches!(event, ShiftCalendarLogType::ShiftEnd { .. })));
+        boundaries
+    }
+
+    /// `Normal` during the working part of a shift, otherwise `Stopped`.
+    pub fn state_at(&self, time: MonotonicTime) -> BasicEnvironmentState {
+        let today = time.as_secs().div_euclid(SECS_PER_DAY);
+        let is_working = (today - 1..=today).any(|day| {
+            let mut is_working = false;
+            for (boundary_time, event) in self.boundaries(day) {
+                if boundary_time > time {
+                    break;
+                }
+                match event {
+                    ShiftCalendarLogType::ShiftStart { .. } => is_working = true,
+                    _ => is_working = false,
+                }
+            }
+            is_working
+        });
+        if is_working { BasicEnvironmentState::Normal } else { BasicEnvironmentState::Stopped }
+    }
+
+    /// Time of the next shift boundary after `time`, if there are any more shifts. After the last holiday, there is
+    /// a working day within a week unless every weekday is non-working, so the search stops there.
+    fn next_boundary(&self, time: MonotonicTime) -> Option<MonotonicTime> {
+        let today = time.as_secs().div_euclid(SECS_PER_DAY);
+        let last_holiday = self.holidays.iter().map(|x| x.as_secs().div_euclid(SECS_PER_DAY)).max().unwrap_or(today);
+        (today - 1..=today.max(last_holiday) + 7).find_map(|day| {
+            self.boundaries(day).into_iter().map(|(boundary_time, _)| boundary_time).find(|x| *x > time)
+        })
+    }
+
+    fn schedule_next_boundary(&mut self, cx: &mut Context<Self>) {
+        if let Some(next_time) = self.next_boundary(cx.time()) {
+            cx.schedule_event(next_time, ShiftCalendar::on_boundary, ()).unwrap();
+        }
+    }
+
+    fn on_boundary(&mut self, _: (), cx: &mut Context<Self>) -> impl Future<Output = ()> {
+        async move {
+            let time = cx.time();
+            let today = time.as_secs().div_euclid(SECS_PER_DAY);
+            let mut source_event_id = EventId::from_scheduler();
+            let events: Vec<ShiftCalendarLogType> = (today - 1..=today)
+                .flat_map(|day| self.boundaries(day))
+                .filter(|(boundary_time, _)| *boundary_time == time)
+                .map(|(_, event)| event)
+                .collect();
+            for event in events {
+                source_event_id = self.log(time, source_event_id, event).await;
+            }
+            let state = self.state_at(time);
+            if state != self.state {
+                self.state = state;
+                self.emit_state.send((self.state.clone(), source_event_id.clone())).await;
+                self.emit_change.send(source_event_id).await;
+            }
+            self.schedule_next_boundary(cx);
+        }
+    }
+
+    fn log(&mut self, now: MonotonicTime, source_event_id: EventId, event: ShiftCalendarLogType) -> impl Future<Output = EventId> + Send {
+        async move {
+            let new_event_id = EventId(format!("{}_{:06}", self.element_code, self.next_event_index));
+            let log = ShiftCalendarLog {
+                time: now.to_string(),
+                event_id: new_event_id.clone(),
+                source_event_id,
+                element_name: self.element_name.clone(),
+                element_type: "ShiftCalendar".to_string(),
+                event,
+            };
+            self.log_emitter.send(log).await;
+            self.next_event_index += 1;
+
+            new_event_id
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShiftCalendarLogType {
+    ChangeoverStart { shift: String },
+    ShiftStart { shift: String },
+    ShiftEnd { shift: String },
+}
+
+#[derive(Clone)]
+pub struct ShiftCalendarLog {
+    pub time: String,
+    pub event_id: EventId,
+    pub source_event_id: EventId,
+    pub element_name: String,
+    pub element_type: String,
+    pub event: ShiftCalendarLogType,
+}
+
+impl Serialize for ShiftCalendarLog {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_struct("ShiftCalendarLog", 7)?;
+        state.serialize_field("time", &self.time)?;
+        state.serialize_field("event_id", &self.event_id)?;
+        state.serialize_field("source_event_id", &self.source_event_id)?;
+        state.serialize_field("element_name", &self.element_name)?;
+        state.serialize_field("element_type", &self.element_type)?;
+        let (event, shift) = match &self.event {
+            ShiftCalendarLogType::ChangeoverStart { shift } => ("ChangeoverStart", shift),
+            ShiftCalendarLogType::ShiftStart { shift } => ("ShiftStart", shift),
+            ShiftCalendarLogType::ShiftEnd { shift } => ("ShiftEnd", shift),
+        };
+        state.serialize_field("event", event)?;
+        state.serialize_field("shift", shift)?;
+        state.end()
+    }
+}
+
+pub struct ShiftCalendarLogger {
+    pub name: String,
+    pub buffer: EventQueue<ShiftCalendarLog>,
+}
+
+impl Logger for ShiftCalendarLogger {
+    type RecordType = ShiftCalendarLog;
+
+    fn new(name: &str) -> Self {
+        ShiftCalendarLogger {
+            name: name.into(),
+            buffer: EventQueue::new(),
+        }
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn get_buffer(self) -> EventQueue<Self::RecordType> {
+        self.buffer
+    }
+}
+
+/// Applies a delay mode change to a process, as a scheduled event.
+pub fn modify_delay_modes<M: HasDelayModes>(model: &mut M, change: DelayModeChange) {
+    model.delay_modes_mut().modify(change);
+}
+
+/// Schedules a delay mode change for a process. The process is updated either side of the change, so that its delay
+/// counters are up to date before the change and it reacts to the change straight away.
+pub fn schedule_delay_mode_change<M>(
+    scheduler: &mut Scheduler,
+    time: MonotonicTime,
+    change: DelayModeChange,
+    source_event_id: EventId,
+    addr: &Address<M>,
+) -> Result<(), Box<dyn Error>> where M: Model + Process + HasDelayModes {
+    scheduler.schedule_event(time, <M as Process>::update_state, source_event_id.clone(), addr.clone())?;
+    scheduler.schedule_event(time, modify_delay_modes::<M>, change, addr.clone())?;
+    scheduler.schedule_event(time, <M as Process>::update_state, source_event_id, addr.clone())?;
+    Ok(())
+}
+
+pub trait Process {
+    type LogDetailsType;
+
+    fn pre_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model;
+
+    fn update_state_impl(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model;
+
+    fn update_state(&mut self, mut source_event_id: EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model {
+        async move {
+            self.pre_update_state(&mut source_event_id, cx).await;
+            self.update_state_impl(&mut source_event_id, cx).await;
+            self.post_update_state(&mut source_event_id, cx).await;
+        }
+    }
+
+    fn post_update_state(&mut self, source_event_id: &mut EventId, cx: &mut Context<Self>) -> impl Future<Output = ()> + Send where Self: Model;
+
+    fn log(&mut self, now: MonotonicTime, source_event: EventId, details: Self::LogDetailsType) -> impl Future<Output = EventId>;
+
+}
+
+pub trait Logger {
+    type RecordType: Serialize + Send + 'static;
+    fn get_name(&self) -> &String;
+    fn get_buffer(self) -> EventQueue<Self::RecordType>;
+    fn write_csv(self, dir: String) -> Result<(), Box<dyn Error>>
+    where
+        Self: Sized,
+    {
+        let file = File::create(format!("{}/{}.csv", dir, self.get_name()))?;
+        let mut writer = WriterBuilder::new().has_headers(true).from_writer(file);
+        self.get_buffer().into_reader().for_each(|log| {
+            writer
+                .serialize(log)
+                .expect("Failed to write log record to CSV file");
+        });
+        writer.flush()?;
+        Ok(()) 
+    }
+    fn new(name: &str) -> Self;
+}
+
+pub trait CustomComponentConnection {
+    fn connect_components(a: &mut Self, b: &mut Self, n: Option<usize>) -> Result<(), Box<dyn ::std::error::Error>>;
+}
+
+pub trait CustomLoggerConnection {
+    type ComponentType;
+    fn connect_logger(a: &mut Self, b: &mut Self::ComponentType, n: Option<usize>) -> Result<(), Box<dyn ::std::error::Error>>;
+}
+
+#[macro_export]
+macro_rules! schedule_delay_mode_change_arms {
+    ($addr:ident, $scheduler:ident, $time:ident, $change:ident, $source_event_id:ident, $($variant:ident),* $(,)?) => {
+        match $addr {
+            $(
+                ComponentModelAddress::$variant(addr) => {
+                    $crate::core::schedule_delay_mode_change($scheduler, $time, $change, $source_event_id, addr)
+                }
+            ),*
+            _ => {
+                Err(format!("Delay mode changes not implemented for {}", $addr).into())
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! register_component_arms {
+    ($component:ident, $sim_init:ident, $($variant:ident),* $(,)?) => {
+        match $component {
+            $(
+                ComponentModel::$variant(a, mb) => {
+                    let name = a.element_name.clone();
+                    let addr = mb.address();
+                    $sim_init = $sim_init.add_model(a, mb, name);
+                }
+            ),*
+            _ => {
+                panic!("Component type not implemented for registration");
+            }
+
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! get_address_arms {
+    ($component:ident, $($variant:ident),* $(,)?) => {
+        match $component {
+            $(
+                ComponentModel::$variant(_, mb) => ComponentModelAddress::$variant(mb.address()),
+            )*
+            _ => {
+                panic!("Component type not implemented for address retrieval");
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! connect_logger_arms {
+    (
+        $a:ident, $b:ident, $n:ident, $(
+            $logger_variant:ident => [ $($component_variant:ident),* $(,)? ]
+        ),* $(,)?
+    ) => {
+        match ($a, $b, $n) {
+            $($(
+                (ComponentLogger::$logger_variant(a), ComponentModel::$component_variant(b, _), _) => {
+                    b.log_emitter.connect_sink(&a.buffer);
+                    Ok(())
+                },
+            )*)*
+            ($a, $b, $n) => <ComponentLogger as CustomLoggerConnection>::connect_logger($a, $b, $n)
+        }
+    };
+}
+// Components and loggers enums must be defined together as logger enum connector method requires the components enum
+#[macro_export]
+macro_rules! define_model_enums {
+    (
+        $(#[$components_enum_meta:meta])*
+        pub enum $ComponentModel:ident {
+          $(
+            $(#[$components_var_meta:meta])*
+            $R:ident $( ( $RT:ty, $RT2:ty ) )?
+          ),* $(,)?
+        }
+
+        $(#[$components_address_enum_meta:meta])*
+        pub enum $ComponentModelAddress:ident {
+        }
+
+        $(#[$logger_enum_meta:meta])*
+        pub enum $ComponentLogger:ident {
+            $(
+                $(#[$logger_var_meta:meta])*
+                $U:ident $( ( $UT:ty ) )?
+            ),* $(,)?
         }
 
         $(#[$sch_event_config_enum_meta:meta])*
@@ -537,6 +1231,9 @@ macro_rules! define_model_enums {
             StringParallelProcess($crate::components::discrete::DiscreteParallelProcess<(), Option<String>, String, String>, $crate::nexosim::Mailbox<$crate::components::discrete::DiscreteParallelProcess<(), Option<String>, String, String>>),
             StringSource($crate::components::discrete::DiscreteSource<String, String, StringItemFactory>, $crate::nexosim::Mailbox<$crate::components::discrete::DiscreteSource<String, String, StringItemFactory>>),
             StringSink($crate::components::discrete::DiscreteSink<(), Option<String>, String>, $crate::nexosim::Mailbox<$crate::components::discrete::DiscreteSink<(), Option<String>, String>>),
+            StringKeyedStock($crate::components::discrete::KeyedDiscreteStock<String>, $crate::nexosim::Mailbox<$crate::components::discrete::KeyedDiscreteStock<String>>),
+            StringKeyedProcess($crate::components::discrete::DiscreteProcess<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>, $crate::nexosim::Mailbox<$crate::components::discrete::DiscreteProcess<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>>),
+            StringKeyedParallelProcess($crate::components::discrete::DiscreteParallelProcess<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>, $crate::nexosim::Mailbox<$crate::components::discrete::DiscreteParallelProcess<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>>),
             
             F64ContainerStock($crate::components::discrete::DiscreteStock<F64Container>, $crate::nexosim::Mailbox<$crate::components::discrete::DiscreteStock<F64Container>>),
             F64ContainerProcess($crate::components::discrete::DiscreteProcess<(), Option<F64Container>, F64Container, F64Container>, $crate::nexosim::Mailbox<$crate::components::discrete::DiscreteProcess<(), Option<F64Container>, F64Container, F64Container>>),
@@ -555,6 +1252,8 @@ macro_rules! define_model_enums {
             Vector3ContainerUnloadProcess($crate::components::vector_container::ContainerUnloadingProcess<Vector3Container, Vector3>, $crate::nexosim::Mailbox<$crate::components::vector_container::ContainerUnloadingProcess<Vector3Container, Vector3>>),
             
             BasicEnvironment(BasicEnvironment, $crate::nexosim::Mailbox<BasicEnvironment>),
+            MaintenanceCrew(MaintenanceCrew, $crate::nexosim::Mailbox<MaintenanceCrew>),
+            ShiftCalendar(ShiftCalendar, $crate::nexosim::Mailbox<ShiftCalendar>),
             $(
                 $(#[$components_var_meta])*
                 $R $( ( $RT, $RT2 ) )?
@@ -573,6 +1272,7 @@ macro_rules! define_model_enums {
                         a.state_emitter.connect($crate::components::vector::VectorProcess::update_state, bd.address());
                         b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, ad.address());
                         b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, ad.address());
+                        b.push_upstream.connect($crate::components::vector::VectorStock::add, ad.address());
                         Ok(())
                     },
                     ($ComponentModel::F64Process(a, ad), $ComponentModel::F64Stock(b, bd), _) => {
@@ -583,7 +1283,7 @@ macro_rules! define_model_enums {
                     },
                     ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Process(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorProcess::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
                         Ok(())
                     }
                     // F64Source
@@ -727,6 +1427,7 @@ macro_rules! define_model_enums {
                         a.state_emitter.connect($crate::components::vector::VectorProcess::update_state, bd.address());
                         b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, ad.address());
                         b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, ad.address());
+                        b.push_upstream.connect($crate::components::vector::VectorStock::add, ad.address());
                         Ok(())
                     },
                     ($ComponentModel::Vector3Process(a, ad), $ComponentModel::Vector3Stock(b, bd), _) => {
@@ -823,352 +1524,834 @@ macro_rules! define_model_enums {
                         b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, amb.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3Splitter1(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
-                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
-                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
-                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                    ($ComponentModel::Vector3Splitter1(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
+                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
+                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
+                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                        Ok(())
+                    },
+                    // Vector3Splitter2
+                    ($ComponentModel::Vector3Stock(a, amb), $ComponentModel::Vector3Splitter2(b, bmb), _) => {
+                        a.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, bmb.address());
+                        b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, amb.address());
+                        b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, amb.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3Splitter2(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
+                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
+                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
+                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                        Ok(())
+                    },
+                    // Vector3Splitter3
+                    ($ComponentModel::Vector3Stock(a, amb), $ComponentModel::Vector3Splitter3(b, bmb), _) => {
+                        a.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, bmb.address());
+                        b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, amb.address());
+                        b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, amb.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3Splitter3(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
+                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
+                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
+                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                        Ok(())
+                    },
+                    // Vector3Splitter4
+                    ($ComponentModel::Vector3Stock(a, amb), $ComponentModel::Vector3Splitter4(b, bmb), _) => {
+                        a.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, bmb.address());
+                        b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, amb.address());
+                        b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, amb.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3Splitter4(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
+                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
+                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
+                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                        Ok(())
+                    },
+                    // Vector3Splitter5
+                    ($ComponentModel::Vector3Stock(a, amb), $ComponentModel::Vector3Splitter5(b, bmb), _) => {
+                        a.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, bmb.address());
+                        b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, amb.address());
+                        b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, amb.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3Splitter5(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
+                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
+                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
+                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                        Ok(())
+                    },
+
+                    // Discrete
+                    ($ComponentModel::StringStock(a, ad), $ComponentModel::StringProcess(b, bd), _) => {
+                        a.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, bd.address());
+                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, ad.address());
+                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, ad.address());
+                        b.push_upstream.connect($crate::components::discrete::DiscreteStock::add, ad.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringProcess(a, ad), $ComponentModel::StringStock(b, bd), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, ad.address());
+                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bd.address());
+                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringStock(a, am), $ComponentModel::StringParallelProcess(b, bm), _) => {
+                        a.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, bm.address());
+                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
+                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                        b.push_upstream.connect($crate::components::discrete::DiscreteStock::add, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringParallelProcess(a, am), $ComponentModel::StringStock(b, bm), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, am.address());
+                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
+                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringSource(a, am), $ComponentModel::StringStock(b, bm), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteSource::update_state, am.address());
+                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
+                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringStock(a, am), $ComponentModel::StringSink(b, bm), _) => {
+                        a.state_emitter.connect($crate::components::discrete::DiscreteSink::update_state, bm.address());
+                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
+                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                        Ok(())
+                    },
+                    // Keyed discrete stocks, withdrawn from with an ItemRequest
+                    ($ComponentModel::StringKeyedStock(a, ad), $ComponentModel::StringKeyedProcess(b, bd), _) => {
+                        a.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, bd.address());
+                        b.req_upstream.connect($crate::components::discrete::KeyedDiscreteStock::get_state_async, ad.address());
+                        b.withdraw_upstream.connect($crate::components::discrete::KeyedDiscreteStock::remove, ad.address());
+                        b.push_upstream.connect($crate::components::discrete::KeyedDiscreteStock::add, ad.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringKeyedProcess(a, ad), $ComponentModel::StringKeyedStock(b, bd), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, ad.address());
+                        a.req_downstream.connect($crate::components::discrete::KeyedDiscreteStock::get_state_async, bd.address());
+                        a.push_downstream.connect($crate::components::discrete::KeyedDiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringKeyedProcess(a, ad), $ComponentModel::StringStock(b, bd), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, ad.address());
+                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bd.address());
+                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringProcess(a, ad), $ComponentModel::StringKeyedStock(b, bd), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, ad.address());
+                        a.req_downstream.connect($crate::components::discrete::KeyedDiscreteStock::get_state_async, bd.address());
+                        a.push_downstream.connect($crate::components::discrete::KeyedDiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringKeyedStock(a, ad), $ComponentModel::StringKeyedParallelProcess(b, bd), _) => {
+                        a.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, bd.address());
+                        b.req_upstream.connect($crate::components::discrete::KeyedDiscreteStock::get_state_async, ad.address());
+                        b.withdraw_upstream.connect($crate::components::discrete::KeyedDiscreteStock::remove, ad.address());
+                        b.push_upstream.connect($crate::components::discrete::KeyedDiscreteStock::add, ad.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringKeyedParallelProcess(a, ad), $ComponentModel::StringKeyedStock(b, bd), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, ad.address());
+                        a.req_downstream.connect($crate::components::discrete::KeyedDiscreteStock::get_state_async, bd.address());
+                        a.push_downstream.connect($crate::components::discrete::KeyedDiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringKeyedParallelProcess(a, ad), $ComponentModel::StringStock(b, bd), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, ad.address());
+                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bd.address());
+                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringParallelProcess(a, ad), $ComponentModel::StringKeyedStock(b, bd), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, ad.address());
+                        a.req_downstream.connect($crate::components::discrete::KeyedDiscreteStock::get_state_async, bd.address());
+                        a.push_downstream.connect($crate::components::discrete::KeyedDiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringSource(a, am), $ComponentModel::StringKeyedStock(b, bm), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteSource::update_state, am.address());
+                        a.req_downstream.connect($crate::components::discrete::KeyedDiscreteStock::get_state_async, bm.address());
+                        a.push_downstream.connect($crate::components::discrete::KeyedDiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+
+                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerProcess(b, bm), _) => {
+                        a.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, bm.address());
+                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
+                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                        b.push_upstream.connect($crate::components::discrete::DiscreteStock::add, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerProcess(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, am.address());
+                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
+                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerParallelProcess(b, bm), _) => {
+                        a.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, bm.address());
+                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
+                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                        b.push_upstream.connect($crate::components::discrete::DiscreteStock::add, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerParallelProcess(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, am.address());
+                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
+                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerSource(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
+                        b.state_emitter.connect($crate::components::discrete::DiscreteSource::<Vector3Container, Vector3Container, Vector3ContainerFactory>::update_state, am.address());
+                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::<Vector3Container>::get_state_async, bm.address());
+                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::<Vector3Container>::add, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerSink(b, bm), _) => {
+                        a.state_emitter.connect($crate::components::discrete::DiscreteSink::<(), Option<Vector3Container>, Vector3Container>::update_state, bm.address());
+                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::<Vector3Container>::get_state_async, am.address());
+                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::<Vector3Container>::remove, am.address());
+                        Ok(())
+                    },
+
+                    ($ComponentModel::Vector3Stock(a, am), $ComponentModel::Vector3ContainerLoadProcess(b, bm), _) => {
+                        a.state_emitter.connect($crate::components::vector_container::ContainerLoadingProcess::update_state, bm.address());
+                        b.req_us_resource.connect($crate::components::vector::VectorStock::get_state_async, am.address());
+                        b.withdraw_us_resource.connect($crate::components::vector::VectorStock::remove, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerLoadProcess(b, bm), _) => {
+                        a.state_emitter.connect($crate::components::vector_container::ContainerLoadingProcess::update_state, bm.address());
+                        b.req_us_containers.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
+                        b.withdraw_us_containers.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerLoadProcess(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
+                        b.state_emitter.connect($crate::components::vector_container::ContainerLoadingProcess::update_state, am.address());
+                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
+                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerUnloadProcess(b, bm), _) => {
+                        a.state_emitter.connect($crate::components::vector_container::ContainerUnloadingProcess::update_state, bm.address());
+                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
+                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerUnloadProcess(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
+                        b.state_emitter.connect($crate::components::vector_container::ContainerUnloadingProcess::update_state, am.address());
+                        a.req_ds_containers.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
+                        a.push_ds_containers.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerUnloadProcess(a, am), $ComponentModel::Vector3Stock(b, bm), _) => {
+                        b.state_emitter.connect($crate::components::vector_container::ContainerUnloadingProcess::update_state, am.address());
+                        a.req_ds_resource.connect($crate::components::vector::VectorStock::get_state_async, bm.address());
+                        a.push_ds_resource.connect($crate::components::vector::VectorStock::add, bm.address());
+                        Ok(())
+                    },
+                    /**
+                     * Environments
+                     */
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Process(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorProcess::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Source(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSource::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Sink(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSink::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner1(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner2(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner3(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner4(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner5(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter1(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter2(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter3(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter4(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter5(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Process(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorProcess::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Source(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSource::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Sink(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSink::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner1(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner2(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner3(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner4(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner5(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter1(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter2(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter3(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter4(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter5(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteProcess::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringSource(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteSource::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringSink(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteSink::<(), Option<String>, String>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringParallelProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteParallelProcess::<(), Option<String>, String, String>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringKeyedProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteProcess::<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringKeyedParallelProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteParallelProcess::<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteProcess::<(), Option<Vector3Container>, Vector3Container, Vector3Container>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerSource(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteSource::<Vector3Container, Vector3Container, Vector3ContainerFactory>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerSink(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteSink::<(), Option<Vector3Container>, Vector3Container>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerLoadProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector_container::ContainerLoadingProcess::<Vector3Container, Vector3>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerUnloadProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector_container::ContainerUnloadingProcess::<Vector3Container, Vector3>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::BasicEnvironment::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Process(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorProcess::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Source(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSource::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Sink(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSink::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Combiner1(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Combiner2(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Combiner3(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Combiner4(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Combiner5(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Splitter1(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Splitter2(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Splitter3(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    // Vector3Splitter2
-                    ($ComponentModel::Vector3Stock(a, amb), $ComponentModel::Vector3Splitter2(b, bmb), _) => {
-                        a.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, bmb.address());
-                        b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, amb.address());
-                        b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, amb.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Splitter4(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3Splitter2(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
-                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
-                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
-                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::F64Splitter5(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    // Vector3Splitter3
-                    ($ComponentModel::Vector3Stock(a, amb), $ComponentModel::Vector3Splitter3(b, bmb), _) => {
-                        a.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, bmb.address());
-                        b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, amb.address());
-                        b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, amb.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Process(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorProcess::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3Splitter3(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
-                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
-                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
-                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Source(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSource::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    // Vector3Splitter4
-                    ($ComponentModel::Vector3Stock(a, amb), $ComponentModel::Vector3Splitter4(b, bmb), _) => {
-                        a.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, bmb.address());
-                        b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, amb.address());
-                        b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, amb.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Sink(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSink::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3Splitter4(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
-                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
-                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
-                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Combiner1(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    // Vector3Splitter5
-                    ($ComponentModel::Vector3Stock(a, amb), $ComponentModel::Vector3Splitter5(b, bmb), _) => {
-                        a.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, bmb.address());
-                        b.req_upstream.connect($crate::components::vector::VectorStock::get_state_async, amb.address());
-                        b.withdraw_upstream.connect($crate::components::vector::VectorStock::remove, amb.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Combiner2(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3Splitter5(a, amb), $ComponentModel::Vector3Stock(b, bmb), Some(n)) => {
-                        b.state_emitter.connect($crate::components::vector::VectorSplitter::update_state, amb.address());
-                        a.req_downstreams[n].connect($crate::components::vector::VectorStock::get_state_async, bmb.address());
-                        a.push_downstreams[n].connect($crate::components::vector::VectorStock::add, bmb.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Combiner3(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-
-                    // Discrete
-                    ($ComponentModel::StringStock(a, ad), $ComponentModel::StringProcess(b, bd), _) => {
-                        a.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, bd.address());
-                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, ad.address());
-                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, ad.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Combiner4(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::StringProcess(a, ad), $ComponentModel::StringStock(b, bd), _) => {
-                        b.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, ad.address());
-                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bd.address());
-                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bd.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Combiner5(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::StringStock(a, am), $ComponentModel::StringParallelProcess(b, bm), _) => {
-                        a.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, bm.address());
-                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
-                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Splitter1(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::StringParallelProcess(a, am), $ComponentModel::StringStock(b, bm), _) => {
-                        b.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, am.address());
-                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
-                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Splitter2(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::StringSource(a, am), $ComponentModel::StringStock(b, bm), _) => {
-                        b.state_emitter.connect($crate::components::discrete::DiscreteSource::update_state, am.address());
-                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
-                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Splitter3(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::StringStock(a, am), $ComponentModel::StringSink(b, bm), _) => {
-                        a.state_emitter.connect($crate::components::discrete::DiscreteSink::update_state, bm.address());
-                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
-                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Splitter4(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-
-                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerProcess(b, bm), _) => {
-                        a.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, bm.address());
-                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
-                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3Splitter5(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerProcess(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
-                        b.state_emitter.connect($crate::components::discrete::DiscreteProcess::update_state, am.address());
-                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
-                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::StringProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteProcess::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerParallelProcess(b, bm), _) => {
-                        a.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, bm.address());
-                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
-                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::StringSource(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteSource::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerParallelProcess(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
-                        b.state_emitter.connect($crate::components::discrete::DiscreteParallelProcess::update_state, am.address());
-                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
-                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::StringSink(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteSink::<(), Option<String>, String>::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerSource(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
-                        b.state_emitter.connect($crate::components::discrete::DiscreteSource::<Vector3Container, Vector3Container, Vector3ContainerFactory>::update_state, am.address());
-                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::<Vector3Container>::get_state_async, bm.address());
-                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::<Vector3Container>::add, bm.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::StringParallelProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteParallelProcess::<(), Option<String>, String, String>::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerSink(b, bm), _) => {
-                        a.state_emitter.connect($crate::components::discrete::DiscreteSink::<(), Option<Vector3Container>, Vector3Container>::update_state, bm.address());
-                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::<Vector3Container>::get_state_async, am.address());
-                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::<Vector3Container>::remove, am.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::StringKeyedProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteProcess::<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-
-                    ($ComponentModel::Vector3Stock(a, am), $ComponentModel::Vector3ContainerLoadProcess(b, bm), _) => {
-                        a.state_emitter.connect($crate::components::vector_container::ContainerLoadingProcess::update_state, bm.address());
-                        b.req_us_resource.connect($crate::components::vector::VectorStock::get_state_async, am.address());
-                        b.withdraw_us_resource.connect($crate::components::vector::VectorStock::remove, am.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::StringKeyedParallelProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteParallelProcess::<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerLoadProcess(b, bm), _) => {
-                        a.state_emitter.connect($crate::components::vector_container::ContainerLoadingProcess::update_state, bm.address());
-                        b.req_us_containers.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
-                        b.withdraw_us_containers.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3ContainerProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteProcess::<(), Option<Vector3Container>, Vector3Container, Vector3Container>::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerLoadProcess(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
-                        b.state_emitter.connect($crate::components::vector_container::ContainerLoadingProcess::update_state, am.address());
-                        a.req_downstream.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
-                        a.push_downstream.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3ContainerSource(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteSource::<Vector3Container, Vector3Container, Vector3ContainerFactory>::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerStock(a, am), $ComponentModel::Vector3ContainerUnloadProcess(b, bm), _) => {
-                        a.state_emitter.connect($crate::components::vector_container::ContainerUnloadingProcess::update_state, bm.address());
-                        b.req_upstream.connect($crate::components::discrete::DiscreteStock::get_state_async, am.address());
-                        b.withdraw_upstream.connect($crate::components::discrete::DiscreteStock::remove, am.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3ContainerSink(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteSink::<(), Option<Vector3Container>, Vector3Container>::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerUnloadProcess(a, am), $ComponentModel::Vector3ContainerStock(b, bm), _) => {
-                        b.state_emitter.connect($crate::components::vector_container::ContainerUnloadingProcess::update_state, am.address());
-                        a.req_ds_containers.connect($crate::components::discrete::DiscreteStock::get_state_async, bm.address());
-                        a.push_ds_containers.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3ContainerLoadProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector_container::ContainerLoadingProcess::<Vector3Container, Vector3>::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::Vector3ContainerUnloadProcess(a, am), $ComponentModel::Vector3Stock(b, bm), _) => {
-                        b.state_emitter.connect($crate::components::vector_container::ContainerUnloadingProcess::update_state, am.address());
-                        a.req_ds_resource.connect($crate::components::vector::VectorStock::get_state_async, bm.address());
-                        a.push_ds_resource.connect($crate::components::vector::VectorStock::add, bm.address());
+                    ($ComponentModel::MaintenanceCrew(a, am), $ComponentModel::Vector3ContainerUnloadProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::vector_container::ContainerUnloadingProcess::<Vector3Container, Vector3>::update_state, bm.address());
+                        b.req_crew.connect($crate::core::MaintenanceCrew::request, am.address());
+                        b.release_crew.connect($crate::core::MaintenanceCrew::release, am.address());
                         Ok(())
                     },
-                    /**
-                     * Environments
-                     */
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Process(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, _), $ComponentModel::BasicEnvironment(_, bm), _) => {
+                        a.emit_state.connect($crate::core::BasicEnvironment::set_state, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Process(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorProcess::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Source(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Source(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSource::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Sink(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Sink(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSink::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner1(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Combiner1(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner2(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Combiner2(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner3(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Combiner3(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner4(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Combiner4(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Combiner5(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Combiner5(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter1(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Splitter1(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter2(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Splitter2(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter3(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Splitter3(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter4(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Splitter4(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::F64Splitter5(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::F64Splitter5(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Process(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Process(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorProcess::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Source(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Source(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSource::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Sink(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Sink(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSink::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner1(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Combiner1(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner2(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Combiner2(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner3(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Combiner3(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner4(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Combiner4(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Combiner5(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Combiner5(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorCombiner::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter1(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Splitter1(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter2(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Splitter2(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter3(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Splitter3(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter4(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Splitter4(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3Splitter5(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3Splitter5(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector::VectorSplitter::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringProcess(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::StringProcess(b, bm), _) => {
                         a.emit_change.connect($crate::components::discrete::DiscreteProcess::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringSource(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::StringSource(b, bm), _) => {
                         a.emit_change.connect($crate::components::discrete::DiscreteSource::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringSink(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::StringSink(b, bm), _) => {
                         a.emit_change.connect($crate::components::discrete::DiscreteSink::<(), Option<String>, String>::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::StringParallelProcess(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::StringParallelProcess(b, bm), _) => {
                         a.emit_change.connect($crate::components::discrete::DiscreteParallelProcess::<(), Option<String>, String, String>::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerProcess(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::StringKeyedProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteProcess::<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::StringKeyedParallelProcess(b, bm), _) => {
+                        a.emit_change.connect($crate::components::discrete::DiscreteParallelProcess::<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>::update_state, bm.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3ContainerProcess(b, bm), _) => {
                         a.emit_change.connect($crate::components::discrete::DiscreteProcess::<(), Option<Vector3Container>, Vector3Container, Vector3Container>::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerSource(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3ContainerSource(b, bm), _) => {
                         a.emit_change.connect($crate::components::discrete::DiscreteSource::<Vector3Container, Vector3Container, Vector3ContainerFactory>::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerSink(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3ContainerSink(b, bm), _) => {
                         a.emit_change.connect($crate::components::discrete::DiscreteSink::<(), Option<Vector3Container>, Vector3Container>::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerLoadProcess(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3ContainerLoadProcess(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector_container::ContainerLoadingProcess::<Vector3Container, Vector3>::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
-                    ($ComponentModel::BasicEnvironment(a, am), $ComponentModel::Vector3ContainerUnloadProcess(b, bm), _) => {
+                    ($ComponentModel::ShiftCalendar(a, am), $ComponentModel::Vector3ContainerUnloadProcess(b, bm), _) => {
                         a.emit_change.connect($crate::components::vector_container::ContainerUnloadingProcess::<Vector3Container, Vector3>::update_state, bm.address());
-                        b.req_environment.connect($crate::core::BasicEnvironment::get_state_async, am.address());
+                        b.req_named_environment.connect($crate::core::ShiftCalendar::get_named_state_async, am.address());
                         Ok(())
                     },
                     (a,b,n) => {
@@ -1177,6 +2360,67 @@ macro_rules! define_model_enums {
                 }
             }
 
+            /// Connects the `push_loss` port of process `a` to stock `b`, which receives work in progress scrapped under
+            /// `EnvironmentStopPolicy::Scrap`.
+            pub fn connect_loss(
+                a: &mut $ComponentModel,
+                b: &mut $ComponentModel,
+            ) -> Result<(), Box<dyn ::std::error::Error>> {
+                match (a, b) {
+                    ($ComponentModel::F64Process(a, _), $ComponentModel::F64Stock(_, bd)) => {
+                        a.push_loss.connect($crate::components::vector::VectorStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3Process(a, _), $ComponentModel::Vector3Stock(_, bd)) => {
+                        a.push_loss.connect($crate::components::vector::VectorStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringProcess(a, _), $ComponentModel::StringStock(_, bd)) => {
+                        a.push_loss.connect($crate::components::discrete::DiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringParallelProcess(a, _), $ComponentModel::StringStock(_, bm)) => {
+                        a.push_loss.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringKeyedProcess(a, _), $ComponentModel::StringKeyedStock(_, bd)) => {
+                        a.push_loss.connect($crate::components::discrete::KeyedDiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringKeyedProcess(a, _), $ComponentModel::StringStock(_, bd)) => {
+                        a.push_loss.connect($crate::components::discrete::DiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringProcess(a, _), $ComponentModel::StringKeyedStock(_, bd)) => {
+                        a.push_loss.connect($crate::components::discrete::KeyedDiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringKeyedParallelProcess(a, _), $ComponentModel::StringKeyedStock(_, bd)) => {
+                        a.push_loss.connect($crate::components::discrete::KeyedDiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringKeyedParallelProcess(a, _), $ComponentModel::StringStock(_, bd)) => {
+                        a.push_loss.connect($crate::components::discrete::DiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::StringParallelProcess(a, _), $ComponentModel::StringKeyedStock(_, bd)) => {
+                        a.push_loss.connect($crate::components::discrete::KeyedDiscreteStock::add, bd.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerProcess(a, _), $ComponentModel::Vector3ContainerStock(_, bm)) => {
+                        a.push_loss.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+                    ($ComponentModel::Vector3ContainerParallelProcess(a, _), $ComponentModel::Vector3ContainerStock(_, bm)) => {
+                        a.push_loss.connect($crate::components::discrete::DiscreteStock::add, bm.address());
+                        Ok(())
+                    },
+                    (a, b) => {
+                        Err(format!("connect_loss not implemented for types ({}, {})", a, b).into())
+                    }
+                }
+            }
+
             pub fn register_component(mut sim_init: $crate::nexosim::SimInit, component: Self) -> $crate::nexosim::SimInit {
                 use $crate::register_component_arms;
                 register_component_arms!(component, sim_init,
@@ -1187,9 +2431,10 @@ macro_rules! define_model_enums {
                     Vector3Combiner1, Vector3Combiner2, Vector3Combiner3, Vector3Combiner4, Vector3Combiner5,
                     Vector3Splitter1, Vector3Splitter2, Vector3Splitter3, Vector3Splitter4, Vector3Splitter5,
                     StringStock, StringProcess, StringParallelProcess, StringSource, StringSink,
+                    StringKeyedStock, StringKeyedProcess, StringKeyedParallelProcess,
                     Vector3ContainerStock, Vector3ContainerProcess, Vector3ContainerParallelProcess,
                     Vector3ContainerSource, Vector3ContainerSink, Vector3ContainerLoadProcess, Vector3ContainerUnloadProcess,
-                    BasicEnvironment,
+                    BasicEnvironment, MaintenanceCrew, ShiftCalendar,
                     $($R),*
                 );
                 sim_init
@@ -1206,9 +2451,10 @@ macro_rules! define_model_enums {
                     Vector3Combiner1, Vector3Combiner2, Vector3Combiner3, Vector3Combiner4, Vector3Combiner5,
                     Vector3Splitter1, Vector3Splitter2, Vector3Splitter3, Vector3Splitter4, Vector3Splitter5,
                     StringStock, StringProcess, StringParallelProcess, StringSource, StringSink,
+                    StringKeyedStock, StringKeyedProcess, StringKeyedParallelProcess,
                     Vector3ContainerStock, Vector3ContainerProcess, Vector3ContainerParallelProcess,
                     Vector3ContainerSource, Vector3ContainerSink, Vector3ContainerLoadProcess, Vector3ContainerUnloadProcess,
-                    BasicEnvironment,
+                    BasicEnvironment, MaintenanceCrew, ShiftCalendar,
                     $($R),*
                 )
             }
@@ -1251,6 +2497,9 @@ macro_rules! define_model_enums {
             StringParallelProcess($crate::nexosim::Address<$crate::components::discrete::DiscreteParallelProcess<(), Option<String>, String, String>>),
             StringSource($crate::nexosim::Address<$crate::components::discrete::DiscreteSource<String, String, StringItemFactory>>),
             StringSink($crate::nexosim::Address<$crate::components::discrete::DiscreteSink<(), Option<String>, String>>),
+            StringKeyedStock($crate::nexosim::Address<$crate::components::discrete::KeyedDiscreteStock<String>>),
+            StringKeyedProcess($crate::nexosim::Address<$crate::components::discrete::DiscreteProcess<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>>),
+            StringKeyedParallelProcess($crate::nexosim::Address<$crate::components::discrete::DiscreteParallelProcess<$crate::components::discrete::ItemRequest<String>, Option<String>, String, String>>),
 
             F64ContainerLoadProcess($crate::nexosim::Address<$crate::components::vector_container::ContainerLoadingProcess<F64Container, f64>>),
             F64ContainerUnloadProcess($crate::nexosim::Address<$crate::components::vector_container::ContainerUnloadingProcess<F64Container, f64>>),
@@ -1269,6 +2518,8 @@ macro_rules! define_model_enums {
             Vector3ContainerSink($crate::nexosim::Address<$crate::components::discrete::DiscreteSink<(), Option<Vector3Container>, Vector3Container>>),
 
             BasicEnvironment($crate::nexosim::Address<BasicEnvironment>),
+            MaintenanceCrew($crate::nexosim::Address<MaintenanceCrew>),
+            ShiftCalendar($crate::nexosim::Address<ShiftCalendar>),
             $(
                 $R $( ($crate::nexosim::Address<$RT>) )?
             ),*
@@ -1293,6 +2544,8 @@ macro_rules! define_model_enums {
             Vector3ContainerProcessLogger($crate::components::discrete::DiscreteProcessLogger<Vector3Container>),
 
             BasicEnvironmentLogger(BasicEnvironmentLogger),
+            MaintenanceCrewLogger(MaintenanceCrewLogger),
+            ShiftCalendarLogger(ShiftCalendarLogger),
             $(
                 $(#[$logger_var_meta])*
                 $U $( ( $UT ) )?
@@ -1318,8 +2571,8 @@ macro_rules! define_model_enums {
                         Vector3Splitter1, Vector3Splitter2, Vector3Splitter3, Vector3Splitter4, Vector3Splitter5
                     ],
                     
-                    StringStockLogger => [StringStock],
-                    StringProcessLogger => [StringProcess, StringParallelProcess, StringSource, StringSink],
+                    StringStockLogger => [StringStock, StringKeyedStock],
+                    StringProcessLogger => [StringProcess, StringParallelProcess, StringSource, StringSink, StringKeyedProcess, StringKeyedParallelProcess],
                     
                     F64ContainerStockLogger => [F64ContainerStock],
                     F64ContainerProcessLogger => [
@@ -1334,6 +2587,8 @@ macro_rules! define_model_enums {
                     ],
                     
                     BasicEnvironmentLogger => [BasicEnvironment],
+                    MaintenanceCrewLogger => [MaintenanceCrew],
+                    ShiftCalendarLogger => [ShiftCalendar],
                 )
             }
 
@@ -1352,6 +2607,8 @@ macro_rules! define_model_enums {
                     $ComponentLogger::Vector3ContainerProcessLogger(a) => { a.write_csv(dir.to_string()) },
 
                     $ComponentLogger::BasicEnvironmentLogger(a) => { a.write_csv(dir.to_string()) },
+                    $ComponentLogger::MaintenanceCrewLogger(a) => { a.write_csv(dir.to_string()) },
+                    $ComponentLogger::ShiftCalendarLogger(a) => { a.write_csv(dir.to_string()) },
 
                     $(
                         $ComponentLogger::$U (a) => {
@@ -1372,12 +2629,37 @@ macro_rules! define_model_enums {
             SetProcessQuantity(DistributionConfig),
             SetProcessTime(DistributionConfig),
             SetEnvironmentState(BasicEnvironmentState),
+            AddDelayMode(DelayMode),
+            RemoveDelayMode(String),
+            ForceDelay { name: String, duration: ::std::time::Duration },
         }
 
         impl $ScheduledEventConfig {
             pub fn schedule_event(&self, time: &$crate::nexosim::MonotonicTime, scheduler: &mut $crate::nexosim::Scheduler, addr: &$ComponentModelAddress, df: &mut DistributionFactory) -> Result<(), Box<dyn ::std::error::Error>> {
                 let time = time.clone();
                 let source_event_id = $crate::prelude::EventId::from_scheduler();
+                let delay_mode_change = match self {
+                    $ScheduledEventConfig::AddDelayMode(mode) => Some(DelayModeChange::Add(mode.clone())),
+                    $ScheduledEventConfig::RemoveDelayMode(name) => Some(DelayModeChange::Remove(name.clone())),
+                    $ScheduledEventConfig::ForceDelay { name, duration } => Some(DelayModeChange::Force { name: name.clone(), duration: *duration }),
+                    _ => None,
+                };
+                if let Some(change) = delay_mode_change {
+                    use $crate::schedule_delay_mode_change_arms;
+                    return schedule_delay_mode_change_arms!(addr, scheduler, time, change, source_event_id,
+                        F64Process, F64Source, F64Sink,
+                        F64Combiner1, F64Combiner2, F64Combiner3, F64Combiner4, F64Combiner5,
+                        F64Splitter1, F64Splitter2, F64Splitter3, F64Splitter4, F64Splitter5,
+                        Vector3Process, Vector3Source, Vector3Sink,
+                        Vector3Combiner1, Vector3Combiner2, Vector3Combiner3, Vector3Combiner4, Vector3Combiner5,
+                        Vector3Splitter1, Vector3Splitter2, Vector3Splitter3, Vector3Splitter4, Vector3Splitter5,
+                        StringProcess, StringParallelProcess, StringSource, StringSink, StringKeyedProcess, StringKeyedParallelProcess,
+                        F64ContainerProcess, F64ContainerParallelProcess, F64ContainerSource, F64ContainerSink,
+                        F64ContainerLoadProcess, F64ContainerUnloadProcess,
+                        Vector3ContainerProcess, Vector3ContainerParallelProcess, Vector3ContainerSource, Vector3ContainerSink,
+                        Vector3ContainerLoadProcess, Vector3ContainerUnloadProcess,
+                    );
+                }
                 match (self, addr) {
                     ($ScheduledEventConfig::SetLowCapacity(low_capacity), $ComponentModelAddress::F64Stock(addr)) => {
                         scheduler.schedule_event(time, $crate::components::vector::VectorStock::<f64>::with_low_capacity_inplace, low_capacity.clone(), addr.clone())?;
@@ -1411,6 +2693,13 @@ macro_rules! define_model_enums {
             };
         }
 
+        #[macro_export]
+        macro_rules! connect_loss {
+            (&mut $a:ident, &mut $b:ident) => {
+                $ComponentModel::connect_loss(&mut $a, &mut $b)
+            };
+        }
+
         #[macro_export]
         macro_rules! connect_logger {
             (&mut $a:ident, &mut $b:ident) => {
@@ -1434,3 +2723,343 @@ macro_rules! define_model_enums {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reduced_environment_rate() {
+        let state = BasicEnvironmentState::Reduced(0.6);
+        assert_eq!(state.progress(Duration::from_secs(10)), Duration::from_secs(6));
+        assert_eq!(state.time_to_progress(Duration::from_secs(6)), Some(Duration::from_secs(10)));
+
+        // Rounded up, so the process has finished when next checked
+        let process_time_left = Duration::from_nanos(1_000_000_001);
+        let time = state.time_to_progress(process_time_left).unwrap();
+        assert!(state.progress(time) >= process_time_left);
+
+        assert!(!state.is_stopped());
+        assert!(BasicEnvironmentState::Reduced(0.).is_stopped());
+        assert_eq!(BasicEnvironmentState::Stopped.time_to_progress(Duration::from_secs(1)), None);
+        assert_eq!(BasicEnvironmentState::Normal.time_to_progress(Duration::from_secs(1)), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_environment_time_series_from_csv() {
+        let path = std::env::temp_dir().join("quokkasim_test_environment.csv");
+        std::fs::write(&path, "time,state\n2025-01-01 12:00:00,Stopped\n2025-01-01 06:00:00,0.6\n2025-01-01 18:00:00,Normal\n").unwrap();
+        let series = EnvironmentTimeSeries::from_csv(path.to_str().unwrap()).unwrap();
+        let time = |hour| MonotonicTime::try_from_date_time(2025, 1, 1, hour, 0, 0, 0).unwrap();
+        assert_eq!(series.changes, vec![
+            (time(6), BasicEnvironmentState::Reduced(0.6)),
+            (time(12), BasicEnvironmentState::Stopped),
+            (time(18), BasicEnvironmentState::Normal),
+        ]);
+
+        assert_eq!("Reduced(0.5)".parse::<BasicEnvironmentState>().unwrap(), BasicEnvironmentState::Reduced(0.5));
+        assert_eq!("1".parse::<BasicEnvironmentState>().unwrap(), BasicEnvironmentState::Normal);
+        assert!("1.5".parse::<BasicEnvironmentState>().is_err());
+        assert!("Raining".parse::<BasicEnvironmentState>().is_err());
+    }
+
+    #[test]
+    fn test_shift_calendar() {
+        let time = |day, hour| MonotonicTime::try_from_date_time(2025, 1, day, hour, 0, 0, 0).unwrap();
+        let calendar = ShiftCalendar::new()
+            .with_shift("Day", 6., 12.).unwrap()
+            .with_shift("Night", 18., 12.).unwrap()
+            .with_changeover(Duration::from_secs(1800))
+            .with_non_working_weekdays(vec![5, 6])
+            .with_holiday(time(1, 12));
+
+        // 2025-01-06 is a Monday
+        assert_eq!(calendar.state_at(time(6, 6)), BasicEnvironmentState::Stopped);
+        assert_eq!(calendar.state_at(time(6, 7)), BasicEnvironmentState::Normal);
+        assert_eq!(calendar.state_at(time(6, 3)), BasicEnvironmentState::Stopped);
+        assert_eq!(calendar.state_at(time(7, 3)), BasicEnvironmentState::Normal);
+
+        // Friday's night shift runs into Saturday, and nothing else runs until Monday
+        assert_eq!(calendar.state_at(time(11, 3)), BasicEnvironmentState::Normal);
+        assert_eq!(calendar.state_at(time(11, 7)), BasicEnvironmentState::Stopped);
+        assert_eq!(calendar.next_boundary(time(11, 7)), Some(time(13, 6)));
+
+        // New Year's Day is a holiday
+        assert_eq!(calendar.state_at(time(1, 12)), BasicEnvironmentState::Stopped);
+
+        let boundaries = calendar.boundaries(time(6, 0).as_secs() / 86400);
+        assert_eq!(boundaries[2..4], [
+            (time(6, 18), ShiftCalendarLogType::ShiftEnd { shift: "Day".into() }),
+            (time(6, 18), ShiftCalendarLogType::ChangeoverStart { shift: "Night".into() }),
+        ]);
+        assert_eq!(calendar.next_boundary(time(6, 7)), Some(time(6, 18)));
+
+        // Holidays may run for more than a year, and without any working weekdays there are no more shifts
+        let day = |n: i64| MonotonicTime::new(n * SECS_PER_DAY, 0).unwrap();
+        let closure = (0..500).fold(ShiftCalendar::new().with_shift("Day", 6., 12.).unwrap(), |calendar, n| calendar.with_holiday(day(n)));
+        assert_eq!(closure.next_boundary(day(0)), Some(day(500) + Duration::from_secs(6 * 3600)));
+        let closed = calendar.with_non_working_weekdays((0..7).collect());
+        assert_eq!(closed.next_boundary(time(6, 7)), None);
+
+        for (start_hour, duration_hours) in [(-1., 8.), (24., 8.), (f64::NAN, 8.), (6., 0.), (6., -8.), (6., 25.), (6., f64::INFINITY)] {
+            assert!(ShiftCalendar::new().with_shift("Day", start_hour, duration_hours).is_err());
+        }
+    }
+
+    #[test]
+    fn test_combine_environment_states() {
+        let states = |x: &[(&str, BasicEnvironmentState)]| x.iter().map(|(name, state)| (name.to_string(), state.clone())).collect::<Vec<_>>();
+        assert_eq!(combine_environment_states(vec![]), (BasicEnvironmentState::Normal, vec![]));
+        assert_eq!(
+            combine_environment_states(states(&[("Weather", BasicEnvironmentState::Reduced(0.5)), ("Power", BasicEnvironmentState::Reduced(0.8)), ("Shifts", BasicEnvironmentState::Normal)])),
+            (BasicEnvironmentState::Reduced(0.4), vec!["Weather".to_string(), "Power".to_string()]),
+        );
+        assert_eq!(
+            combine_environment_states(states(&[("Weather", BasicEnvironmentState::Reduced(0.5)), ("Power", BasicEnvironmentState::Stopped), ("Shifts", BasicEnvironmentState::Stopped)])),
+            (BasicEnvironmentState::Stopped, vec!["Power".to_string(), "Shifts".to_string()]),
+        );
+    }
+
+    #[test]
+    fn test_process_stopped_by_one_of_two_environments() {
+        let time = |secs| MonotonicTime::EPOCH + Duration::from_secs(secs);
+        let mut upstream = VectorStock::new().with_name("Stock1").with_initial_resource(100.).with_max_capacity(1000.);
+        let mut process: VectorProcess<f64, f64, f64, f64> = VectorProcess::new()
+            .with_name("Process")
+            .with_process_quantity_distr(Distribution::Constant(10.))
+            .with_process_time_distr(Distribution::Constant(10.));
+        let mut downstream = VectorStock::new().with_name("Stock2").with_max_capacity(1000.);
+        let mut power = BasicEnvironment::new().with_name("Power").with_time_series(EnvironmentTimeSeries::new(vec![
+            (time(15), BasicEnvironmentState::Stopped),
+            (time(35), BasicEnvironmentState::Normal),
+        ]));
+        // Connected by hand to the unnamed state, as in models built before environments were named
+        let mut weather = BasicEnvironment::new().with_name("Weather").with_time_series(EnvironmentTimeSeries::new(vec![
+            (time(50), BasicEnvironmentState::Stopped),
+        ]));
+        let (upstream_mbox, process_mbox, downstream_mbox) = (Mailbox::new(), Mailbox::new(), Mailbox::new());
+        let (power_mbox, weather_mbox) = (Mailbox::new(), Mailbox::new());
+
+        upstream.state_emitter.connect(VectorProcess::update_state, &process_mbox);
+        downstream.state_emitter.connect(VectorProcess::update_state, &process_mbox);
+        power.emit_change.connect(VectorProcess::update_state, &process_mbox);
+        weather.emit_change.connect(VectorProcess::update_state, &process_mbox);
+        process.req_upstream.connect(VectorStock::get_state_async, &upstream_mbox);
+        process.withdraw_upstream.connect(VectorStock::remove, &upstream_mbox);
+        process.req_downstream.connect(VectorStock::get_state_async, &downstream_mbox);
+        process.push_downstream.connect(VectorStock::add, &downstream_mbox);
+        process.req_named_environment.connect(BasicEnvironment::get_named_state_async, &power_mbox);
+        process.req_environment.connect(BasicEnvironment::get_state_async, &weather_mbox);
+        let logger = VectorProcessLogger::new("ProcessLogger");
+        process.log_emitter.connect_sink(&logger.buffer);
+
+        let (mut simu, _) = SimInit::new()
+            .add_model(upstream, upstream_mbox, "Stock1")
+            .add_model(process, process_mbox, "Process")
+            .add_model(downstream, downstream_mbox, "Stock2")
+            .add_model(power, power_mbox, "Power")
+            .add_model(weather, weather_mbox, "Weather")
+            .init(MonotonicTime::EPOCH)
+            .unwrap();
+        simu.step_until(time(60)).unwrap();
+
+        let logs: Vec<_> = logger.buffer.into_reader().map(|log| (log.time, log.event)).collect();
+        let environment_changes: Vec<_> = logs.iter().filter_map(|(time, event)| match event {
+            VectorProcessLogType::ProcessStopped { reason } | VectorProcessLogType::ProcessContinue { reason } => Some((time.as_str(), reason.as_str())),
+            _ => None,
+        }).collect();
+        assert_eq!(environment_changes, vec![
+            ("1970-01-01 00:00:15 UTC", "Stopped by Power"),
+            ("1970-01-01 00:00:35 UTC", "Resumed by environment"),
+            ("1970-01-01 00:00:50 UTC", "Stopped by environment"),
+        ]);
+        // The process paused for the 20s that Power was stopped
+        let successes: Vec<_> = logs.iter().filter(|(_, event)| matches!(event, VectorProcessLogType::ProcessSuccess { .. })).map(|(time, _)| time.as_str()).collect();
+        assert_eq!(successes, vec!["1970-01-01 00:00:10 UTC", "1970-01-01 00:00:40 UTC", "1970-01-01 00:00:50 UTC"]);
+    }
+
+    #[test]
+    fn test_stopped_process_only_wakes_for_running_delay_clocks() {
+        let time = |secs| MonotonicTime::EPOCH + Duration::from_secs(secs);
+        let mut upstream = VectorStock::new().with_name("Stock1").with_initial_resource(100.).with_max_capacity(1000.);
+        let mut process: VectorProcess<f64, f64, f64, f64> = VectorProcess::new()
+            .with_name("Process")
+            .with_process_quantity_distr(Distribution::Constant(10.))
+            .with_process_time_distr(Distribution::Constant(10.))
+            .with_delay_mode(DelayModeChange::Add(DelayMode::new("Wear", Distribution::Constant(100.), Distribution::Constant(5.))))
+            .with_delay_mode(DelayModeChange::Add(DelayMode::new("Corrosion", Distribution::Constant(200.), Distribution::Constant(5.))
+                .with_clock(DelayClock::Calendar)));
+        let mut downstream = VectorStock::new().with_name("Stock2").with_max_capacity(1000.);
+        let mut power = BasicEnvironment::new().with_name("Power").with_time_series(EnvironmentTimeSeries::new(vec![
+            (time(15), BasicEnvironmentState::Stopped),
+            (time(500), BasicEnvironmentState::Normal),
+        ]));
+        let (upstream_mbox, process_mbox, downstream_mbox, power_mbox) = (Mailbox::new(), Mailbox::new(), Mailbox::new(), Mailbox::new());
+
+        upstream.state_emitter.connect(VectorProcess::update_state, &process_mbox);
+        downstream.state_emitter.connect(VectorProcess::update_state, &process_mbox);
+        power.emit_change.connect(VectorProcess::update_state, &process_mbox);
+        process.req_upstream.connect(VectorStock::get_state_async, &upstream_mbox);
+        process.withdraw_upstream.connect(VectorStock::remove, &upstream_mbox);
+        process.req_downstream.connect(VectorStock::get_state_async, &downstream_mbox);
+        process.push_downstream.connect(VectorStock::add, &downstream_mbox);
+        process.req_named_environment.connect(BasicEnvironment::get_named_state_async, &power_mbox);
+        let logger = VectorProcessLogger::new("ProcessLogger");
+        process.log_emitter.connect_sink(&logger.buffer);
+
+        let (mut simu, _) = SimInit::new()
+            .add_model(upstream, upstream_mbox, "Stock1")
+            .add_model(process, process_mbox, "Process")
+            .add_model(downstream, downstream_mbox, "Stock2")
+            .add_model(power, power_mbox, "Power")
+            .init(MonotonicTime::EPOCH)
+            .unwrap();
+        // Past the end of the process that was paused at 15s, which was already scheduled
+        simu.step_until(time(20)).unwrap();
+
+        // Wear's busy-clock counter is held while stopped, so the next wake-up is for Corrosion. Its unplanned fix is
+        // then held until the environment resumes
+        simu.step().unwrap();
+        assert_eq!(simu.time(), time(200));
+        simu.step().unwrap();
+        assert_eq!(simu.time(), time(500));
+
+        let delays: Vec<_> = logger.buffer.into_reader().filter_map(|log| match log.event {
+            VectorProcessLogType::DelayStart { delay_name, .. } => Some((log.time, delay_name)),
+            _ => None,
+        }).collect();
+        assert_eq!(delays, vec![("1970-01-01 00:03:20 UTC".to_string(), "Corrosion".to_string())]);
+    }
+
+    type TimedEvents = Vec<(String, String)>;
+
+    /// Runs Stock1 -> Process -> Stock2 with `policy`, the process's loss stream going to Loss, and Power stopping the
+    /// process from 15s to 35s, partway through its second 10s process. Returns the process events and the balances of
+    /// stocks added to, each with their time.
+    fn run_with_stop_policy(policy: EnvironmentStopPolicy, upstream_capacity: f64, connect_outputs: bool) -> Result<(TimedEvents, TimedEvents), ExecutionError> {
+        let time = |secs| MonotonicTime::EPOCH + Duration::from_secs(secs);
+        let mut upstream = VectorStock::new().with_name("Stock1").with_initial_resource(100.).with_max_capacity(upstream_capacity);
+        let mut process: VectorProcess<f64, f64, f64, f64> = VectorProcess::new()
+            .with_name("Process")
+            .with_process_quantity_distr(Distribution::Constant(10.))
+            .with_process_time_distr(Distribution::Constant(10.))
+            .with_env_stop_policy(policy);
+        let mut downstream = VectorStock::new().with_name("Stock2").with_max_capacity(1000.);
+        let mut loss = VectorStock::new().with_name("Loss").with_max_capacity(1000.);
+        let mut power = BasicEnvironment::new().with_name("Power").with_time_series(EnvironmentTimeSeries::new(vec![
+            (time(15), BasicEnvironmentState::Stopped),
+            (time(35), BasicEnvironmentState::Normal),
+        ]));
+        let (upstream_mbox, process_mbox, downstream_mbox) = (Mailbox::new(), Mailbox::new(), Mailbox::new());
+        let (loss_mbox, power_mbox) = (Mailbox::new(), Mailbox::new());
+
+        upstream.state_emitter.connect(VectorProcess::update_state, &process_mbox);
+        downstream.state_emitter.connect(VectorProcess::update_state, &process_mbox);
+        power.emit_change.connect(VectorProcess::update_state, &process_mbox);
+        process.req_upstream.connect(VectorStock::get_state_async, &upstream_mbox);
+        process.withdraw_upstream.connect(VectorStock::remove, &upstream_mbox);
+        process.req_downstream.connect(VectorStock::get_state_async, &downstream_mbox);
+        process.push_downstream.connect(VectorStock::add, &downstream_mbox);
+        process.req_named_environment.connect(BasicEnvironment::get_named_state_async, &power_mbox);
+        if connect_outputs {
+            process.push_upstream.connect(VectorStock::add, &upstream_mbox);
+            process.push_loss.connect(VectorStock::add, &loss_mbox);
+        }
+        let process_logger = VectorProcessLogger::new("ProcessLogger");
+        let stock_logger = VectorStockLogger::new("StockLogger");
+        process.log_emitter.connect_sink(&process_logger.buffer);
+        for stock in [&mut upstream, &mut downstream, &mut loss] {
+            stock.log_emitter.connect_sink(&stock_logger.buffer);
+        }
+
+        let (mut simu, _) = SimInit::new()
+            .add_model(upstream, upstream_mbox, "Stock1")
+            .add_model(process, process_mbox, "Process")
+            .add_model(downstream, downstream_mbox, "Stock2")
+            .add_model(loss, loss_mbox, "Loss")
+            .add_model(power, power_mbox, "Power")
+            .init(MonotonicTime::EPOCH)?;
+        simu.step_until(time(44))?;
+
+        let process_events = process_logger.buffer.into_reader().filter_map(|log| {
+            let event = match log.event {
+                VectorProcessLogType::ProcessStart { .. } => "Start".into(),
+                VectorProcessLogType::ProcessSuccess { .. } => "Success".into(),
+                VectorProcessLogType::ProcessStopped { reason } | VectorProcessLogType::ProcessContinue { reason } => reason,
+                VectorProcessLogType::ProcessAborted { quantity, .. } => format!("Aborted {quantity}"),
+                VectorProcessLogType::ProcessScrapped { quantity, .. } => format!("Scrapped {quantity}"),
+                VectorProcessLogType::ProcessFailure { reason } => reason.into(),
+                _ => return None,
+            };
+            Some((log.time[11..19].to_string(), event))
+        }).collect();
+        let additions = stock_logger.buffer.into_reader().filter_map(|log| match log.details {
+            VectorStockLogType::Add { balance, .. } => Some((log.time[11..19].to_string(), format!("{} {balance}", log.element_name))),
+            _ => None,
+        }).collect();
+        Ok((process_events, additions))
+    }
+
+    fn timed(events: &[(&str, &str)]) -> TimedEvents {
+        events.iter().map(|(time, event)| (time.to_string(), event.to_string())).collect()
+    }
+
+    #[test]
+    fn test_stop_policy_pause() {
+        let (events, additions) = run_with_stop_policy(EnvironmentStopPolicy::Pause, 1000., true).unwrap();
+        assert_eq!(events, timed(&[
+            ("00:00:00", "Start"), ("00:00:10", "Success"), ("00:00:10", "Start"), ("00:00:15", "Stopped by Power"),
+            ("00:00:35", "Resumed by environment"), ("00:00:40", "Success"), ("00:00:40", "Start"),
+        ]));
+        assert_eq!(additions, timed(&[("00:00:10", "Stock2 10"), ("00:00:40", "Stock2 20")]));
+    }
+
+    #[test]
+    fn test_stop_policy_finish_then_stop() {
+        let (events, additions) = run_with_stop_policy(EnvironmentStopPolicy::FinishThenStop, 1000., true).unwrap();
+        assert_eq!(events, timed(&[
+            ("00:00:00", "Start"), ("00:00:10", "Success"), ("00:00:10", "Start"), ("00:00:15", "Stopped by Power"),
+            ("00:00:20", "Success"), ("00:00:35", "Resumed by environment"), ("00:00:35", "Start"),
+        ]));
+        assert_eq!(additions, timed(&[("00:00:10", "Stock2 10"), ("00:00:20", "Stock2 20")]));
+    }
+
+    #[test]
+    fn test_stop_policy_abort_upstream() {
+        let (events, additions) = run_with_stop_policy(EnvironmentStopPolicy::AbortUpstream, 1000., true).unwrap();
+        assert_eq!(events, timed(&[
+            ("00:00:00", "Start"), ("00:00:10", "Success"), ("00:00:10", "Start"), ("00:00:15", "Stopped by Power"),
+            ("00:00:15", "Aborted 10"), ("00:00:35", "Resumed by environment"), ("00:00:35", "Start"),
+        ]));
+        assert_eq!(additions, timed(&[("00:00:10", "Stock2 10"), ("00:00:15", "Stock1 90")]));
+
+        // With no room upstream, the work in progress is paused instead
+        let (events, additions) = run_with_stop_policy(EnvironmentStopPolicy::AbortUpstream, 85., true).unwrap();
+        assert_eq!(events[3..6], timed(&[
+            ("00:00:15", "Stopped by Power"), ("00:00:15", "No room upstream to abort to, pausing instead"),
+            ("00:00:35", "Resumed by environment"),
+        ]));
+        assert_eq!(additions, timed(&[("00:00:10", "Stock2 10"), ("00:00:40", "Stock2 20")]));
+    }
+
+    #[test]
+    fn test_stop_policy_scrap() {
+        let (events, additions) = run_with_stop_policy(EnvironmentStopPolicy::Scrap, 1000., true).unwrap();
+        assert_eq!(events, timed(&[
+            ("00:00:00", "Start"), ("00:00:10", "Success"), ("00:00:10", "Start"), ("00:00:15", "Stopped by Power"),
+            ("00:00:15", "Scrapped 10"), ("00:00:35", "Resumed by environment"), ("00:00:35", "Start"),
+        ]));
+        assert_eq!(additions, timed(&[("00:00:10", "Stock2 10"), ("00:00:15", "Loss 10")]));
+    }
+
+    #[test]
+    fn test_stop_policy_needs_connected_output() {
+        let (events, additions) = run_with_stop_policy(EnvironmentStopPolicy::Scrap, 1000., false).unwrap();
+        assert_eq!(events, timed(&[
+            ("00:00:00", "Stop policy Scrap needs push_loss connected, pausing instead"), ("00:00:00", "Start"),
+            ("00:00:10", "Success"), ("00:00:10", "Start"), ("00:00:15", "Stopped by Power"),
+            ("00:00:35", "Resumed by environment"), ("00:00:40", "Success"), ("00:00:40", "Start"),
+        ]));
+        assert_eq!(additions, timed(&[("00:00:10", "Stock2 10"), ("00:00:40", "Stock2 20")]));
+    }
+}
@@ -1,19 +1,232 @@
-use std::time::Duration;
+use std::{fmt::{Display, Formatter, Result as FmtResult}, time::Duration};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use crate::common::Distribution;
+use tai_time::MonotonicTime;
+use crate::common::{Distribution, DistributionParametersError, Schedule, ScheduleIndex, TimeUnit};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct DelayMode {
     pub name: String,
     pub until_delay_distr: Distribution,
     pub until_fix_distr: Distribution,
+    /// Unit of samples from distributions that do not declare their own unit.
+    time_unit: TimeUnit,
+    trigger: DelayTrigger,
+    category: DelayCategory,
+    /// Clock against which the time until a `DelayTrigger::Sampled` delay counts down.
+    clock: DelayClock,
+    /// Whether the fix needs a member of a shared `MaintenanceCrew`. If so, the delay waits for one to be assigned
+    /// before `until_fix_distr` is sampled and starts counting down.
+    requires_crew: bool,
+    /// Precedence over other delay modes when several are due or running - see `DelayPolicy`. Higher values take
+    /// precedence.
+    priority: u32,
+}
+
+impl DelayMode {
+    /// A sampled, unplanned delay mode on the busy clock, with samples in seconds.
+    pub fn new(name: impl Into<String>, until_delay_distr: Distribution, until_fix_distr: Distribution) -> Self {
+        DelayMode { name: name.into(), until_delay_distr, until_fix_distr, ..Default::default() }
+    }
+
+    pub fn with_time_unit(mut self, time_unit: TimeUnit) -> Self {
+        self.time_unit = time_unit;
+        self
+    }
+
+    pub fn with_trigger(mut self, trigger: DelayTrigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    pub fn with_category(mut self, category: DelayCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: DelayClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_requires_crew(mut self, requires_crew: bool) -> Self {
+        self.requires_crew = requires_crew;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn time_unit(&self) -> TimeUnit {
+        self.time_unit
+    }
+
+    pub fn trigger(&self) -> &DelayTrigger {
+        &self.trigger
+    }
+
+    pub fn category(&self) -> DelayCategory {
+        self.category
+    }
+
+    pub fn clock(&self) -> DelayClock {
+        self.clock
+    }
+
+    pub fn requires_crew(&self) -> bool {
+        self.requires_crew
+    }
+
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Clock against which the fix counts down. Planned and calendar-triggered delays, e.g. a shutdown on a
+    /// calendar, run to the calendar whether or not the process would otherwise be operating. Other fixes only
+    /// progress while the process is not stopped by its environment.
+    pub fn fix_clock(&self) -> DelayClock {
+        if self.category == DelayCategory::Planned || matches!(self.trigger, DelayTrigger::Calendar(_)) {
+            DelayClock::Calendar
+        } else {
+            DelayClock::Busy
+        }
+    }
+
+    /// Samples the time until the next delay, as `until_delay_distr` applies at `now` if the time is known.
+    fn sample_until_delay(&mut self, now: Option<MonotonicTime>) -> Duration {
+        match now {
+            Some(now) => self.until_delay_distr.sample_duration_at(now, self.time_unit),
+            None => self.until_delay_distr.sample_duration(self.time_unit),
+        }
+    }
+
+    /// Samples the throughput until the next delay of a `DelayTrigger::Throughput` mode.
+    fn sample_throughput_until_delay(&mut self, now: Option<MonotonicTime>) -> f64 {
+        match now {
+            Some(now) => self.until_delay_distr.sample_at(now),
+            None => self.until_delay_distr.sample(),
+        }
+    }
+
+    /// Samples the time until fix, as `until_fix_distr` applies at `now` if the time is known.
+    fn sample_until_fix(&mut self, now: Option<MonotonicTime>) -> Duration {
+        match now {
+            Some(now) => self.until_fix_distr.sample_duration_at(now, self.time_unit),
+            None => self.until_fix_distr.sample_duration(self.time_unit),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Which elapsed time ages a delay mode towards its next delay.
+pub enum DelayClock {
+    /// Only while the process is busy, e.g. wear from operation.
+    #[default]
+    Busy,
+    /// Whenever the process is scheduled to be available, i.e. not stopped by its environment, busy or idle.
+    Available,
+    /// Always, e.g. corrosion, which ages equipment even while it sits idle or stopped.
+    Calendar,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Time elapsed since the last update of `DelayModes`, measured against each `DelayClock`.
+pub struct ElapsedTime {
+    pub calendar: Duration,
+    pub available: Duration,
+    /// Time spent processing or in a delay. Unplanned fixes count down against this clock - see
+    /// `DelayMode::fix_clock`.
+    pub busy: Duration,
+}
+
+impl ElapsedTime {
+    /// The same elapsed time on every clock.
+    pub fn uniform(duration: Duration) -> Self {
+        ElapsedTime { calendar: duration, available: duration, busy: duration }
+    }
+
+    pub fn of(&self, clock: DelayClock) -> Duration {
+        match clock {
+            DelayClock::Busy => self.busy,
+            DelayClock::Available => self.available,
+            DelayClock::Calendar => self.calendar,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// What starts a delay mode.
+pub enum DelayTrigger {
+    /// After an amount of operating time sampled from `until_delay_distr`. A constant distribution gives maintenance
+    /// at a fixed interval of operating time, e.g. every 500 operating hours.
+    #[default]
+    Sampled,
+    /// At each start of a calendar, in simulation time, regardless of whether the process is operating.
+    /// `until_delay_distr` is not used.
+    Calendar(MaintenanceCalendar),
+    /// After an amount of throughput sampled from `until_delay_distr`, e.g. every 200,000 t processed by a vector
+    /// process, or every 500 items processed by a discrete process. Processes report their throughput with
+    /// `DelayModes::record_throughput`.
+    Throughput,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Whether a delay is planned (e.g. scheduled maintenance) or unplanned (e.g. a breakdown), for separating the two in
+/// availability reporting.
+pub enum DelayCategory {
+    #[default]
+    Unplanned,
+    Planned,
+}
+
+impl Display for DelayCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DelayCategory::Unplanned => write!(f, "Unplanned"),
+            DelayCategory::Planned => write!(f, "Planned"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Start times of calendar-based delays, e.g. every Tuesday at 06:00.
+pub struct MaintenanceCalendar {
+    schedule: Schedule,
+}
+
+impl MaintenanceCalendar {
+    /// Starts at each of `starts`, in the units of `index` - see `ScheduleIndex`.
+    pub fn new(index: ScheduleIndex, starts: Vec<f64>) -> Result<Self, DistributionParametersError> {
+        Ok(MaintenanceCalendar { schedule: Schedule::new(index, starts)? })
+    }
+
+    /// Starts every day at `hour` (0 to 24).
+    pub fn daily(hour: f64) -> Result<Self, DistributionParametersError> {
+        Self::new(ScheduleIndex::HourOfDay, vec![hour])
+    }
+
+    /// Starts every week on `weekday` (Monday = 0) at `hour` (0 to 24).
+    pub fn weekly(weekday: u32, hour: f64) -> Result<Self, DistributionParametersError> {
+        Self::new(ScheduleIndex::DayOfWeek, vec![weekday as f64 + hour / 24.])
+    }
+
+    /// Time of the next start at or after `time` if `inclusive`, otherwise strictly after `time`.
+    pub fn next_start(&self, time: MonotonicTime, inclusive: bool) -> Option<MonotonicTime> {
+        self.schedule.time_to_next_start(time, inclusive).map(|duration| time + duration)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum DelayState {
     TimeUntilDelay(Duration),
     TimeUntilFix(Duration),
+    /// Delayed, but the fix has not started as no maintenance crew has been assigned yet. `requested` is set once
+    /// the crew has queued the request.
+    AwaitingCrew { requested: bool },
+    /// Preempted by a higher-priority delay under `DelayPolicy::Preempt`, holding the state to resume.
+    Suspended(Box<DelayState>),
 }
 
 impl DelayState {
@@ -21,11 +234,38 @@ impl DelayState {
         match self {
             DelayState::TimeUntilDelay(duration) => *duration,
             DelayState::TimeUntilFix(duration) => *duration,
+            DelayState::AwaitingCrew { .. } => Duration::ZERO,
+            DelayState::Suspended(state) => state.as_duration(),
         }
     }
+
+    /// Whether the delay is holding up the process, either fixing or waiting for a maintenance crew.
+    pub fn is_running(&self) -> bool {
+        matches!(self, DelayState::TimeUntilFix(_) | DelayState::AwaitingCrew { .. })
+    }
+
+    /// Whether the delay has started and not yet been fixed.
+    pub fn is_in_delay(&self) -> bool {
+        self.is_running() || matches!(self, DelayState::Suspended(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// How a delay that becomes due is handled while another delay is running.
+pub enum DelayPolicy {
+    /// The delay waits for the running delay to be fixed. Waiting delays start in order of priority.
+    #[default]
+    Queue,
+    /// The delay starts straight away, and is fixed alongside the running delays.
+    Overlap,
+    /// The delay suspends a running delay of lower priority, which resumes once no higher-priority delay is running.
+    /// Otherwise it waits as with `Queue`.
+    Preempt,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Change of the delay that downtime is attributed to. Under `DelayPolicy::Overlap`, downtime is attributed to the
+/// running delay of highest priority, staying with the earliest started of equal priority.
 pub struct DelayStateTransition {
     pub from: Option<String>,
     pub to: Option<String>,
@@ -42,17 +282,61 @@ impl DelayStateTransition {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// A change to one delay, for processes to log - see `DelayModes::take_delay_events`. Each delay has one `Start` and
+/// one `End`, in between which downtime may be attributed to other delays.
+pub enum DelayEvent {
+    /// The delay has started, with downtime attributed to it unless followed by a `Suspend`.
+    Start,
+    /// Downtime is no longer attributed to the delay, as a delay of higher priority has started alongside it or
+    /// preempted it - see `DelayPolicy`.
+    Suspend,
+    /// Downtime is attributed to the delay again.
+    Resume,
+    /// The delay has been fixed or removed.
+    End,
+}
+
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum DelayModeChange {
     Add(DelayMode),
     Remove(String),
     RemoveAll,
+    /// Makes the named delay due now, with a fix of `duration` rather than one sampled from `until_fix_distr`. It
+    /// starts as the `DelayPolicy` allows. A name without a delay mode gives a one-off delay.
+    Force { name: String, duration: Duration },
+}
+
+/// Components with delay modes, which can be changed while the simulation runs.
+pub trait HasDelayModes {
+    fn delay_modes_mut(&mut self) -> &mut DelayModes;
 }
 
 #[derive(Debug, Clone)]
 pub struct DelayModes {
     pub modes: IndexMap<String, DelayMode>,
     pub state: IndexMap<String, DelayState>,
+    pub policy: DelayPolicy,
+    /// Delay that downtime is currently attributed to.
+    attributed: Option<String>,
+    /// Next start time of each calendar-triggered delay mode, once known.
+    calendar_due: IndexMap<String, MonotonicTime>,
+    /// Throughput remaining until each throughput-triggered delay mode, while counting down.
+    throughput_remaining: IndexMap<String, f64>,
+    /// Delays holding a maintenance crew member, until returned with `release_crews`.
+    crewed: Vec<String>,
+    /// Fix durations of forced delays, used in place of a sample when they start.
+    forced: IndexMap<String, Duration>,
+    /// Modes added for a one-off forced delay, removed once it is fixed.
+    one_off: Vec<String>,
+    /// Time of the last update, at which time-varying distributions are sampled. Unknown until the first
+    /// `update_state_at`.
+    now: Option<MonotonicTime>,
+    /// Delays whose start has been recorded as a `DelayEvent`, and whose end has not.
+    started: Vec<String>,
+    /// Delay events not yet taken with `take_delay_events`.
+    events: Vec<(String, DelayEvent)>,
 }
 
 impl Default for DelayModes {
@@ -60,116 +344,464 @@ impl Default for DelayModes {
         DelayModes {
             modes: IndexMap::new(),
             state: IndexMap::new(),
+            policy: DelayPolicy::default(),
+            attributed: None,
+            calendar_due: IndexMap::new(),
+            throughput_remaining: IndexMap::new(),
+            crewed: Vec::new(),
+            forced: IndexMap::new(),
+            one_off: Vec::new(),
+            now: None,
+            started: Vec::new(),
+            events: Vec::new(),
         }
     }
 }
 
 impl DelayModes {
-    pub fn active_delay_mut(&mut self) -> Option<(&String, &mut Duration)> {
-        self.state.iter_mut().find_map(|(name, state)| {
+    /// The running delay whose fix ends soonest, if any.
+    pub fn active_delay(&self) -> Option<(&String, &Duration)> {
+        self.state.iter().filter_map(|(name, state)| {
             match state {
                 DelayState::TimeUntilFix(duration) => Some((name, duration)),
                 _ => None,
             }
-        })
+        }).min_by_key(|(_, duration)| **duration)
     }
 
-    pub fn active_delay(&self) -> Option<(&String, &Duration)> {
-        self.state.iter().find_map(|(name, state)| {
+    /// The delay that downtime is currently attributed to, if any.
+    pub fn attributed_delay(&self) -> Option<&String> {
+        self.attributed.as_ref()
+    }
+
+    /// Delays waiting for a maintenance crew.
+    pub fn awaiting_crew(&self) -> Vec<String> {
+        self.state.iter().filter_map(|(name, state)| {
             match state {
-                DelayState::TimeUntilFix(duration) => Some((name, duration)),
+                DelayState::AwaitingCrew { .. } => Some(name.clone()),
                 _ => None,
             }
-        })
+        }).collect()
     }
 
-    pub fn update_state(&mut self, time_elapsed: Duration) -> DelayStateTransition {
+    /// Whether the process is delayed, either by an active delay or one waiting for a maintenance crew.
+    pub fn is_delayed(&self) -> bool {
+        self.state.values().any(DelayState::is_in_delay)
+    }
+
+    /// Whether the named delay mode needs a maintenance crew.
+    pub fn requires_crew(&self, name: &str) -> bool {
+        self.modes.get(name).is_some_and(|mode| mode.requires_crew)
+    }
 
-        // If in a delay, decrement the time remaining. If time left is zero, return the delay's name
-        let active_delay = if let Some((delay_name, delay_dur_remaining)) = self.active_delay_mut() {
-            *delay_dur_remaining = delay_dur_remaining.saturating_sub(time_elapsed);
-            Some((delay_name.clone(), *delay_dur_remaining))
+    /// Marks a delay waiting for a maintenance crew as queued with the crew. Returns whether it was newly queued.
+    pub fn queue_for_crew(&mut self, name: &str) -> bool {
+        match self.state.get_mut(name) {
+            Some(DelayState::AwaitingCrew { requested }) => !std::mem::replace(requested, true),
+            _ => false,
+        }
+    }
+
+    /// Starts the fix of a delay waiting for a maintenance crew, now that one has been assigned. Returns whether the
+    /// delay had been queued waiting for the crew.
+    pub fn assign_crew(&mut self, name: &str) -> bool {
+        if let Some(DelayState::AwaitingCrew { requested }) = self.state.get(name) {
+            let requested = *requested;
+            let time_until_fix = self.sample_time_until_fix(name);
+            self.state.insert(name.to_string(), DelayState::TimeUntilFix(time_until_fix));
+            self.crewed.push(name.to_string());
+            requested
         } else {
-            None
-        };
-        let mut from: Option<String> = None;
-        let mut to: Option<String> = None;
-
-        if let Some((active_delay_name, delay_dur_remaining)) = active_delay {
-            // If delay has expired, sample time until next delay
-            if delay_dur_remaining.is_zero() {
-                let time_until_delay_secs = self.modes.get_mut(&active_delay_name).unwrap().until_delay_distr.sample();
-                let time_until_delay = Duration::from_secs_f64(time_until_delay_secs); // TODO: Units for delay, instead of assuming seconds
-                self.state.insert(active_delay_name.clone(), DelayState::TimeUntilDelay(time_until_delay));
+            false
+        }
+    }
+
+    /// Delays which no longer need their maintenance crew member, having been fixed or removed. Each is returned
+    /// once, for the crew member to be released.
+    pub fn release_crews(&mut self) -> Vec<String> {
+        let (released, crewed) = self.crewed.drain(..)
+            .partition(|name| !self.state.get(name).is_some_and(DelayState::is_in_delay));
+        self.crewed = crewed;
+        released
+    }
+
+    /// Category of the named delay mode, or the default category if there is no such mode.
+    pub fn category(&self, name: &str) -> DelayCategory {
+        self.modes.get(name).map(|mode| mode.category).unwrap_or_default()
+    }
+
+    fn priority(&self, name: &str) -> u32 {
+        self.modes.get(name).map(|mode| mode.priority).unwrap_or_default()
+    }
+
+    /// Advances the delay counters by `time_elapsed` on every clock. Calendar-triggered delays are not updated, as
+    /// they need the current time - see `update_state_at`.
+    pub fn update_state(&mut self, time_elapsed: Duration) -> DelayStateTransition {
+        self.update(None, ElapsedTime::uniform(time_elapsed))
+    }
+
+    /// Advances the delay counters to `now`, where `elapsed` is the time since the last update on each clock.
+    /// Calendar-triggered delays follow `now`, whether or not the process was operating.
+    pub fn update_state_at(&mut self, now: MonotonicTime, elapsed: ElapsedTime) -> DelayStateTransition {
+        self.update(Some(now), elapsed)
+    }
+
+    fn update(&mut self, now: Option<MonotonicTime>, elapsed: ElapsedTime) -> DelayStateTransition {
+        self.now = now.or(self.now.map(|time| time + elapsed.calendar));
+
+        // Decrement all times until delay by the elapsed time on their clock. Busy time does not accrue during a delay
+        let in_delay = self.is_delayed();
+        self.state.iter_mut().for_each(|(name, state)| {
+            if let DelayState::TimeUntilDelay(duration) = state
+                && let Some(mode) = self.modes.get(name)
+                && let DelayTrigger::Sampled = mode.trigger {
+                let time_elapsed = match mode.clock {
+                    DelayClock::Busy if in_delay => Duration::ZERO,
+                    clock => elapsed.of(clock),
+                };
+                *duration = duration.saturating_sub(time_elapsed);
             }
-            from = Some(active_delay_name.clone());
-        } else {
-            // If not in delay, decrement all times until delay
-            self.state.iter_mut().for_each(|(name, state)| {
-                if let DelayState::TimeUntilDelay(duration) = state {
-                    *duration = duration.saturating_sub(time_elapsed);
+        });
+
+        // Decrement the time remaining of running fixes on their clock, and find those which have finished
+        let mut fixed = Vec::new();
+        for (name, state) in self.state.iter_mut() {
+            if let DelayState::TimeUntilFix(remaining) = state {
+                let fix_clock = self.modes.get(name).map(DelayMode::fix_clock).unwrap_or_default();
+                *remaining = remaining.saturating_sub(elapsed.of(fix_clock));
+                if remaining.is_zero() {
+                    fixed.push(name.clone());
                 }
-            });
+            }
         }
 
-        let active_delay = self.active_delay();
-        if let Some((active_delay_name, _)) = active_delay {
-            // If still in delay, return the name of the delay
-            to = Some(active_delay_name.clone());
-        } else {
-            // If any durations are zero, find the first and make it the active delay
-            let delay_to_start = self.state.iter_mut().find_map(|(name, state)| {
-                match state {
-                    DelayState::TimeUntilDelay(duration) if *duration <= Duration::ZERO => {
-                        Some(name.clone())
-                    },
-                    _ => None,
-                }
-            });
-            if let Some(delay_to_start) = delay_to_start {
-                // Sample a new time until fix for the delay
-                let time_until_fix_secs = self.modes.get_mut(&delay_to_start).unwrap().until_fix_distr.sample();
-                let time_until_fix = Duration::from_secs_f64(time_until_fix_secs); // TODO: Units for delay, instead of assuming seconds
-                self.state.insert(delay_to_start.clone(), DelayState::TimeUntilFix(time_until_fix));
-                to = Some(delay_to_start);
+        // Sample time until next delay. Calendar delays get theirs from the calendar below
+        for name in fixed {
+            if self.one_off.contains(&name) {
+                self.modify(DelayModeChange::Remove(name));
+                continue;
+            }
+            let mode = self.modes.get_mut(&name).unwrap();
+            match mode.trigger {
+                DelayTrigger::Sampled => {
+                    let time_until_delay = mode.sample_until_delay(self.now);
+                    self.state.insert(name, DelayState::TimeUntilDelay(time_until_delay));
+                },
+                DelayTrigger::Calendar(_) => {
+                    self.state.shift_remove(&name);
+                },
+                DelayTrigger::Throughput => {
+                    let throughput_until_delay = mode.sample_throughput_until_delay(self.now);
+                    self.throughput_remaining.insert(name.clone(), throughput_until_delay);
+                    self.state.shift_remove(&name);
+                },
             }
         }
 
+        self.start_deferred_counters();
+
+        if let Some(now) = now {
+            self.update_calendar_counters(now);
+        }
+
+        self.start_due_delays(now);
+
+        let from = self.attributed.take();
+        self.attributed = self.attribute(from.as_ref());
+        self.record_events(from.as_ref());
         DelayStateTransition {
             from,
-            to,
+            to: self.attributed.clone(),
         }
     }
 
-    pub fn get_next_event(&self) -> Option<(String, DelayState)> {
-        if let Some((delay_name, time_until_fix)) = self.active_delay() {
-            return Some((delay_name.clone(), DelayState::TimeUntilFix(*time_until_fix)));
-        }
-        let to_next_delay = self.state.iter().filter_map(|(name, state)| {
+    /// Starts the delays whose time until delay has run out, as far as the policy allows.
+    fn start_due_delays(&mut self, now: Option<MonotonicTime>) {
+        // Due delays in order of priority, keeping the order of modes for equal priorities
+        let mut due: Vec<String> = self.state.iter().filter_map(|(name, state)| {
             match state {
-                DelayState::TimeUntilDelay(duration) => Some((name, duration)),
+                DelayState::TimeUntilDelay(duration) if duration.is_zero() => Some(name.clone()),
                 _ => None,
             }
-        }).min_by_key(|(_, duration)| *duration).map(|(name, duration)| (name.clone(), DelayState::TimeUntilDelay(*duration)));
-        to_next_delay
+        }).collect();
+        due.sort_by_key(|name| std::cmp::Reverse(self.priority(name)));
+
+        let running = self.state.iter().find(|(_, state)| state.is_running()).map(|(name, _)| name.clone());
+        match self.policy {
+            DelayPolicy::Overlap => {
+                for name in due {
+                    self.start(name, now);
+                }
+            },
+            DelayPolicy::Queue => {
+                if running.is_none() && let Some(name) = due.into_iter().next() {
+                    self.start(name, now);
+                }
+            },
+            DelayPolicy::Preempt => {
+                let next_due = due.into_iter().next();
+                match running {
+                    Some(running) => {
+                        if let Some(name) = next_due && self.priority(&name) > self.priority(&running) {
+                            if let Some(state) = self.state.get_mut(&running) {
+                                let suspended = std::mem::replace(state, DelayState::TimeUntilDelay(Duration::ZERO));
+                                *state = DelayState::Suspended(Box::new(suspended));
+                            }
+                            self.start(name, now);
+                        }
+                    },
+                    None => {
+                        // Resume the highest-priority suspended delay, unless a due delay outranks it
+                        let suspended = self.state.iter()
+                            .filter(|(_, state)| matches!(state, DelayState::Suspended(_)))
+                            .map(|(name, _)| name.clone())
+                            .rev()
+                            .max_by_key(|name| self.priority(name));
+                        match (suspended, next_due) {
+                            (Some(suspended), Some(name)) if self.priority(&name) > self.priority(&suspended) => self.start(name, now),
+                            (Some(suspended), _) => {
+                                if let Some(state) = self.state.get_mut(&suspended)
+                                    && let DelayState::Suspended(resumed) = std::mem::replace(state, DelayState::TimeUntilDelay(Duration::ZERO)) {
+                                    *state = *resumed;
+                                }
+                            },
+                            (None, Some(name)) => self.start(name, now),
+                            (None, None) => {},
+                        }
+                    },
+                }
+            },
+        }
+    }
+
+    /// Starts a delay by sampling its time until fix, unless it must wait for a maintenance crew first.
+    fn start(&mut self, name: String, now: Option<MonotonicTime>) {
+        let delay_state = if self.requires_crew(&name) {
+            DelayState::AwaitingCrew { requested: false }
+        } else {
+            DelayState::TimeUntilFix(self.sample_time_until_fix(&name))
+        };
+        let mode = &self.modes[&name];
+        if let (DelayTrigger::Calendar(calendar), Some(now)) = (&mode.trigger, now) {
+            match calendar.next_start(now, false) {
+                Some(next) => self.calendar_due.insert(name.clone(), next),
+                None => self.calendar_due.shift_remove(&name),
+            };
+        }
+        self.state.insert(name, delay_state);
+    }
+
+    /// The forced fix duration of a delay if any, otherwise a sample from its `until_fix_distr`.
+    fn sample_time_until_fix(&mut self, name: &str) -> Duration {
+        if let Some(duration) = self.forced.shift_remove(name) {
+            return duration;
+        }
+        self.modes.get_mut(name).unwrap().sample_until_fix(self.now)
+    }
+
+    /// Samples the first time or throughput until delay of modes that were added before the time was known, and whose
+    /// `until_delay_distr` is time-varying.
+    fn start_deferred_counters(&mut self) {
+        let Some(now) = self.now else { return };
+        for (name, mode) in self.modes.iter_mut() {
+            if !mode.until_delay_distr.is_time_varying() || self.state.contains_key(name) || self.throughput_remaining.contains_key(name) {
+                continue;
+            }
+            match mode.trigger {
+                DelayTrigger::Sampled => {
+                    self.state.insert(name.clone(), DelayState::TimeUntilDelay(mode.sample_until_delay(Some(now))));
+                },
+                DelayTrigger::Throughput => {
+                    self.throughput_remaining.insert(name.clone(), mode.sample_throughput_until_delay(Some(now)));
+                },
+                DelayTrigger::Calendar(_) => {},
+            }
+        }
+    }
+
+    /// The running delay to attribute downtime to - the one of highest priority, staying with `current` if no other
+    /// running delay outranks it.
+    fn attribute(&self, current: Option<&String>) -> Option<String> {
+        // Reversed, so that the first of equal priority is the maximum
+        let highest = self.state.iter()
+            .filter(|(_, state)| state.is_running())
+            .map(|(name, _)| name)
+            .rev()
+            .max_by_key(|name| self.priority(name));
+        match (current, highest) {
+            (Some(current), Some(highest)) if self.state.get(current).is_some_and(DelayState::is_running)
+                && self.priority(current) >= self.priority(highest) => Some(current.clone()),
+            (_, highest) => highest.cloned(),
+        }
+    }
+
+    /// Records the delays that have ended or started since the last update, and the change of attribution from
+    /// `from`.
+    fn record_events(&mut self, from: Option<&String>) {
+        let to = self.attributed.clone();
+        let (ended, ongoing): (Vec<String>, Vec<String>) = self.started.drain(..)
+            .partition(|name| !self.state.get(name).is_some_and(DelayState::is_in_delay));
+        self.started = ongoing;
+        self.events.extend(ended.into_iter().map(|name| (name, DelayEvent::End)));
+        if let Some(from) = from && to.as_ref() != Some(from) && self.started.contains(from) {
+            self.events.push((from.clone(), DelayEvent::Suspend));
+        }
+
+        let new_delays: Vec<String> = self.state.iter()
+            .filter(|(name, state)| state.is_in_delay() && !self.started.contains(name))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if let Some(to) = &to && from != Some(to) && !new_delays.contains(to) {
+            self.events.push((to.clone(), DelayEvent::Resume));
+        }
+        for name in new_delays {
+            self.events.push((name.clone(), DelayEvent::Start));
+            if to.as_ref() != Some(&name) {
+                self.events.push((name.clone(), DelayEvent::Suspend));
+            }
+            self.started.push(name);
+        }
+    }
+
+    /// Delay events since last taken, in order. Unlike the `DelayStateTransition` returned by each update, these
+    /// include every start and end, of delays whether or not downtime is attributed to them.
+    pub fn take_delay_events(&mut self) -> Vec<(String, DelayEvent)> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Counts `quantity` of throughput towards throughput-triggered delays. A delay whose throughput is used up becomes
+    /// due immediately, and starts on the next update once the policy allows.
+    pub fn record_throughput(&mut self, quantity: f64) {
+        let mut due = Vec::new();
+        for (name, remaining) in self.throughput_remaining.iter_mut() {
+            *remaining -= quantity;
+            if *remaining <= 0. {
+                due.push(name.clone());
+            }
+        }
+        for name in due {
+            self.throughput_remaining.shift_remove(&name);
+            self.state.insert(name, DelayState::TimeUntilDelay(Duration::ZERO));
+        }
+    }
+
+    /// Sets the time until each calendar-triggered delay from its next start. Delays that are due but blocked by
+    /// another delay stay at zero until they can start.
+    fn update_calendar_counters(&mut self, now: MonotonicTime) {
+        for (name, mode) in self.modes.iter() {
+            let DelayTrigger::Calendar(calendar) = &mode.trigger else { continue };
+            if self.state.get(name).is_some_and(DelayState::is_in_delay) || self.forced.contains_key(name) {
+                continue;
+            }
+            if !self.calendar_due.contains_key(name) && !self.state.contains_key(name) {
+                // First update since the mode was added
+                if let Some(first) = calendar.next_start(now, true) {
+                    self.calendar_due.insert(name.clone(), first);
+                }
+            }
+            match self.calendar_due.get(name) {
+                Some(due) => {
+                    let time_until_delay = due.checked_duration_since(now).unwrap_or(Duration::ZERO);
+                    self.state.insert(name.clone(), DelayState::TimeUntilDelay(time_until_delay));
+                },
+                None => {
+                    // No further starts
+                    self.state.shift_remove(name);
+                },
+            }
+        }
+    }
+
+    /// The next delay event - the soonest end of a running fix, or start of a delay. While delayed, only delays that
+    /// can start and whose counters still run are considered.
+    pub fn get_next_event(&self) -> Option<(String, DelayState)> {
+        self.next_event(|_| true)
+    }
+
+    /// The next delay event as `get_next_event`, leaving out counters on clocks that do not run for a process that is
+    /// stopped by its environment or not, and processing or not - see `ElapsedTime`. Busy time accrues while
+    /// processing or in a delay, and neither busy nor available time while stopped.
+    pub fn get_next_event_while(&self, is_env_stopped: bool, is_processing: bool) -> Option<(String, DelayState)> {
+        let is_busy = is_processing || self.is_delayed();
+        self.next_event(|clock| match clock {
+            DelayClock::Busy => is_busy && !is_env_stopped,
+            DelayClock::Available => !is_env_stopped,
+            DelayClock::Calendar => true,
+        })
+    }
+
+    fn next_event(&self, is_running: impl Fn(DelayClock) -> bool) -> Option<(String, DelayState)> {
+        let in_delay = self.is_delayed();
+        self.state.iter().filter(|(name, state)| {
+            let Some(mode) = self.modes.get(*name) else { return false };
+            match state {
+                DelayState::TimeUntilFix(_) => is_running(mode.fix_clock()),
+                DelayState::TimeUntilDelay(duration) => {
+                    // Calendar delays follow the calendar, whatever the mode's clock
+                    let clock = match mode.trigger {
+                        DelayTrigger::Calendar(_) => DelayClock::Calendar,
+                        _ => mode.clock,
+                    };
+                    let can_start = !in_delay || (self.policy != DelayPolicy::Queue && !duration.is_zero() && clock != DelayClock::Busy);
+                    can_start && is_running(clock)
+                },
+                _ => false,
+            }
+        }).min_by_key(|(_, state)| state.as_duration()).map(|(name, state)| (name.clone(), state.clone()))
     }
 
     pub fn modify(&mut self, change: DelayModeChange) {
         match change {
             DelayModeChange::Add(mut mode) => {
-                let time_until_delay = mode.until_delay_distr.sample();
                 let delay_name = mode.name.clone();
-                self.modes.insert(delay_name.clone(), mode);
-                self.state.insert(delay_name, DelayState::TimeUntilDelay(Duration::from_secs_f64(time_until_delay))); // TODO: Units for delay, instead of assuming seconds
+                self.state.shift_remove(&delay_name);
+                self.calendar_due.shift_remove(&delay_name);
+                self.throughput_remaining.shift_remove(&delay_name);
+                self.forced.shift_remove(&delay_name);
+                self.one_off.retain(|x| *x != delay_name);
+                // Calendar delays, and time-varying distributions before the time is known, get their first counter
+                // on the next `update_state_at`
+                let deferred = self.now.is_none() && mode.until_delay_distr.is_time_varying();
+                match mode.trigger {
+                    DelayTrigger::Sampled if !deferred => {
+                        let time_until_delay = mode.sample_until_delay(self.now);
+                        self.state.insert(delay_name.clone(), DelayState::TimeUntilDelay(time_until_delay));
+                    },
+                    DelayTrigger::Throughput if !deferred => {
+                        let throughput_until_delay = mode.sample_throughput_until_delay(self.now);
+                        self.throughput_remaining.insert(delay_name.clone(), throughput_until_delay);
+                    },
+                    _ => {},
+                }
+                self.modes.insert(delay_name, mode);
             },
             DelayModeChange::Remove(name) => {
                 self.modes.shift_remove(&name);
                 self.state.shift_remove(&name);
+                self.calendar_due.shift_remove(&name);
+                self.throughput_remaining.shift_remove(&name);
+                self.forced.shift_remove(&name);
+                self.one_off.retain(|x| *x != name);
             },
             DelayModeChange::RemoveAll => {
                 self.modes.clear();
                 self.state.clear();
-            }
+                self.calendar_due.clear();
+                self.throughput_remaining.clear();
+                self.forced.clear();
+                self.one_off.clear();
+            },
+            DelayModeChange::Force { name, duration } => {
+                if !self.modes.contains_key(&name) {
+                    self.modes.insert(name.clone(), DelayMode { name: name.clone(), ..Default::default() });
+                    self.one_off.push(name.clone());
+                }
+                // A delay already under way is left as it is
+                if !self.state.get(&name).is_some_and(DelayState::is_in_delay) {
+                    self.forced.insert(name.clone(), duration);
+                    self.state.insert(name, DelayState::TimeUntilDelay(Duration::ZERO));
+                }
+            },
         }
     }
 }
@@ -178,15 +810,12 @@ impl DelayModes {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::{DistributionConfig, DistributionFactory};
 
     #[test]
     fn test_transition_for_single_delay() {
         let mut dm = DelayModes::default();
-        dm.modify(DelayModeChange::Add(DelayMode {
-            name: "TestDelay".to_string(),
-            until_delay_distr: Distribution::Constant(13.0),
-            until_fix_distr: Distribution::Constant(5.0),
-        }));
+        dm.modify(DelayModeChange::Add(DelayMode::new("TestDelay", Distribution::Constant(13.0), Distribution::Constant(5.0))));
 
         let update_1 = dm.update_state(Duration::from_secs(4));
         assert_eq!(update_1, DelayStateTransition { from: None, to: None });
@@ -204,16 +833,8 @@ mod tests {
     #[test]
     fn test_transition_for_multiple_delays_and_time_to_next() {
         let mut dm = DelayModes::default();
-        dm.modify(DelayModeChange::Add(DelayMode {
-            name: "Delays1".to_string(),
-            until_delay_distr: Distribution::Constant(13.0),
-            until_fix_distr: Distribution::Constant(5.0),
-        }));
-        dm.modify(DelayModeChange::Add(DelayMode {
-            name: "Delays2".to_string(),
-            until_delay_distr: Distribution::Constant(15.0),
-            until_fix_distr: Distribution::Constant(4.0),
-        }));
+        dm.modify(DelayModeChange::Add(DelayMode::new("Delays1", Distribution::Constant(13.0), Distribution::Constant(5.0))));
+        dm.modify(DelayModeChange::Add(DelayMode::new("Delays2", Distribution::Constant(15.0), Distribution::Constant(4.0))));
 
         let duration_to_next = dm.get_next_event().unwrap().1.as_duration();
         assert_eq!(duration_to_next, Duration::from_secs(13));
@@ -240,4 +861,311 @@ mod tests {
         let update_5 = dm.update_state(duration_to_next);
         assert_eq!(update_5, DelayStateTransition { from: None, to: Some("Delays1".to_string()) });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_delay_time_units() {
+        let mut df = DistributionFactory::new(1);
+        let mut dm = DelayModes::default();
+        dm.modify(DelayModeChange::Add(DelayMode::new("Liner", Distribution::Constant(2.0), df.create("Const(30, unit=min)".parse().unwrap()).unwrap())
+            .with_time_unit(TimeUnit::Hours)));
+
+        let (_, state) = dm.get_next_event().unwrap();
+        assert_eq!(state, DelayState::TimeUntilDelay(Duration::from_secs(7200)));
+        dm.update_state(Duration::from_secs(7200));
+        let (_, state) = dm.get_next_event().unwrap();
+        assert_eq!(state, DelayState::TimeUntilFix(Duration::from_secs(1800)));
+    }
+
+    #[test]
+    fn test_calendar_planned_maintenance() {
+        let mut dm = DelayModes::default();
+        dm.modify(DelayModeChange::Add(DelayMode::new("WeeklyShutdown", Distribution::default(), Distribution::Constant(8.0))
+            .with_time_unit(TimeUnit::Hours)
+            .with_trigger(DelayTrigger::Calendar(MaintenanceCalendar::weekly(1, 6.).unwrap()))
+            .with_category(DelayCategory::Planned)));
+        dm.modify(DelayModeChange::Add(DelayMode::new("Breakdown", Distribution::Constant(1.0), Distribution::Constant(1.0))
+            .with_time_unit(TimeUnit::Hours)));
+        let hours = |h: u64| Duration::from_secs(h * 3600);
+        // Monday 00:00
+        let start = MonotonicTime::try_from_date_time(2025, 1, 6, 0, 0, 0, 0).unwrap();
+
+        // The calendar advances while idle, but the operating-time counter does not
+        assert_eq!(dm.update_state_at(start, ElapsedTime::default()), DelayStateTransition { from: None, to: None });
+        assert_eq!(dm.state["WeeklyShutdown"], DelayState::TimeUntilDelay(hours(30)));
+        let idle = ElapsedTime { calendar: hours(30), available: hours(30), busy: Duration::ZERO };
+        assert_eq!(dm.update_state_at(start + hours(30), idle), DelayStateTransition { from: None, to: Some("WeeklyShutdown".to_string()) });
+        assert_eq!(dm.state["Breakdown"], DelayState::TimeUntilDelay(hours(1)));
+        assert_eq!(dm.category("WeeklyShutdown"), DelayCategory::Planned);
+        assert_eq!(dm.category("Breakdown"), DelayCategory::Unplanned);
+
+        assert_eq!(dm.get_next_event(), Some(("WeeklyShutdown".to_string(), DelayState::TimeUntilFix(hours(8)))));
+        assert_eq!(dm.update_state_at(start + hours(38), ElapsedTime::uniform(hours(8))), DelayStateTransition { from: Some("WeeklyShutdown".to_string()), to: None });
+        assert_eq!(dm.state["WeeklyShutdown"], DelayState::TimeUntilDelay(hours(7 * 24 - 8)));
+    }
+
+    #[test]
+    fn test_throughput_triggered_delay() {
+        let mut dm = DelayModes::default();
+        dm.modify(DelayModeChange::Add(DelayMode::new("LinerChange", Distribution::Constant(200_000.), Distribution::Constant(12.))
+            .with_time_unit(TimeUnit::Hours)
+            .with_trigger(DelayTrigger::Throughput)
+            .with_category(DelayCategory::Planned)));
+
+        // Operating time alone never triggers it
+        assert_eq!(dm.update_state(Duration::from_secs(1_000_000)), DelayStateTransition { from: None, to: None });
+        assert_eq!(dm.get_next_event(), None);
+
+        dm.record_throughput(150_000.);
+        assert_eq!(dm.update_state(Duration::from_secs(60)), DelayStateTransition { from: None, to: None });
+        dm.record_throughput(50_000.);
+        assert_eq!(dm.update_state(Duration::from_secs(60)), DelayStateTransition { from: None, to: Some("LinerChange".to_string()) });
+
+        // Throughput is not counted during the delay, and the count restarts after it
+        dm.record_throughput(100_000.);
+        assert_eq!(dm.update_state(Duration::from_secs(12 * 3600)), DelayStateTransition { from: Some("LinerChange".to_string()), to: None });
+        dm.record_throughput(199_999.);
+        assert_eq!(dm.update_state(Duration::from_secs(60)), DelayStateTransition { from: None, to: None });
+    }
+
+    #[test]
+    fn test_delay_clock_basis() {
+        let mut dm = DelayModes::default();
+        for (name, clock) in [("Wear", DelayClock::Busy), ("Fouling", DelayClock::Available), ("Corrosion", DelayClock::Calendar)] {
+            dm.modify(DelayModeChange::Add(DelayMode {
+                name: name.to_string(),
+                until_delay_distr: Distribution::Constant(10.),
+                until_fix_distr: Distribution::Constant(1.),
+                time_unit: TimeUnit::Hours,
+                clock,
+                ..Default::default()
+            }));
+        }
+        let hours = |h: u64| Duration::from_secs(h * 3600);
+        let start = MonotonicTime::EPOCH;
+
+        // Lightly loaded, and stopped by its environment for part of the time
+        let elapsed = ElapsedTime { calendar: hours(10), available: hours(6), busy: hours(2) };
+        assert_eq!(dm.update_state_at(start + hours(10), elapsed), DelayStateTransition { from: None, to: Some("Corrosion".to_string()) });
+        assert_eq!(dm.state["Wear"], DelayState::TimeUntilDelay(hours(8)));
+        assert_eq!(dm.state["Fouling"], DelayState::TimeUntilDelay(hours(4)));
+
+        // During the delay, busy time does not accrue but available time does
+        let elapsed = ElapsedTime::uniform(hours(1));
+        assert_eq!(dm.update_state_at(start + hours(11), elapsed), DelayStateTransition { from: Some("Corrosion".to_string()), to: None });
+        assert_eq!(dm.state["Wear"], DelayState::TimeUntilDelay(hours(8)));
+        assert_eq!(dm.state["Fouling"], DelayState::TimeUntilDelay(hours(3)));
+        assert_eq!(dm.state["Corrosion"], DelayState::TimeUntilDelay(hours(10)));
+    }
+
+    #[test]
+    fn test_next_event_on_running_clocks() {
+        let mut dm = DelayModes::default();
+        for (name, hours, clock) in [("Wear", 1., DelayClock::Busy), ("Fouling", 2., DelayClock::Available), ("Corrosion", 3., DelayClock::Calendar)] {
+            dm.modify(DelayModeChange::Add(DelayMode::new(name, Distribution::Constant(hours), Distribution::Constant(1.))
+                .with_time_unit(TimeUnit::Hours)
+                .with_clock(clock)));
+        }
+        let next = |dm: &DelayModes, is_env_stopped, is_processing| dm.get_next_event_while(is_env_stopped, is_processing).map(|(name, _)| name);
+
+        assert_eq!(dm.get_next_event().map(|(name, _)| name), Some("Wear".to_string()));
+        assert_eq!(next(&dm, false, true), Some("Wear".to_string()));
+        assert_eq!(next(&dm, false, false), Some("Fouling".to_string()));
+        assert_eq!(next(&dm, true, true), Some("Corrosion".to_string()));
+
+        // Unplanned fixes wait while stopped
+        dm.modify(DelayModeChange::RemoveAll);
+        dm.modify(DelayModeChange::Force { name: "Breakdown".into(), duration: Duration::from_secs(60) });
+        dm.update_state(Duration::ZERO);
+        assert_eq!(next(&dm, false, false), Some("Breakdown".to_string()));
+        assert_eq!(next(&dm, true, false), None);
+    }
+
+    #[test]
+    fn test_fix_clock() {
+        let mut dm = DelayModes { policy: DelayPolicy::Overlap, ..Default::default() };
+        dm.modify(DelayModeChange::Add(DelayMode::new("Reline", Distribution::Constant(2.), Distribution::Constant(4.))
+            .with_time_unit(TimeUnit::Hours)
+            .with_category(DelayCategory::Planned)));
+        dm.modify(DelayModeChange::Add(DelayMode::new("Breakdown", Distribution::Constant(2.), Distribution::Constant(4.))
+            .with_time_unit(TimeUnit::Hours)));
+        let hours = |h: u64| Duration::from_secs(h * 3600);
+        let start = MonotonicTime::EPOCH;
+        assert_eq!(dm.modes["Reline"].fix_clock(), DelayClock::Calendar);
+        assert_eq!(dm.modes["Breakdown"].fix_clock(), DelayClock::Busy);
+
+        dm.update_state_at(start + hours(2), ElapsedTime::uniform(hours(2)));
+        assert!(dm.state.values().all(|state| *state == DelayState::TimeUntilFix(hours(4))));
+
+        // Stopped by the environment for 3 hours - the planned fix carries on, the breakdown fix waits
+        let stopped = ElapsedTime { calendar: hours(3), available: Duration::ZERO, busy: Duration::ZERO };
+        dm.update_state_at(start + hours(5), stopped);
+        assert_eq!(dm.state["Reline"], DelayState::TimeUntilFix(hours(1)));
+        assert_eq!(dm.state["Breakdown"], DelayState::TimeUntilFix(hours(4)));
+
+        dm.update_state_at(start + hours(6), ElapsedTime { calendar: hours(1), ..stopped });
+        assert_eq!(dm.state["Reline"], DelayState::TimeUntilDelay(hours(2)));
+        assert_eq!(dm.state["Breakdown"], DelayState::TimeUntilFix(hours(4)));
+    }
+
+    #[test]
+    fn test_delay_awaiting_crew() {
+        let mut dm = DelayModes::default();
+        dm.modify(DelayModeChange::Add(DelayMode::new("Breakdown", Distribution::Constant(10.), Distribution::Constant(5.))
+            .with_requires_crew(true)));
+
+        // The delay starts, but waits for a crew rather than counting down its fix
+        assert_eq!(dm.update_state(Duration::from_secs(10)), DelayStateTransition { from: None, to: Some("Breakdown".to_string()) });
+        assert_eq!(dm.awaiting_crew(), vec!["Breakdown".to_string()]);
+        assert!(dm.is_delayed());
+        assert!(dm.active_delay().is_none());
+        assert_eq!(dm.update_state(Duration::from_secs(20)), DelayStateTransition { from: Some("Breakdown".to_string()), to: Some("Breakdown".to_string()) });
+
+        assert!(dm.queue_for_crew("Breakdown"));
+        assert!(!dm.queue_for_crew("Breakdown"));
+
+        // Once assigned, the fix counts down as usual, and the crew member is released when it ends
+        assert!(dm.assign_crew("Breakdown"));
+        assert_eq!(dm.state["Breakdown"], DelayState::TimeUntilFix(Duration::from_secs(5)));
+        assert!(dm.release_crews().is_empty());
+        assert_eq!(dm.update_state(Duration::from_secs(5)), DelayStateTransition { from: Some("Breakdown".to_string()), to: None });
+        assert_eq!(dm.state["Breakdown"], DelayState::TimeUntilDelay(Duration::from_secs(10)));
+        assert_eq!(dm.release_crews(), vec!["Breakdown".to_string()]);
+        assert!(dm.release_crews().is_empty());
+    }
+
+    fn add_delay_modes(dm: &mut DelayModes, modes: &[(&str, f64, f64, u32)]) {
+        for (name, until_delay, until_fix, priority) in modes {
+            dm.modify(DelayModeChange::Add(DelayMode {
+                name: name.to_string(),
+                until_delay_distr: Distribution::Constant(*until_delay),
+                until_fix_distr: Distribution::Constant(*until_fix),
+                clock: DelayClock::Calendar,
+                priority: *priority,
+                ..Default::default()
+            }));
+        }
+    }
+
+    #[test]
+    fn test_delay_policy_queue() {
+        let mut dm = DelayModes::default();
+        add_delay_modes(&mut dm, &[("Minor", 10., 5., 0), ("Major", 12., 5., 1), ("Other", 12., 5., 0)]);
+
+        assert_eq!(dm.update_state(Duration::from_secs(10)), DelayStateTransition { from: None, to: Some("Minor".to_string()) });
+        // Both others become due during the delay, and wait for it. The higher priority goes first
+        assert_eq!(dm.update_state(Duration::from_secs(5)), DelayStateTransition { from: Some("Minor".to_string()), to: Some("Major".to_string()) });
+        assert_eq!(dm.state["Other"], DelayState::TimeUntilDelay(Duration::ZERO));
+        assert_eq!(dm.get_next_event(), Some(("Major".to_string(), DelayState::TimeUntilFix(Duration::from_secs(5)))));
+        assert_eq!(dm.update_state(Duration::from_secs(5)), DelayStateTransition { from: Some("Major".to_string()), to: Some("Other".to_string()) });
+    }
+
+    #[test]
+    fn test_delay_policy_overlap() {
+        let mut dm = DelayModes { policy: DelayPolicy::Overlap, ..Default::default() };
+        add_delay_modes(&mut dm, &[("Minor", 10., 5., 0), ("Major", 12., 5., 1), ("Other", 13., 1., 0)]);
+
+        assert_eq!(dm.update_state(Duration::from_secs(10)), DelayStateTransition { from: None, to: Some("Minor".to_string()) });
+        // Calendar-clock delays keep counting down during the delay, so the next event is the start of Major
+        assert_eq!(dm.get_next_event(), Some(("Major".to_string(), DelayState::TimeUntilDelay(Duration::from_secs(2)))));
+
+        // Major starts alongside Minor, and takes the attribution as the higher priority
+        assert_eq!(dm.update_state(Duration::from_secs(2)), DelayStateTransition { from: Some("Minor".to_string()), to: Some("Major".to_string()) });
+        assert_eq!(dm.state["Minor"], DelayState::TimeUntilFix(Duration::from_secs(3)));
+
+        // Other starts and ends during Major, without taking the attribution
+        assert_eq!(dm.update_state(Duration::from_secs(1)), DelayStateTransition { from: Some("Major".to_string()), to: Some("Major".to_string()) });
+        assert_eq!(dm.state["Other"], DelayState::TimeUntilFix(Duration::from_secs(1)));
+        assert_eq!(dm.update_state(Duration::from_secs(1)), DelayStateTransition { from: Some("Major".to_string()), to: Some("Major".to_string()) });
+        assert_eq!(dm.state["Other"], DelayState::TimeUntilDelay(Duration::from_secs(13)));
+
+        // Minor is fixed before Major, which is then fixed in turn
+        assert_eq!(dm.update_state(Duration::from_secs(1)), DelayStateTransition { from: Some("Major".to_string()), to: Some("Major".to_string()) });
+        assert_eq!(dm.state["Minor"], DelayState::TimeUntilDelay(Duration::from_secs(10)));
+        assert_eq!(dm.update_state(Duration::from_secs(2)), DelayStateTransition { from: Some("Major".to_string()), to: None });
+        assert!(!dm.is_delayed());
+
+        // Every delay starts and ends once, with Other's downtime attributed to Major throughout
+        assert_eq!(dm.take_delay_events(), delay_events(&[
+            ("Minor", DelayEvent::Start), ("Minor", DelayEvent::Suspend), ("Major", DelayEvent::Start), ("Other", DelayEvent::Start),
+            ("Other", DelayEvent::Suspend), ("Other", DelayEvent::End), ("Minor", DelayEvent::End), ("Major", DelayEvent::End),
+        ]));
+        assert!(dm.take_delay_events().is_empty());
+    }
+
+    #[test]
+    fn test_delay_policy_preempt() {
+        let mut dm = DelayModes { policy: DelayPolicy::Preempt, ..Default::default() };
+        add_delay_modes(&mut dm, &[("Minor", 10., 5., 0), ("Major", 12., 4., 1), ("Other", 13., 1., 0)]);
+
+        assert_eq!(dm.update_state(Duration::from_secs(10)), DelayStateTransition { from: None, to: Some("Minor".to_string()) });
+
+        // Major suspends Minor with 3s of its fix remaining
+        assert_eq!(dm.update_state(Duration::from_secs(2)), DelayStateTransition { from: Some("Minor".to_string()), to: Some("Major".to_string()) });
+        assert_eq!(dm.state["Minor"], DelayState::Suspended(Box::new(DelayState::TimeUntilFix(Duration::from_secs(3)))));
+        assert!(dm.is_delayed());
+
+        // Other is due during Major, but can't preempt it, and waits behind the suspended Minor
+        assert_eq!(dm.update_state(Duration::from_secs(1)), DelayStateTransition { from: Some("Major".to_string()), to: Some("Major".to_string()) });
+        assert_eq!(dm.update_state(Duration::from_secs(3)), DelayStateTransition { from: Some("Major".to_string()), to: Some("Minor".to_string()) });
+        assert_eq!(dm.state["Minor"], DelayState::TimeUntilFix(Duration::from_secs(3)));
+        assert_eq!(dm.update_state(Duration::from_secs(3)), DelayStateTransition { from: Some("Minor".to_string()), to: Some("Other".to_string()) });
+        assert_eq!(dm.take_delay_events(), delay_events(&[
+            ("Minor", DelayEvent::Start), ("Minor", DelayEvent::Suspend), ("Major", DelayEvent::Start), ("Major", DelayEvent::End),
+            ("Minor", DelayEvent::Resume), ("Minor", DelayEvent::End), ("Other", DelayEvent::Start),
+        ]));
+    }
+
+    fn delay_events(events: &[(&str, DelayEvent)]) -> Vec<(String, DelayEvent)> {
+        events.iter().map(|(name, event)| (name.to_string(), *event)).collect()
+    }
+
+    #[test]
+    fn test_forced_delay() {
+        let mut dm = DelayModes::default();
+        add_delay_modes(&mut dm, &[("Minor", 10., 5., 0)]);
+
+        // A forced delay on a known mode uses the given duration, then resamples as usual
+        dm.modify(DelayModeChange::Force { name: "Minor".into(), duration: Duration::from_secs(2) });
+        assert_eq!(dm.update_state(Duration::ZERO), DelayStateTransition { from: None, to: Some("Minor".to_string()) });
+        assert_eq!(dm.state["Minor"], DelayState::TimeUntilFix(Duration::from_secs(2)));
+        assert_eq!(dm.update_state(Duration::from_secs(2)), DelayStateTransition { from: Some("Minor".to_string()), to: None });
+        assert_eq!(dm.state["Minor"], DelayState::TimeUntilDelay(Duration::from_secs(10)));
+
+        // An unknown name is a one-off, removed once fixed
+        dm.modify(DelayModeChange::Force { name: "Breakdown".into(), duration: Duration::from_secs(3) });
+        assert_eq!(dm.update_state(Duration::ZERO), DelayStateTransition { from: None, to: Some("Breakdown".to_string()) });
+        assert_eq!(dm.update_state(Duration::from_secs(3)), DelayStateTransition { from: Some("Breakdown".to_string()), to: None });
+        assert!(!dm.modes.contains_key("Breakdown"));
+        assert!(!dm.state.contains_key("Breakdown"));
+    }
+
+    #[test]
+    fn test_time_varying_delay_distributions() {
+        let mut df = DistributionFactory::new(1);
+        let hours = |h: u64| Duration::from_secs(h * 3600);
+        let day = MonotonicTime::try_from_date_time(2025, 1, 1, 0, 0, 0, 0).unwrap();
+        // Delays are due sooner during the day shift, and take longer to fix at night
+        let until_delay = df.create(DistributionConfig::Scheduled {
+            index: ScheduleIndex::HourOfDay,
+            segments: vec![(0., DistributionConfig::Constant(10.)), (6., DistributionConfig::Constant(1.))],
+        }).unwrap();
+        let until_fix = df.create(DistributionConfig::Scheduled {
+            index: ScheduleIndex::HourOfDay,
+            segments: vec![(6., DistributionConfig::Constant(1.)), (18., DistributionConfig::Constant(3.))],
+        }).unwrap();
+        let mut dm = DelayModes::default();
+        dm.modify(DelayModeChange::Add(DelayMode::new("Breakdown", until_delay, until_fix).with_time_unit(TimeUnit::Hours)));
+
+        // Added before the time is known, so first sampled at the first update
+        assert!(dm.get_next_event().is_none());
+        dm.update_state_at(day + hours(7), ElapsedTime::default());
+        assert_eq!(dm.state["Breakdown"], DelayState::TimeUntilDelay(hours(1)));
+
+        dm.update_state_at(day + hours(8), ElapsedTime::uniform(hours(1)));
+        assert_eq!(dm.state["Breakdown"], DelayState::TimeUntilFix(hours(1)));
+        dm.update_state_at(day + hours(9), ElapsedTime::uniform(hours(1)));
+        assert_eq!(dm.state["Breakdown"], DelayState::TimeUntilDelay(hours(1)));
+        dm.update_state_at(day + hours(19), ElapsedTime::uniform(hours(10)));
+        assert_eq!(dm.state["Breakdown"], DelayState::TimeUntilFix(hours(3)));
+    }
+}
@@ -0,0 +1,728 @@
+//! Compact textual form of `DistributionConfig`, e.g. `Tri(10, 20, 15)`, `Exp(mean=30)` or
+//! `TruncNormal(30, 10, min=1)`.
+//!
+//! Each distribution is written as its name followed by its parameters in brackets. Parameters may be given
+//! positionally, in the order of the fields of the corresponding `DistributionConfig` variant, or by name. A bare
+//! number is a Constant. Lists are written in square brackets, and weighted or scheduled entries as `key: value`:
+//!
+//! ```text
+//! Mixture(0.8: Exp(5), 0.2: Uniform(50, 70))
+//! Scheduled(HourOfDay, 6: Exp(120), 18: Exp(600))
+//! Empirical([4.1, 5.3, 6.0, 7.2])
+//! Discrete(file="grades.csv")
+//! ```
+//!
+//! Any distribution may declare the time unit of its samples with a `unit` parameter (`s`, `min`, `h` or `d`), e.g.
+//! `Exp(30, unit=min)`, which gives a `DistributionConfig::Timed`.
+//!
+//! `DistributionConfig` implements `FromStr` and `Display` with this syntax, and deserialises from either a string in
+//! this form or the tagged serde form.
+
+use std::{error::Error, fmt::{Display, Formatter, Result as FmtResult}, str::FromStr};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::common::{DiscreteData, DistributionConfig, EmpiricalData, ScheduleIndex, TimeUnit};
+
+/// Error from parsing the compact form of a `DistributionConfig`. `position` is the 0-based character offset in the
+/// input at which the problem was found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistributionParseError {
+    pub msg: String,
+    pub position: usize,
+}
+
+impl Error for DistributionParseError {}
+
+impl Display for DistributionParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} at position {}", self.msg, self.position)
+    }
+}
+
+fn parse_error<T>(msg: impl Into<String>, position: usize) -> Result<T, DistributionParseError> {
+    Err(DistributionParseError { msg: msg.into(), position })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    Open,
+    Close,
+    OpenList,
+    CloseList,
+    Comma,
+    Equals,
+    Colon,
+    End,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Number(x) => write!(f, "number {}", x),
+            Token::Str(s) => write!(f, "string {:?}", s),
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+            Token::OpenList => write!(f, "'['"),
+            Token::CloseList => write!(f, "']'"),
+            Token::Comma => write!(f, "','"),
+            Token::Equals => write!(f, "'='"),
+            Token::Colon => write!(f, "':'"),
+            Token::End => write!(f, "end of input"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, DistributionParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let simple = match c {
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            '[' => Some(Token::OpenList),
+            ']' => Some(Token::CloseList),
+            ',' => Some(Token::Comma),
+            '=' => Some(Token::Equals),
+            ':' => Some(Token::Colon),
+            _ => None,
+        };
+        if let Some(token) = simple {
+            tokens.push((token, start));
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' || c == '-' || c == '+' {
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.'
+                || ((chars[i] == '-' || chars[i] == '+') && matches!(chars[i - 1], 'e' | 'E'))) {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            match text.parse::<f64>() {
+                Ok(x) => tokens.push((Token::Number(x), start)),
+                Err(_) => return parse_error(format!("Invalid number '{}'", text), start),
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), start));
+        } else if c == '"' {
+            i += 1;
+            let mut s = String::new();
+            loop {
+                match chars.get(i) {
+                    None => return parse_error("Unterminated string", start),
+                    Some('"') => break,
+                    Some('\\') => {
+                        match chars.get(i + 1) {
+                            Some(escaped @ ('"' | '\\')) => s.push(*escaped),
+                            _ => return parse_error("Invalid escape in string, expected \\\" or \\\\", i),
+                        }
+                        i += 2;
+                    },
+                    Some(other) => {
+                        s.push(*other);
+                        i += 1;
+                    },
+                }
+            }
+            tokens.push((Token::Str(s), start));
+            i += 1;
+        } else {
+            return parse_error(format!("Unexpected character '{}'", c), start);
+        }
+    }
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone)]
+enum NodeKind {
+    Number(f64),
+    Str(String),
+    /// Name, optionally followed by bracketed arguments
+    Call(String, Option<Vec<Arg>>),
+    List(Vec<Node>),
+    Pair(Box<Node>, Box<Node>),
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    kind: NodeKind,
+    position: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Arg {
+    key: Option<String>,
+    value: Node,
+    position: usize,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].0
+    }
+
+    fn position(&self) -> usize {
+        self.tokens[self.index].1
+    }
+
+    fn next(&mut self) -> (Token, usize) {
+        let token = self.tokens[self.index].clone();
+        if token.0 != Token::End {
+            self.index += 1;
+        }
+        token
+    }
+
+    /// value [':' value]
+    fn parse_entry(&mut self) -> Result<Node, DistributionParseError> {
+        let key = self.parse_value()?;
+        if *self.peek() == Token::Colon {
+            self.next();
+            let value = self.parse_value()?;
+            Ok(Node { position: key.position, kind: NodeKind::Pair(Box::new(key), Box::new(value)) })
+        } else {
+            Ok(key)
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Node, DistributionParseError> {
+        let (token, position) = self.next();
+        let kind = match token {
+            Token::Number(x) => NodeKind::Number(x),
+            Token::Str(s) => NodeKind::Str(s),
+            Token::Ident(name) => {
+                if *self.peek() == Token::Open {
+                    self.next();
+                    NodeKind::Call(name, Some(self.parse_args()?))
+                } else {
+                    NodeKind::Call(name, None)
+                }
+            },
+            Token::OpenList => {
+                let mut items = Vec::new();
+                if *self.peek() == Token::CloseList {
+                    self.next();
+                } else {
+                    loop {
+                        items.push(self.parse_entry()?);
+                        match self.next() {
+                            (Token::Comma, _) => {},
+                            (Token::CloseList, _) => break,
+                            (token, position) => return parse_error(format!("Expected ',' or ']', found {}", token), position),
+                        }
+                    }
+                }
+                NodeKind::List(items)
+            },
+            token => return parse_error(format!("Expected a distribution, number, string or list, found {}", token), position),
+        };
+        Ok(Node { kind, position })
+    }
+
+    /// Arguments up to and including the closing bracket
+    fn parse_args(&mut self) -> Result<Vec<Arg>, DistributionParseError> {
+        let mut args = Vec::new();
+        if *self.peek() == Token::Close {
+            self.next();
+            return Ok(args);
+        }
+        loop {
+            let position = self.position();
+            let key = match (self.peek().clone(), &self.tokens.get(self.index + 1).map(|t| &t.0)) {
+                (Token::Ident(name), Some(Token::Equals)) => {
+                    self.next();
+                    self.next();
+                    Some(name)
+                },
+                _ => None,
+            };
+            let value = self.parse_entry()?;
+            args.push(Arg { key, value, position });
+            match self.next() {
+                (Token::Comma, _) => {},
+                (Token::Close, _) => break,
+                (token, position) => return parse_error(format!("Expected ',' or ')', found {}", token), position),
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Assigns arguments to named parameters, positionally and then by keyword. The first `required` parameters must be
+/// present.
+fn bind<'a>(name: &str, position: usize, args: &'a [Arg], params: &[&str], required: usize) -> Result<Vec<Option<&'a Node>>, DistributionParseError> {
+    let mut bound: Vec<Option<&Node>> = vec![None; params.len()];
+    let mut seen_keyword = false;
+    for (i, arg) in args.iter().enumerate() {
+        let slot = match &arg.key {
+            None if seen_keyword => return parse_error(format!("Positional argument follows named argument in {}", name), arg.position),
+            None if i >= params.len() => {
+                return parse_error(format!("{} takes at most {} arguments ({}), got {}", name, params.len(), params.join(", "), args.len()), arg.position)
+            },
+            None => i,
+            Some(key) => {
+                seen_keyword = true;
+                match params.iter().position(|p| p == key) {
+                    Some(slot) => slot,
+                    None => return parse_error(format!("{} has no parameter '{}', expected one of: {}", name, key, params.join(", ")), arg.position),
+                }
+            },
+        };
+        if bound[slot].is_some() {
+            return parse_error(format!("Parameter '{}' of {} given more than once", params[slot], name), arg.position);
+        }
+        bound[slot] = Some(&arg.value);
+    }
+    if let Some(missing) = (0..required).find(|i| bound[*i].is_none()) {
+        return parse_error(format!("{} requires parameter '{}' ({} required: {})", name, params[missing], required, params[..required].join(", ")), position);
+    }
+    Ok(bound)
+}
+
+fn number(node: &Node) -> Result<f64, DistributionParseError> {
+    match node.kind {
+        NodeKind::Number(x) => Ok(x),
+        _ => parse_error("Expected a number", node.position),
+    }
+}
+
+fn optional_number(node: Option<&Node>) -> Result<Option<f64>, DistributionParseError> {
+    node.map(number).transpose()
+}
+
+fn string(node: &Node) -> Result<String, DistributionParseError> {
+    match &node.kind {
+        NodeKind::Str(s) => Ok(s.clone()),
+        _ => parse_error("Expected a quoted string", node.position),
+    }
+}
+
+fn numbers(node: &Node) -> Result<Vec<f64>, DistributionParseError> {
+    match &node.kind {
+        NodeKind::List(items) => items.iter().map(number).collect(),
+        _ => parse_error("Expected a list of numbers, e.g. [1, 2, 3]", node.position),
+    }
+}
+
+fn pair(node: &Node) -> Result<(&Node, &Node), DistributionParseError> {
+    match &node.kind {
+        NodeKind::Pair(key, value) => Ok((key, value)),
+        _ => parse_error("Expected an entry of the form 'key: value'", node.position),
+    }
+}
+
+fn schedule_index(node: &Node) -> Result<ScheduleIndex, DistributionParseError> {
+    match &node.kind {
+        NodeKind::Call(name, None) if name == "HourOfDay" => Ok(ScheduleIndex::HourOfDay),
+        NodeKind::Call(name, None) if name == "DayOfWeek" => Ok(ScheduleIndex::DayOfWeek),
+        NodeKind::Call(name, args) if name == "Piecewise" => {
+            let args = args.as_deref().unwrap_or_default();
+            let bound = bind(name, node.position, args, &["period"], 0)?;
+            Ok(ScheduleIndex::Piecewise { period: optional_number(bound[0])? })
+        },
+        _ => parse_error("Expected a schedule index: HourOfDay, DayOfWeek or Piecewise(period=...)", node.position),
+    }
+}
+
+fn time_unit(node: &Node) -> Result<TimeUnit, DistributionParseError> {
+    match &node.kind {
+        NodeKind::Call(name, None) => match name.as_str() {
+            "s" | "sec" | "seconds" => Ok(TimeUnit::Seconds),
+            "min" | "minutes" => Ok(TimeUnit::Minutes),
+            "h" | "hours" => Ok(TimeUnit::Hours),
+            "d" | "days" => Ok(TimeUnit::Days),
+            _ => parse_error(format!("Unknown time unit '{}', expected one of: s, min, h, d", name), node.position),
+        },
+        _ => parse_error("Expected a time unit: s, min, h or d", node.position),
+    }
+}
+
+type Segments<'a> = Vec<(f64, &'a Node)>;
+
+/// Remaining arguments of Scheduled and NonHomogeneousPoisson, as `start: value` pairs
+fn segments<'a>(name: &str, position: usize, args: &'a [Arg]) -> Result<(ScheduleIndex, Segments<'a>), DistributionParseError> {
+    if let Some(arg) = args.iter().find(|a| a.key.is_some()) {
+        return parse_error(format!("{} takes a schedule index followed by 'start: value' entries, not named parameters", name), arg.position);
+    }
+    let Some((first, rest)) = args.split_first() else {
+        return parse_error(format!("{} requires a schedule index", name), position);
+    };
+    let index = schedule_index(&first.value)?;
+    let entries = rest.iter().map(|a| {
+        let (start, value) = pair(&a.value)?;
+        Ok((number(start)?, value))
+    }).collect::<Result<Vec<_>, _>>()?;
+    Ok((index, entries))
+}
+
+const NAMES: &str = "Uniform, Triangular (Tri), Constant (Const), Normal, TruncNormal, Exponential (Exp), LogNormal, Gamma, \
+    Weibull, Erlang, Beta, Pert, Empirical, Discrete, Mixture, Shifted, Scaled, Clamped, Scheduled, NonHomogeneousPoisson (NHPP)";
+
+fn config(node: &Node) -> Result<DistributionConfig, DistributionParseError> {
+    let (name, args) = match &node.kind {
+        NodeKind::Number(x) => return Ok(DistributionConfig::Constant(*x)),
+        NodeKind::Call(name, Some(args)) => (name.as_str(), args.as_slice()),
+        NodeKind::Call(name, None) => return parse_error(format!("Expected '(' after distribution name '{}'", name), node.position),
+        _ => return parse_error(format!("Expected a distribution, one of: {}", NAMES), node.position),
+    };
+    let position = node.position;
+    if let Some(i) = args.iter().position(|a| a.key.as_deref() == Some("unit")) {
+        let unit = time_unit(&args[i].value)?;
+        let mut rest = args.to_vec();
+        rest.remove(i);
+        let inner = Node { kind: NodeKind::Call(name.to_string(), Some(rest)), position };
+        return Ok(DistributionConfig::Timed { unit, inner: Box::new(config(&inner)?) });
+    }
+    let config = match name {
+        "Uniform" => {
+            let a = bind(name, position, args, &["min", "max"], 2)?;
+            DistributionConfig::Uniform { min: number(a[0].unwrap())?, max: number(a[1].unwrap())? }
+        },
+        "Triangular" | "Tri" => {
+            let a = bind(name, position, args, &["min", "max", "mode"], 3)?;
+            DistributionConfig::Triangular { min: number(a[0].unwrap())?, max: number(a[1].unwrap())?, mode: number(a[2].unwrap())? }
+        },
+        "Constant" | "Const" => {
+            let a = bind(name, position, args, &["value"], 1)?;
+            DistributionConfig::Constant(number(a[0].unwrap())?)
+        },
+        "Normal" => {
+            let a = bind(name, position, args, &["mean", "std"], 2)?;
+            DistributionConfig::Normal { mean: number(a[0].unwrap())?, std: number(a[1].unwrap())? }
+        },
+        "TruncNormal" => {
+            let a = bind(name, position, args, &["mean", "std", "min", "max"], 2)?;
+            DistributionConfig::TruncNormal { mean: number(a[0].unwrap())?, std: number(a[1].unwrap())?, min: optional_number(a[2])?, max: optional_number(a[3])? }
+        },
+        "Exponential" | "Exp" => {
+            let a = bind(name, position, args, &["mean"], 1)?;
+            DistributionConfig::Exponential { mean: number(a[0].unwrap())? }
+        },
+        "LogNormal" => {
+            let a = bind(name, position, args, &["mean", "std"], 2)?;
+            DistributionConfig::LogNormal { mean: number(a[0].unwrap())?, std: number(a[1].unwrap())? }
+        },
+        "Gamma" => {
+            let a = bind(name, position, args, &["shape", "scale"], 2)?;
+            DistributionConfig::Gamma { shape: number(a[0].unwrap())?, scale: number(a[1].unwrap())? }
+        },
+        "Weibull" => {
+            let a = bind(name, position, args, &["shape", "scale"], 2)?;
+            DistributionConfig::Weibull { shape: number(a[0].unwrap())?, scale: number(a[1].unwrap())? }
+        },
+        "Erlang" => {
+            let a = bind(name, position, args, &["k", "mean"], 2)?;
+            let k = a[0].unwrap();
+            let k_value = number(k)?;
+            if k_value < 0. || k_value.fract() != 0. || k_value > u32::MAX as f64 {
+                return parse_error("Erlang k must be a non-negative integer", k.position);
+            }
+            DistributionConfig::Erlang { k: k_value as u32, mean: number(a[1].unwrap())? }
+        },
+        "Beta" => {
+            let a = bind(name, position, args, &["alpha", "beta", "min", "max"], 4)?;
+            DistributionConfig::Beta { alpha: number(a[0].unwrap())?, beta: number(a[1].unwrap())?, min: number(a[2].unwrap())?, max: number(a[3].unwrap())? }
+        },
+        "Pert" => {
+            let a = bind(name, position, args, &["min", "max", "mode"], 3)?;
+            DistributionConfig::Pert { min: number(a[0].unwrap())?, max: number(a[1].unwrap())?, mode: number(a[2].unwrap())? }
+        },
+        "Empirical" => {
+            let a = bind(name, position, args, &["samples", "edges", "counts", "file", "histogram_file"], 0)?;
+            let data = match (a[0], a[1], a[2], a[3], a[4]) {
+                (Some(samples), None, None, None, None) => EmpiricalData::Samples(numbers(samples)?),
+                (None, Some(edges), Some(counts), None, None) => EmpiricalData::Histogram { bin_edges: numbers(edges)?, counts: numbers(counts)? },
+                (None, None, None, Some(path), None) => EmpiricalData::SamplesCsv { path: string(path)? },
+                (None, None, None, None, Some(path)) => EmpiricalData::HistogramCsv { path: string(path)? },
+                _ => return parse_error("Empirical requires exactly one of: samples, edges and counts, file, or histogram_file", position),
+            };
+            DistributionConfig::Empirical { data }
+        },
+        "Discrete" => {
+            let a = bind(name, position, args, &["values", "weights", "file"], 0)?;
+            let data = match (a[0], a[1], a[2]) {
+                (Some(values), Some(weights), None) => DiscreteData::Points { values: numbers(values)?, weights: numbers(weights)? },
+                (None, None, Some(path)) => DiscreteData::Csv { path: string(path)? },
+                _ => return parse_error("Discrete requires either values and weights, or file", position),
+            };
+            DistributionConfig::Discrete { data }
+        },
+        "Mixture" => {
+            if let Some(arg) = args.iter().find(|a| a.key.is_some()) {
+                return parse_error("Mixture takes 'weight: distribution' entries, not named parameters", arg.position);
+            }
+            let components = args.iter().map(|a| {
+                let (weight, inner) = pair(&a.value)?;
+                Ok((number(weight)?, config(inner)?))
+            }).collect::<Result<Vec<_>, _>>()?;
+            DistributionConfig::Mixture { components }
+        },
+        "Shifted" => {
+            let a = bind(name, position, args, &["offset", "inner"], 2)?;
+            DistributionConfig::Shifted { offset: number(a[0].unwrap())?, inner: Box::new(config(a[1].unwrap())?) }
+        },
+        "Scaled" => {
+            let a = bind(name, position, args, &["factor", "inner"], 2)?;
+            DistributionConfig::Scaled { factor: number(a[0].unwrap())?, inner: Box::new(config(a[1].unwrap())?) }
+        },
+        "Clamped" => {
+            let a = bind(name, position, args, &["inner", "min", "max"], 1)?;
+            DistributionConfig::Clamped { min: optional_number(a[1])?, max: optional_number(a[2])?, inner: Box::new(config(a[0].unwrap())?) }
+        },
+        "Scheduled" => {
+            let (index, entries) = segments(name, position, args)?;
+            let segments = entries.into_iter().map(|(start, inner)| Ok((start, config(inner)?))).collect::<Result<Vec<_>, _>>()?;
+            DistributionConfig::Scheduled { index, segments }
+        },
+        "NonHomogeneousPoisson" | "NHPP" => {
+            let (index, entries) = segments(name, position, args)?;
+            let rates = entries.into_iter().map(|(start, rate)| Ok((start, number(rate)?))).collect::<Result<Vec<_>, _>>()?;
+            DistributionConfig::NonHomogeneousPoisson { index, rates }
+        },
+        _ => return parse_error(format!("Unknown distribution '{}', expected one of: {}", name, NAMES), position),
+    };
+    Ok(config)
+}
+
+impl FromStr for DistributionConfig {
+    type Err = DistributionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { tokens: tokenize(s)?, index: 0 };
+        let node = parser.parse_value()?;
+        let result = config(&node)?;
+        match parser.next() {
+            (Token::End, _) => Ok(result),
+            (token, position) => parse_error(format!("Expected end of input, found {}", token), position),
+        }
+    }
+}
+
+struct NumberList<'a>(&'a [f64]);
+
+impl Display for NumberList<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "[")?;
+        for (i, x) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", x)?;
+        }
+        write!(f, "]")
+    }
+}
+
+struct Quoted<'a>(&'a str);
+
+impl Display for Quoted<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "\"{}\"", self.0.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+impl Display for ScheduleIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ScheduleIndex::HourOfDay => write!(f, "HourOfDay"),
+            ScheduleIndex::DayOfWeek => write!(f, "DayOfWeek"),
+            ScheduleIndex::Piecewise { period: None } => write!(f, "Piecewise"),
+            ScheduleIndex::Piecewise { period: Some(period) } => write!(f, "Piecewise(period={})", period),
+        }
+    }
+}
+
+impl Display for TimeUnit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            TimeUnit::Seconds => write!(f, "s"),
+            TimeUnit::Minutes => write!(f, "min"),
+            TimeUnit::Hours => write!(f, "h"),
+            TimeUnit::Days => write!(f, "d"),
+        }
+    }
+}
+
+impl Display for DistributionConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DistributionConfig::Uniform { min, max } => write!(f, "Uniform({}, {})", min, max),
+            DistributionConfig::Triangular { min, max, mode } => write!(f, "Triangular({}, {}, {})", min, max, mode),
+            DistributionConfig::Constant(x) => write!(f, "Constant({})", x),
+            DistributionConfig::Normal { mean, std } => write!(f, "Normal({}, {})", mean, std),
+            DistributionConfig::TruncNormal { mean, std, min, max } => {
+                write!(f, "TruncNormal({}, {}", mean, std)?;
+                if let Some(min) = min {
+                    write!(f, ", min={}", min)?;
+                }
+                if let Some(max) = max {
+                    write!(f, ", max={}", max)?;
+                }
+                write!(f, ")")
+            },
+            DistributionConfig::Exponential { mean } => write!(f, "Exponential({})", mean),
+            DistributionConfig::LogNormal { mean, std } => write!(f, "LogNormal({}, {})", mean, std),
+            DistributionConfig::Gamma { shape, scale } => write!(f, "Gamma({}, {})", shape, scale),
+            DistributionConfig::Weibull { shape, scale } => write!(f, "Weibull({}, {})", shape, scale),
+            DistributionConfig::Erlang { k, mean } => write!(f, "Erlang({}, {})", k, mean),
+            DistributionConfig::Beta { alpha, beta, min, max } => write!(f, "Beta({}, {}, {}, {})", alpha, beta, min, max),
+            DistributionConfig::Pert { min, max, mode } => write!(f, "Pert({}, {}, {})", min, max, mode),
+            DistributionConfig::Empirical { data } => match data {
+                EmpiricalData::Samples(samples) => write!(f, "Empirical({})", NumberList(samples)),
+                EmpiricalData::Histogram { bin_edges, counts } => write!(f, "Empirical(edges={}, counts={})", NumberList(bin_edges), NumberList(counts)),
+                EmpiricalData::SamplesCsv { path } => write!(f, "Empirical(file={})", Quoted(path)),
+                EmpiricalData::HistogramCsv { path } => write!(f, "Empirical(histogram_file={})", Quoted(path)),
+            },
+            DistributionConfig::Discrete { data } => match data {
+                DiscreteData::Points { values, weights } => write!(f, "Discrete(values={}, weights={})", NumberList(values), NumberList(weights)),
+                DiscreteData::Csv { path } => write!(f, "Discrete(file={})", Quoted(path)),
+            },
+            DistributionConfig::Mixture { components } => {
+                write!(f, "Mixture(")?;
+                for (i, (weight, inner)) in components.iter().enumerate() {
+                    write!(f, "{}{}: {}", if i > 0 { ", " } else { "" }, weight, inner)?;
+                }
+                write!(f, ")")
+            },
+            DistributionConfig::Shifted { offset, inner } => write!(f, "Shifted({}, {})", offset, inner),
+            DistributionConfig::Scaled { factor, inner } => write!(f, "Scaled({}, {})", factor, inner),
+            DistributionConfig::Clamped { min, max, inner } => {
+                write!(f, "Clamped({}", inner)?;
+                if let Some(min) = min {
+                    write!(f, ", min={}", min)?;
+                }
+                if let Some(max) = max {
+                    write!(f, ", max={}", max)?;
+                }
+                write!(f, ")")
+            },
+            DistributionConfig::Scheduled { index, segments } => {
+                write!(f, "Scheduled({}", index)?;
+                for (start, inner) in segments {
+                    write!(f, ", {}: {}", start, inner)?;
+                }
+                write!(f, ")")
+            },
+            DistributionConfig::NonHomogeneousPoisson { index, rates } => {
+                write!(f, "NonHomogeneousPoisson({}", index)?;
+                for (start, rate) in rates {
+                    write!(f, ", {}: {}", start, rate)?;
+                }
+                write!(f, ")")
+            },
+            DistributionConfig::Timed { unit, inner } => {
+                // Every other form ends in ')', before which the unit is inserted
+                let inner = inner.to_string();
+                write!(f, "{}, unit={})", inner.strip_suffix(')').unwrap_or(&inner), unit)
+            },
+        }
+    }
+}
+
+// `DistributionConfig` derives serde with `remote = "Self"`, which generates the tagged implementations as inherent
+// functions of the same names, called below.
+
+/// Serialises in the tagged form, so that existing JSON consumers are unaffected.
+impl Serialize for DistributionConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DistributionConfig::serialize(self, serializer)
+    }
+}
+
+/// Deserialises from either the compact string form or the tagged form.
+impl<'de> Deserialize<'de> for DistributionConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+            value => DistributionConfig::deserialize(value).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_examples() {
+        let parsed: DistributionConfig = "Tri(10, 20, 15)".parse().unwrap();
+        assert!(matches!(parsed, DistributionConfig::Triangular { min: 10., max: 20., mode: 15. }));
+        let parsed: DistributionConfig = "Exp(mean=30)".parse().unwrap();
+        assert!(matches!(parsed, DistributionConfig::Exponential { mean: 30. }));
+        let parsed: DistributionConfig = "TruncNormal(30, 10, min=1)".parse().unwrap();
+        assert!(matches!(parsed, DistributionConfig::TruncNormal { mean: 30., std: 10., min: Some(1.), max: None }));
+        let parsed: DistributionConfig = " 2.5e1 ".parse().unwrap();
+        assert!(matches!(parsed, DistributionConfig::Constant(25.)));
+        let parsed: DistributionConfig = "Tri(unit=h, 1, 3, 2)".parse().unwrap();
+        let DistributionConfig::Timed { unit: TimeUnit::Hours, inner } = parsed else { panic!("Expected Timed, got {:?}", parsed) };
+        assert!(matches!(*inner, DistributionConfig::Triangular { min: 1., max: 3., mode: 2. }));
+    }
+
+    #[test]
+    fn test_print_and_parse_round_trip() {
+        let texts = [
+            "Uniform(-1.5, 2)",
+            "TruncNormal(30, 10, min=1, max=50)",
+            "Erlang(3, 12)",
+            "Empirical([1, 2.5, 4])",
+            "Empirical(edges=[0, 10, 20], counts=[3, 1])",
+            "Discrete(file=\"data/grade \\\"A\\\".csv\")",
+            "Mixture(0.8: Exponential(5), 0.2: Shifted(50, Uniform(0, 20)))",
+            "Clamped(Normal(10, 4), min=0)",
+            "Scheduled(HourOfDay, 6: Exponential(120), 18: Constant(600))",
+            "NonHomogeneousPoisson(Piecewise(period=86400), 0: 0.001, 43200: 0)",
+            "Exponential(30, unit=min)",
+            "Mixture(0.8: Exponential(5), 0.2: Uniform(50, 70), unit=h)",
+        ];
+        for text in texts {
+            let parsed: DistributionConfig = text.parse().unwrap_or_else(|e| panic!("{}: {}", text, e));
+            assert_eq!(parsed.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("Tri(10, 20)", "Tri requires parameter 'mode'", 0),
+            ("Exp(mean=30, mean=40)", "Parameter 'mean' of Exp given more than once", 13),
+            ("Exp(meen=30)", "Exp has no parameter 'meen'", 4),
+            ("Normal(1, 2", "Expected ',' or ')', found end of input", 11),
+            ("Norml(1, 2)", "Unknown distribution 'Norml'", 0),
+            ("Uniform(1, 2) x", "Expected end of input, found 'x'", 14),
+            ("Uniform(1, x)", "Expected a number", 11),
+            ("Shifted(1, x)", "Expected '(' after distribution name 'x'", 11),
+            ("Mixture(0.5 Exp(1))", "Expected ',' or ')', found 'Exp'", 12),
+            ("Uniform(1, 2e)", "Invalid number '2e'", 11),
+            ("Exp(30, unit=hr)", "Unknown time unit 'hr'", 13),
+        ];
+        for (text, msg, position) in cases {
+            let err = text.parse::<DistributionConfig>().unwrap_err();
+            assert!(err.msg.starts_with(msg), "{}: {}", text, err);
+            assert_eq!(err.position, position, "{}: {}", text, err);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_compact_or_tagged() {
+        let configs: Vec<DistributionConfig> = serde_json::from_str(r#"["Exp(30)", {"type": "Uniform", "min": 1, "max": 2}]"#).unwrap();
+        assert!(matches!(configs[0], DistributionConfig::Exponential { mean: 30. }));
+        assert!(matches!(configs[1], DistributionConfig::Uniform { min: 1., max: 2. }));
+        let err = serde_json::from_str::<DistributionConfig>(r#""Exp(30""#).unwrap_err();
+        assert!(err.to_string().contains("at position 6"));
+    }
+}
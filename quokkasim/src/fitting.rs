@@ -0,0 +1,468 @@
+use std::f64::consts::PI;
+use statrs::function::gamma::{digamma, ln_gamma};
+use crate::common::{std_normal_mass, DistributionConfig, DistributionFactory, DistributionParametersError, TRUNC_NORMAL_MIN_MASS};
+
+/// Distribution families that can be fitted to data by maximum likelihood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistributionFamily {
+    Uniform,
+    Triangular,
+    Normal,
+    TruncNormal,
+    Exponential,
+    LogNormal,
+    Gamma,
+    Weibull,
+    Erlang,
+    Beta,
+    Pert,
+}
+
+impl DistributionFamily {
+    pub const ALL: [DistributionFamily; 11] = [
+        DistributionFamily::Uniform,
+        DistributionFamily::Triangular,
+        DistributionFamily::Normal,
+        DistributionFamily::TruncNormal,
+        DistributionFamily::Exponential,
+        DistributionFamily::LogNormal,
+        DistributionFamily::Gamma,
+        DistributionFamily::Weibull,
+        DistributionFamily::Erlang,
+        DistributionFamily::Beta,
+        DistributionFamily::Pert,
+    ];
+
+    /// Whether the family is only defined for positive observations.
+    pub fn requires_positive(&self) -> bool {
+        matches!(self, DistributionFamily::Exponential | DistributionFamily::LogNormal | DistributionFamily::Gamma
+            | DistributionFamily::Weibull | DistributionFamily::Erlang)
+    }
+}
+
+/// Goodness-of-fit statistic used to rank fitted distributions. Smaller is better for both. Anderson–Darling gives
+/// more weight to the tails than Kolmogorov–Smirnov.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoodnessOfFit {
+    KolmogorovSmirnov,
+    AndersonDarling,
+}
+
+/// Maximum likelihood fit of a single family to a set of observations.
+#[derive(Debug, Clone)]
+pub struct FitResult {
+    pub family: DistributionFamily,
+    pub config: DistributionConfig,
+    pub log_likelihood: f64,
+    pub ks_statistic: f64,
+    pub ad_statistic: f64,
+}
+
+/// Fits every supported family to `data` by maximum likelihood, and returns the fits from best to worst according to
+/// `rank_by`. Families whose support does not cover the data (e.g. LogNormal with non-positive observations) are
+/// omitted. Any other failure to fit a family is returned as an error.
+pub fn fit_all(data: &[f64], rank_by: GoodnessOfFit) -> Result<Vec<FitResult>, DistributionParametersError> {
+    check_data(data)?;
+    let all_positive = data.iter().all(|x| *x > 0.);
+    let mut results = DistributionFamily::ALL.iter()
+        .filter(|family| all_positive || !family.requires_positive())
+        .map(|family| fit(data, *family))
+        .collect::<Result<Vec<_>, _>>()?;
+    results.sort_by(|a, b| match rank_by {
+        GoodnessOfFit::KolmogorovSmirnov => a.ks_statistic.total_cmp(&b.ks_statistic),
+        GoodnessOfFit::AndersonDarling => a.ad_statistic.total_cmp(&b.ad_statistic),
+    });
+    Ok(results)
+}
+
+/// Fits a single family to `data` by maximum likelihood.
+pub fn fit(data: &[f64], family: DistributionFamily) -> Result<FitResult, DistributionParametersError> {
+    check_data(data)?;
+    if family.requires_positive() && data.iter().any(|x| *x <= 0.) {
+        return Err(DistributionParametersError {
+            msg: format!("{:?} requires all observations to be positive", family)
+        })
+    }
+    let n = data.len() as f64;
+    let mean = data.iter().sum::<f64>() / n;
+    let var = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    let mut sorted = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let (data_min, data_max) = (sorted[0], sorted[sorted.len() - 1]);
+    // Triangular, Beta and Pert also fit the bounds of their interval, as distances beyond the extreme observations.
+    // These are in units of the mean spacing of the observations and on a log scale, so that the bounds stay outside
+    // the data. The fits start from the unbiased estimate of Uniform bounds, one mean spacing beyond the extremes.
+    let spacing = (data_max - data_min) / (n - 1.);
+    let bounds = |u: f64, v: f64| (data_min - spacing * u.exp(), data_max + spacing * v.exp());
+
+    let (config, log_likelihood) = match family {
+        DistributionFamily::Uniform => {
+            (DistributionConfig::Uniform { min: data_min, max: data_max }, -n * (data_max - data_min).ln())
+        },
+        DistributionFamily::Triangular => {
+            let [u, v] = maximise(|[u, v]| {
+                let (min, max) = bounds(u, v);
+                triangular_mode_mle(&sorted, min, max).1
+            }, [0., 0.], [1., 1.]);
+            let (min, max) = bounds(u, v);
+            let (mode, log_likelihood) = triangular_mode_mle(&sorted, min, max);
+            (DistributionConfig::Triangular { min, max, mode }, log_likelihood)
+        },
+        DistributionFamily::Normal => {
+            (DistributionConfig::Normal { mean, std: var.sqrt() }, -n / 2. * (2. * PI * var).ln() - n / 2.)
+        },
+        DistributionFamily::TruncNormal => {
+            // The likelihood grows as the window narrows, so the bounds are the extreme observations
+            let log_likelihood = |[mu, ln_sigma]: [f64; 2]| trunc_normal_log_likelihood(data, mu, ln_sigma.exp(), data_min, data_max);
+            let [mu, ln_sigma] = maximise(log_likelihood, [mean, var.sqrt().ln()], [var.sqrt(), 0.5]);
+            let config = DistributionConfig::TruncNormal { mean: mu, std: ln_sigma.exp(), min: Some(data_min), max: Some(data_max) };
+            (config, log_likelihood([mu, ln_sigma]))
+        },
+        DistributionFamily::Exponential => {
+            (DistributionConfig::Exponential { mean }, -n * mean.ln() - n)
+        },
+        DistributionFamily::LogNormal => {
+            let sum_ln = data.iter().map(|x| x.ln()).sum::<f64>();
+            let mu = sum_ln / n;
+            let var = data.iter().map(|x| (x.ln() - mu).powi(2)).sum::<f64>() / n;
+            let config = DistributionConfig::LogNormal {
+                mean: (mu + var / 2.).exp(),
+                std: (mu + var / 2.).exp() * var.exp_m1().sqrt(),
+            };
+            (config, -sum_ln - n / 2. * (2. * PI * var).ln() - n / 2.)
+        },
+        DistributionFamily::Gamma => {
+            let mean_ln = data.iter().map(|x| x.ln()).sum::<f64>() / n;
+            let shape = gamma_shape_mle(mean.ln() - mean_ln);
+            let scale = mean / shape;
+            (DistributionConfig::Gamma { shape, scale }, gamma_log_likelihood(data, shape, scale))
+        },
+        DistributionFamily::Weibull => {
+            let (shape, scale) = weibull_mle(data);
+            let log_likelihood = data.iter()
+                .map(|x| shape.ln() - shape * scale.ln() + (shape - 1.) * x.ln() - (x / scale).powf(shape))
+                .sum();
+            (DistributionConfig::Weibull { shape, scale }, log_likelihood)
+        },
+        DistributionFamily::Erlang => {
+            // The likelihood is unimodal in the shape, so the best integer shape neighbours the continuous MLE
+            let mean_ln = data.iter().map(|x| x.ln()).sum::<f64>() / n;
+            let shape = gamma_shape_mle(mean.ln() - mean_ln);
+            let k = [shape.floor().max(1.), shape.ceil().max(1.)].into_iter()
+                .max_by(|a, b| gamma_log_likelihood(data, *a, mean / a).total_cmp(&gamma_log_likelihood(data, *b, mean / b)))
+                .unwrap();
+            (DistributionConfig::Erlang { k: k as u32, mean }, gamma_log_likelihood(data, k, mean / k))
+        },
+        DistributionFamily::Beta => {
+            // Start from the method of moments estimate, within the initial bounds
+            let (min, max) = bounds(0., 0.);
+            let (m, v) = ((mean - min) / (max - min), var / (max - min).powi(2));
+            let common = (m * (1. - m) / v - 1.).max(1e-3);
+            let log_likelihood = |[ln_alpha, ln_beta, u, v]: [f64; 4]| {
+                let (min, max) = bounds(u, v);
+                beta_log_likelihood(data, ln_alpha.exp(), ln_beta.exp(), min, max)
+            };
+            let x = maximise(log_likelihood, [(m * common).ln(), ((1. - m) * common).ln(), 0., 0.], [0.5, 0.5, 1., 1.]);
+            let [ln_alpha, ln_beta, u, v] = x;
+            let (min, max) = bounds(u, v);
+            (DistributionConfig::Beta { alpha: ln_alpha.exp(), beta: ln_beta.exp(), min, max }, log_likelihood(x))
+        },
+        DistributionFamily::Pert => {
+            // A Beta with alpha + beta = 6, where alpha = 1 + 4 (mode - min) / (max - min), i.e. between 1 and 5
+            let alpha = |w: f64| 1. + 4. / (1. + (-w).exp());
+            let log_likelihood = |[w, u, v]: [f64; 3]| {
+                let (min, max) = bounds(u, v);
+                beta_log_likelihood(data, alpha(w), 6. - alpha(w), min, max)
+            };
+            let x = maximise(log_likelihood, [0., 0., 0.], [1., 1., 1.]);
+            let [w, u, v] = x;
+            let (min, max) = bounds(u, v);
+            let mode = min + (alpha(w) - 1.) / 4. * (max - min);
+            (DistributionConfig::Pert { min, max, mode }, log_likelihood(x))
+        },
+    };
+
+    if !log_likelihood.is_finite() {
+        return Err(DistributionParametersError {
+            msg: format!("Fitting {:?} to the observations failed numerically, giving {:?}", family, config)
+        })
+    }
+    let distr = DistributionFactory::new(0).create(config.clone())?;
+    let cdf: Vec<f64> = sorted.iter().map(|x| distr.cdf(*x)).collect();
+
+    Ok(FitResult {
+        family,
+        config,
+        log_likelihood,
+        ks_statistic: ks_statistic(&cdf),
+        ad_statistic: ad_statistic(&cdf),
+    })
+}
+
+fn check_data(data: &[f64]) -> Result<(), DistributionParametersError> {
+    if data.len() < 2 {
+        return Err(DistributionParametersError {
+            msg: format!("Fitting requires at least 2 observations, got {}", data.len())
+        })
+    }
+    if data.iter().any(|x| !x.is_finite()) {
+        return Err(DistributionParametersError {
+            msg: "Observations must all be finite".to_string()
+        })
+    }
+    if data.iter().all(|x| *x == data[0]) {
+        return Err(DistributionParametersError {
+            msg: "Observations must not all be equal".to_string()
+        })
+    }
+    Ok(())
+}
+
+/// Kolmogorov–Smirnov statistic, from the fitted CDF evaluated at the sorted observations.
+fn ks_statistic(cdf: &[f64]) -> f64 {
+    let n = cdf.len() as f64;
+    cdf.iter().enumerate()
+        .map(|(i, f)| ((i + 1) as f64 / n - f).max(f - i as f64 / n))
+        .fold(0., f64::max)
+}
+
+/// Anderson–Darling statistic, from the fitted CDF evaluated at the sorted observations.
+fn ad_statistic(cdf: &[f64]) -> f64 {
+    let n = cdf.len();
+    // Clamp so that observations outside the fitted support give a large but finite statistic
+    let f = |i: usize| cdf[i].clamp(1e-300, 1. - 1e-16);
+    let sum: f64 = (0..n).map(|i| (2 * i + 1) as f64 * (f(i).ln() + (1. - f(n - 1 - i)).ln())).sum();
+    -(n as f64) - sum / n as f64
+}
+
+fn gamma_log_likelihood(data: &[f64], shape: f64, scale: f64) -> f64 {
+    data.iter().map(|x| (shape - 1.) * x.ln() - x / scale - shape * scale.ln() - ln_gamma(shape)).sum()
+}
+
+/// Log-likelihood of a Beta(alpha, beta) distribution scaled onto [min, max].
+fn beta_log_likelihood(data: &[f64], alpha: f64, beta: f64, min: f64, max: f64) -> f64 {
+    let ln_norm = ln_gamma(alpha) + ln_gamma(beta) - ln_gamma(alpha + beta) + (max - min).ln();
+    data.iter().map(|x| {
+        let y = (x - min) / (max - min);
+        (alpha - 1.) * y.ln() + (beta - 1.) * (1. - y).ln() - ln_norm
+    }).sum()
+}
+
+/// Log-likelihood of a normal distribution truncated to [min, max]. Windows with less than `TRUNC_NORMAL_MIN_MASS`
+/// are excluded, as `DistributionFactory::create` would reject them.
+fn trunc_normal_log_likelihood(data: &[f64], mu: f64, sigma: f64, min: f64, max: f64) -> f64 {
+    let mass = std_normal_mass((min - mu) / sigma, (max - mu) / sigma);
+    if mass.is_nan() || mass < TRUNC_NORMAL_MIN_MASS {
+        return f64::NEG_INFINITY;
+    }
+    let n = data.len() as f64;
+    -data.iter().map(|x| ((x - mu) / sigma).powi(2) / 2.).sum::<f64>() - n * (sigma * (2. * PI).sqrt()).ln() - n * mass.ln()
+}
+
+/// Maximum likelihood mode of a triangular distribution on [min, max], and its log-likelihood, given the sorted
+/// observations. Between observations the log-likelihood is convex in the mode, so its maximum is at an observation.
+fn triangular_mode_mle(sorted: &[f64], min: f64, max: f64) -> (f64, f64) {
+    let n = sorted.len();
+    // Observations below the mode contribute ln(x - min), and those from the mode up ln(max - x)
+    let mut below = vec![0.; n + 1];
+    for i in 0..n {
+        below[i + 1] = below[i] + (sorted[i] - min).ln();
+    }
+    let mut above = vec![0.; n + 1];
+    for i in (0..n).rev() {
+        above[i] = above[i + 1] + (max - sorted[i]).ln();
+    }
+    (0..n).map(|r| {
+        let mode = sorted[r];
+        let log_likelihood = n as f64 * (2. / (max - min)).ln() + below[r] - r as f64 * (mode - min).ln()
+            + above[r] - (n - r) as f64 * (max - mode).ln();
+        (mode, log_likelihood)
+    }).max_by(|a, b| a.1.total_cmp(&b.1)).unwrap()
+}
+
+/// Maximises `f` over `N` parameters by the Nelder–Mead simplex method, from `start` with initial steps `step`.
+fn maximise<const N: usize>(f: impl Fn([f64; N]) -> f64, start: [f64; N], step: [f64; N]) -> [f64; N] {
+    // NaN, e.g. from 0 * ln(0) on a bound, counts as the worst value
+    let value = |x: [f64; N]| {
+        let y = f(x);
+        (if y.is_nan() { f64::NEG_INFINITY } else { y }, x)
+    };
+    let mut simplex: Vec<(f64, [f64; N])> = (0..=N).map(|i| {
+        let mut x = start;
+        if i > 0 {
+            x[i - 1] += step[i - 1];
+        }
+        value(x)
+    }).collect();
+    for _ in 0..500 * N {
+        // Best first
+        simplex.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let (best, worst) = (simplex[0], simplex[N]);
+        if best.0 - worst.0 <= 1e-12 * (1. + best.0.abs()) {
+            break;
+        }
+        let centroid: [f64; N] = std::array::from_fn(|j| simplex[..N].iter().map(|(_, x)| x[j]).sum::<f64>() / N as f64);
+        // Point along the line from the centroid through the worst point
+        let along = |t: f64| value(std::array::from_fn(|j| centroid[j] + t * (worst.1[j] - centroid[j])));
+        let reflected = along(-1.);
+        if reflected.0 > best.0 {
+            let expanded = along(-2.);
+            simplex[N] = if expanded.0 > reflected.0 { expanded } else { reflected };
+        } else if reflected.0 > simplex[N - 1].0 {
+            simplex[N] = reflected;
+        } else {
+            let contracted = if reflected.0 > worst.0 { along(-0.5) } else { along(0.5) };
+            if contracted.0 > worst.0.max(reflected.0) {
+                simplex[N] = contracted;
+            } else {
+                // Shrink towards the best point
+                for vertex in simplex.iter_mut().skip(1) {
+                    *vertex = value(std::array::from_fn(|j| (vertex.1[j] + best.1[j]) / 2.));
+                }
+            }
+        }
+    }
+    simplex.iter().max_by(|a, b| a.0.total_cmp(&b.0)).unwrap().1
+}
+
+/// Solves ln(k) - digamma(k) = s for the Gamma shape k by Newton's method, where s = ln(mean) - mean(ln x) > 0.
+fn gamma_shape_mle(s: f64) -> f64 {
+    let mut k = (3. - s + ((s - 3.).powi(2) + 24. * s).sqrt()) / (12. * s);
+    for _ in 0..50 {
+        let step = (k.ln() - digamma(k) - s) / (1. / k - trigamma(k));
+        let next = (k - step).max(k / 10.);
+        if (next - k).abs() <= 1e-12 * k {
+            return next;
+        }
+        k = next;
+    }
+    k
+}
+
+/// Derivative of the digamma function, via the recurrence up to x >= 6 and then the asymptotic series.
+fn trigamma(mut x: f64) -> f64 {
+    let mut acc = 0.;
+    while x < 6. {
+        acc += 1. / (x * x);
+        x += 1.;
+    }
+    let inv = 1. / x;
+    let inv2 = inv * inv;
+    acc + inv + inv2 / 2. + inv * inv2 * (1. / 6. - inv2 * (1. / 30. - inv2 * (1. / 42. - inv2 / 30.)))
+}
+
+/// Weibull maximum likelihood (shape, scale). The shape solves
+/// sum(x^k ln x) / sum(x^k) - 1/k - mean(ln x) = 0, whose left side is increasing in k, so is found by bisection.
+fn weibull_mle(data: &[f64]) -> (f64, f64) {
+    // Rescale by the maximum so that x^k cannot overflow
+    let x_max = data.iter().cloned().fold(0., f64::max);
+    let ln_x: Vec<f64> = data.iter().map(|x| (x / x_max).ln()).collect();
+    let mean_ln = ln_x.iter().sum::<f64>() / ln_x.len() as f64;
+    let g = |k: f64| {
+        let (num, den) = ln_x.iter().fold((0., 0.), |(num, den), l| {
+            let w = (k * l).exp();
+            (num + w * l, den + w)
+        });
+        num / den - 1. / k - mean_ln
+    };
+    let (mut lo, mut hi) = (1e-3, 1.);
+    while g(hi) < 0. && hi < 1e6 {
+        lo = hi;
+        hi *= 2.;
+    }
+    for _ in 0..200 {
+        let mid = (lo + hi) / 2.;
+        if g(mid) < 0. { lo = mid } else { hi = mid }
+    }
+    let shape = (lo + hi) / 2.;
+    let mean_pow = ln_x.iter().map(|l| (shape * l).exp()).sum::<f64>() / ln_x.len() as f64;
+    (shape, x_max * mean_pow.powf(1. / shape))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(config: DistributionConfig, n: usize) -> Vec<f64> {
+        let mut distr = DistributionFactory::new(42).create(config).unwrap();
+        (0..n).map(|_| distr.sample()).collect()
+    }
+
+    #[test]
+    fn test_fit_recovers_parameters() {
+        let data = samples(DistributionConfig::Gamma { shape: 3., scale: 2. }, 5000);
+        match fit(&data, DistributionFamily::Gamma).unwrap().config {
+            DistributionConfig::Gamma { shape, scale } => {
+                assert!((shape - 3.).abs() < 0.2, "shape {}", shape);
+                assert!((scale - 2.).abs() < 0.2, "scale {}", scale);
+            },
+            config => panic!("Unexpected config {:?}", config),
+        }
+
+        let data = samples(DistributionConfig::Weibull { shape: 1.5, scale: 10. }, 5000);
+        match fit(&data, DistributionFamily::Weibull).unwrap().config {
+            DistributionConfig::Weibull { shape, scale } => {
+                assert!((shape - 1.5).abs() < 0.1, "shape {}", shape);
+                assert!((scale - 10.).abs() < 0.5, "scale {}", scale);
+            },
+            config => panic!("Unexpected config {:?}", config),
+        }
+    }
+
+    #[test]
+    fn test_fit_all_ranks_generating_family_first() {
+        let data = samples(DistributionConfig::Triangular { min: 0., max: 10., mode: 3. }, 2000);
+        for rank_by in [GoodnessOfFit::KolmogorovSmirnov, GoodnessOfFit::AndersonDarling] {
+            let results = fit_all(&data, rank_by).unwrap();
+            assert_eq!(results.len(), DistributionFamily::ALL.len());
+            assert_eq!(results[0].family, DistributionFamily::Triangular);
+        }
+
+        // Negative observations rule out families on the positive half-line
+        let data = samples(DistributionConfig::Normal { mean: 0., std: 1. }, 2000);
+        let results = fit_all(&data, GoodnessOfFit::AndersonDarling).unwrap();
+        assert_eq!(results.len(), 6);
+        assert!(results.iter().all(|result| !result.family.requires_positive()));
+
+        assert!(fit_all(&[1.], GoodnessOfFit::AndersonDarling).is_err());
+        // Other failures are returned rather than omitted, here from the range overflowing
+        assert!(fit_all(&[-1e308, 1e308], GoodnessOfFit::AndersonDarling).is_err());
+    }
+
+    #[test]
+    fn test_fit_bounded_families() {
+        let data = samples(DistributionConfig::Triangular { min: 0., max: 10., mode: 3. }, 5000);
+        match fit(&data, DistributionFamily::Triangular).unwrap().config {
+            DistributionConfig::Triangular { min, max, mode } => {
+                assert!(min.abs() < 0.2 && (max - 10.).abs() < 0.2, "min {} max {}", min, max);
+                assert!((mode - 3.).abs() < 0.3, "mode {}", mode);
+            },
+            config => panic!("Unexpected config {:?}", config),
+        }
+
+        let data = samples(DistributionConfig::Pert { min: 1., max: 9., mode: 3. }, 5000);
+        match fit(&data, DistributionFamily::Pert).unwrap().config {
+            DistributionConfig::Pert { mode, .. } => assert!((mode - 3.).abs() < 0.3, "mode {}", mode),
+            config => panic!("Unexpected config {:?}", config),
+        }
+
+        let data = samples(DistributionConfig::Beta { alpha: 2., beta: 5., min: 10., max: 20. }, 5000);
+        match fit(&data, DistributionFamily::Beta).unwrap().config {
+            DistributionConfig::Beta { alpha, beta, .. } => {
+                assert!((alpha - 2.).abs() < 0.3, "alpha {}", alpha);
+                assert!((beta - 5.).abs() < 0.75, "beta {}", beta);
+            },
+            config => panic!("Unexpected config {:?}", config),
+        }
+
+        let data = samples(DistributionConfig::TruncNormal { mean: 30., std: 10., min: Some(20.), max: None }, 5000);
+        match fit(&data, DistributionFamily::TruncNormal).unwrap().config {
+            DistributionConfig::TruncNormal { mean, std, min, .. } => {
+                assert!((mean - 30.).abs() < 1., "mean {}", mean);
+                assert!((std - 10.).abs() < 1., "std {}", std);
+                assert!((min.unwrap() - 20.).abs() < 0.1);
+            },
+            config => panic!("Unexpected config {:?}", config),
+        }
+    }
+}
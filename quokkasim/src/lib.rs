@@ -5,6 +5,9 @@ pub mod common;
 pub mod core;
 pub mod components;
 pub mod delays;
+pub mod distribution_syntax;
+pub mod fitting;
+pub mod reliability;
 pub use strum;
 pub use strum_macros;
 pub mod nexosim {
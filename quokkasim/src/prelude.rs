@@ -6,4 +6,5 @@ pub use crate::components::{
 pub use crate::core::*;
 pub use crate::common::*;
 pub use crate::delays::*;
+pub use crate::distribution_syntax::DistributionParseError;
 pub use quokkasim_derive_macros::WithMethods;
\ No newline at end of file
@@ -0,0 +1,369 @@
+use std::{error::Error, time::Duration};
+use indexmap::IndexMap;
+use tai_time::MonotonicTime;
+use crate::{
+    common::{parse_date_time, Distribution, TimeUnit},
+    components::{discrete::{DiscreteProcessLog, DiscreteProcessLogType}, vector::{VectorProcessLog, VectorProcessLogType}},
+    delays::{DelayCategory, DelayModes, DelayTrigger},
+};
+
+/// Reliability figures for one delay mode of one component over the observation period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelayModeReliability {
+    pub element_name: String,
+    pub delay_name: String,
+    pub category: DelayCategory,
+    /// Number of delays started, from `DelayStart` records.
+    pub events: usize,
+    pub downtime: Duration,
+    /// Mean time between failures - the component's uptime divided by the number of events.
+    pub mtbf: Option<Duration>,
+    /// Mean time to repair - the downtime of completed delays divided by their number. Under `DelayPolicy::Overlap`
+    /// and `DelayPolicy::Preempt`, downtime while a delay is suspended is attributed to the delay of higher priority.
+    pub mttr: Option<Duration>,
+    /// Fraction of the observation period not lost to this delay mode.
+    pub availability: f64,
+    /// Mean of `until_delay_distr`, for time-based delay modes configured with `ReliabilityAnalysis::with_delay_modes`.
+    pub configured_mtbf: Option<Duration>,
+    /// Mean of `until_fix_distr`, for delay modes configured with `ReliabilityAnalysis::with_delay_modes`.
+    pub configured_mttr: Option<Duration>,
+}
+
+impl DelayModeReliability {
+    /// Relative difference of the observed MTBF from the configured one, if both are known.
+    pub fn mtbf_deviation(&self) -> Option<f64> {
+        relative_deviation(self.mtbf, self.configured_mtbf)
+    }
+
+    /// Relative difference of the observed MTTR from the configured one, if both are known.
+    pub fn mttr_deviation(&self) -> Option<f64> {
+        relative_deviation(self.mttr, self.configured_mttr)
+    }
+
+    /// Whether the observed MTBF or MTTR differs from the configured one by more than `tolerance`, as a fraction of
+    /// the configured value. Note the observed MTBF includes idle time, so only matches the configured one for
+    /// `DelayClock::Calendar` modes, or processes which are always busy.
+    pub fn is_mismatched(&self, tolerance: f64) -> bool {
+        [self.mtbf_deviation(), self.mttr_deviation()].into_iter().flatten().any(|deviation| deviation.abs() > tolerance)
+    }
+}
+
+/// Reliability figures for one component across all of its delay modes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentReliability {
+    pub element_name: String,
+    pub events: usize,
+    pub downtime: Duration,
+    pub mtbf: Option<Duration>,
+    pub mttr: Option<Duration>,
+    pub availability: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReliabilitySummary {
+    pub components: Vec<ComponentReliability>,
+    pub delay_modes: Vec<DelayModeReliability>,
+}
+
+impl ReliabilitySummary {
+    /// Delay modes whose observed MTBF or MTTR differs from the configured one by more than `tolerance`.
+    pub fn mismatches(&self, tolerance: f64) -> Vec<&DelayModeReliability> {
+        self.delay_modes.iter().filter(|x| x.is_mismatched(tolerance)).collect()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct DelayModeRecord {
+    category: DelayCategory,
+    events: usize,
+    repairs: usize,
+    /// Downtime of completed delays.
+    repair_time: Duration,
+    /// Whether a delay is under way, having started and not ended.
+    is_under_way: bool,
+    /// Downtime of the delay under way, up to `open_since`.
+    current_downtime: Duration,
+    /// Since when downtime has been attributed to the delay under way, unless it is suspended.
+    open_since: Option<MonotonicTime>,
+    configured_mtbf: Option<Duration>,
+    configured_mttr: Option<Duration>,
+}
+
+/// Collects `DelayStart`, `DelaySuspend`, `DelayResume` and `DelayEnd` records from process logs, and summarises them as
+/// reliability KPIs.
+#[derive(Debug, Clone)]
+pub struct ReliabilityAnalysis {
+    start: MonotonicTime,
+    records: IndexMap<String, IndexMap<String, DelayModeRecord>>,
+}
+
+impl ReliabilityAnalysis {
+    /// Observes from `start`, usually the start of the simulation.
+    pub fn new(start: MonotonicTime) -> Self {
+        ReliabilityAnalysis { start, records: IndexMap::new() }
+    }
+
+    /// Takes the configured delay modes of a component, to compare their means against the observed figures.
+    pub fn with_delay_modes(mut self, element_name: &str, delay_modes: &DelayModes) -> Self {
+        let component = self.records.entry(element_name.into()).or_default();
+        for (name, mode) in delay_modes.modes.iter() {
+            let record = component.entry(name.clone()).or_default();
+            record.category = mode.category();
+            record.configured_mtbf = match mode.trigger() {
+                DelayTrigger::Sampled => mean_duration(&mode.until_delay_distr, mode.time_unit()),
+                DelayTrigger::Calendar(_) | DelayTrigger::Throughput => None,
+            };
+            record.configured_mttr = mean_duration(&mode.until_fix_distr, mode.time_unit());
+        }
+        self
+    }
+
+    pub fn add_vector_process_log<T>(&mut self, log: &VectorProcessLog<T>) -> Result<(), Box<dyn Error>> {
+        match &log.event {
+            VectorProcessLogType::DelayStart { delay_name, category } => {
+                self.delay_start(&log.element_name, delay_name, *category, parse_date_time(&log.time)?);
+            },
+            VectorProcessLogType::DelaySuspend { delay_name, .. } => {
+                self.delay_suspend(&log.element_name, delay_name, parse_date_time(&log.time)?);
+            },
+            VectorProcessLogType::DelayResume { delay_name, .. } => {
+                self.delay_resume(&log.element_name, delay_name, parse_date_time(&log.time)?);
+            },
+            VectorProcessLogType::DelayEnd { delay_name, .. } => {
+                self.delay_end(&log.element_name, delay_name, parse_date_time(&log.time)?);
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    pub fn add_discrete_process_log<T>(&mut self, log: &DiscreteProcessLog<T>) -> Result<(), Box<dyn Error>> {
+        match &log.event {
+            DiscreteProcessLogType::DelayStart { delay_name, category } => {
+                self.delay_start(&log.element_name, delay_name, *category, parse_date_time(&log.time)?);
+            },
+            DiscreteProcessLogType::DelaySuspend { delay_name, .. } => {
+                self.delay_suspend(&log.element_name, delay_name, parse_date_time(&log.time)?);
+            },
+            DiscreteProcessLogType::DelayResume { delay_name, .. } => {
+                self.delay_resume(&log.element_name, delay_name, parse_date_time(&log.time)?);
+            },
+            DiscreteProcessLogType::DelayEnd { delay_name, .. } => {
+                self.delay_end(&log.element_name, delay_name, parse_date_time(&log.time)?);
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    pub fn delay_start(&mut self, element_name: &str, delay_name: &str, category: DelayCategory, time: MonotonicTime) {
+        let record = self.records.entry(element_name.into()).or_default().entry(delay_name.into()).or_default();
+        record.category = category;
+        record.events += 1;
+        record.is_under_way = true;
+        record.current_downtime = Duration::ZERO;
+        record.open_since = Some(time);
+    }
+
+    /// Stops attributing downtime to the delay under way, as a delay of higher priority has taken over.
+    pub fn delay_suspend(&mut self, element_name: &str, delay_name: &str, time: MonotonicTime) {
+        let record = self.records.entry(element_name.into()).or_default().entry(delay_name.into()).or_default();
+        if let Some(since) = record.open_since.take() {
+            record.current_downtime += time.checked_duration_since(since).unwrap_or(Duration::ZERO);
+        }
+    }
+
+    pub fn delay_resume(&mut self, element_name: &str, delay_name: &str, time: MonotonicTime) {
+        let record = self.records.entry(element_name.into()).or_default().entry(delay_name.into()).or_default();
+        if record.is_under_way && record.open_since.is_none() {
+            record.open_since = Some(time);
+        }
+    }
+
+    pub fn delay_end(&mut self, element_name: &str, delay_name: &str, time: MonotonicTime) {
+        self.delay_suspend(element_name, delay_name, time);
+        let record = self.records.entry(element_name.into()).or_default().entry(delay_name.into()).or_default();
+        if std::mem::take(&mut record.is_under_way) {
+            record.repairs += 1;
+            record.repair_time += std::mem::take(&mut record.current_downtime);
+        }
+    }
+
+    /// Summarises the delays observed up to `end`. Delays still in progress at `end` count towards downtime, but not
+    /// towards MTTR.
+    pub fn summarise(&self, end: MonotonicTime) -> ReliabilitySummary {
+        let period = end.checked_duration_since(self.start).unwrap_or(Duration::ZERO);
+        let availability = |downtime: Duration| {
+            if period.is_zero() { 1. } else { 1. - downtime.as_secs_f64() / period.as_secs_f64() }
+        };
+        let mut components = Vec::new();
+        let mut delay_modes = Vec::new();
+        for (element_name, records) in self.records.iter() {
+            let downtimes: Vec<Duration> = records.values().map(|record| {
+                record.repair_time + record.current_downtime
+                    + record.open_since.and_then(|since| end.checked_duration_since(since)).unwrap_or(Duration::ZERO)
+            }).collect();
+            let downtime: Duration = downtimes.iter().sum();
+            let uptime = period.saturating_sub(downtime);
+            let events: usize = records.values().map(|record| record.events).sum();
+            let repairs: usize = records.values().map(|record| record.repairs).sum();
+            let repair_time: Duration = records.values().map(|record| record.repair_time).sum();
+
+            for ((delay_name, record), mode_downtime) in records.iter().zip(downtimes) {
+                delay_modes.push(DelayModeReliability {
+                    element_name: element_name.clone(),
+                    delay_name: delay_name.clone(),
+                    category: record.category,
+                    events: record.events,
+                    downtime: mode_downtime,
+                    mtbf: mean(uptime, record.events),
+                    mttr: mean(record.repair_time, record.repairs),
+                    availability: availability(mode_downtime),
+                    configured_mtbf: record.configured_mtbf,
+                    configured_mttr: record.configured_mttr,
+                });
+            }
+            components.push(ComponentReliability {
+                element_name: element_name.clone(),
+                events,
+                downtime,
+                mtbf: mean(uptime, events),
+                mttr: mean(repair_time, repairs),
+                availability: availability(downtime),
+            });
+        }
+        ReliabilitySummary { components, delay_modes }
+    }
+}
+
+fn mean(total: Duration, count: usize) -> Option<Duration> {
+    if count == 0 { None } else { Some(total / count as u32) }
+}
+
+fn mean_duration(distr: &Distribution, default_unit: TimeUnit) -> Option<Duration> {
+    let unit = distr.time_unit().unwrap_or(default_unit);
+    Duration::try_from_secs_f64(distr.mean() * unit.secs()).ok()
+}
+
+fn relative_deviation(observed: Option<Duration>, configured: Option<Duration>) -> Option<f64> {
+    match (observed, configured) {
+        (Some(observed), Some(configured)) if !configured.is_zero() => {
+            Some(observed.as_secs_f64() / configured.as_secs_f64() - 1.)
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::EventId, delays::{DelayEvent, DelayMode, DelayModeChange}};
+
+    fn log(time: MonotonicTime, event: VectorProcessLogType<f64>) -> VectorProcessLog<f64> {
+        VectorProcessLog {
+            time: time.to_chrono_date_time(0).unwrap().to_string(),
+            event_id: EventId("P_000000".into()),
+            source_event_id: EventId("P_000000".into()),
+            element_name: "Crusher".into(),
+            element_type: "VectorProcess".into(),
+            event,
+        }
+    }
+
+    #[test]
+    fn test_reliability_summary() {
+        let start = MonotonicTime::EPOCH;
+        let hours = |h: u64| start + Duration::from_secs(h * 3600);
+        let mut delay_modes = DelayModes::default();
+        delay_modes.modify(DelayModeChange::Add(DelayMode::new("Breakdown", Distribution::Constant(40.), Distribution::Constant(1.))
+            .with_time_unit(TimeUnit::Hours)));
+        let mut analysis = ReliabilityAnalysis::new(start).with_delay_modes("Crusher", &delay_modes);
+        let breakdown = |start: bool| {
+            let delay_name = "Breakdown".to_string();
+            let category = DelayCategory::Unplanned;
+            if start { VectorProcessLogType::DelayStart { delay_name, category } } else { VectorProcessLogType::DelayEnd { delay_name, category } }
+        };
+        let logs = [
+            log(hours(10), breakdown(true)),
+            log(hours(12), breakdown(false)),
+            log(hours(50), VectorProcessLogType::DelayStart { delay_name: "Service".into(), category: DelayCategory::Planned }),
+            log(hours(54), VectorProcessLogType::DelayEnd { delay_name: "Service".into(), category: DelayCategory::Planned }),
+            log(hours(60), breakdown(true)),
+            log(hours(64), breakdown(false)),
+            log(hours(98), breakdown(true)),
+            log(hours(99), VectorProcessLogType::ProcessContinue { reason: "Resumed by environment".into() }),
+        ];
+        for log in logs.iter() {
+            analysis.add_vector_process_log(log).unwrap();
+        }
+        let summary = analysis.summarise(hours(100));
+
+        // 6h + 2h open of breakdowns, 4h of service, so 88h uptime
+        let component = &summary.components[0];
+        assert_eq!(component.events, 4);
+        assert_eq!(component.downtime, Duration::from_secs(12 * 3600));
+        assert_eq!(component.mtbf, Some(Duration::from_secs(22 * 3600)));
+        assert_eq!(component.mttr, Some(Duration::from_secs(10 * 3600 / 3)));
+        assert!((component.availability - 0.88).abs() < 1e-12);
+
+        let breakdown = &summary.delay_modes[0];
+        assert_eq!(breakdown.delay_name, "Breakdown");
+        assert_eq!(breakdown.events, 3);
+        assert_eq!(breakdown.downtime, Duration::from_secs(8 * 3600));
+        assert_eq!(breakdown.mttr, Some(Duration::from_secs(3 * 3600)));
+        assert!((breakdown.availability - 0.92).abs() < 1e-12);
+        assert_eq!(breakdown.configured_mtbf, Some(Duration::from_secs(40 * 3600)));
+        assert_eq!(breakdown.configured_mttr, Some(Duration::from_secs(3600)));
+        assert!((breakdown.mttr_deviation().unwrap() - 2.).abs() < 1e-12);
+
+        let service = &summary.delay_modes[1];
+        assert_eq!(service.category, DelayCategory::Planned);
+        assert_eq!(service.configured_mttr, None);
+        assert!(!service.is_mismatched(0.1));
+
+        // Observed MTTR is three times that configured
+        assert_eq!(summary.mismatches(0.5).iter().map(|x| x.delay_name.as_str()).collect::<Vec<_>>(), vec!["Breakdown"]);
+        assert!(summary.mismatches(2.5).is_empty());
+    }
+
+    #[test]
+    fn test_reliability_of_overlapping_delays() {
+        let start = MonotonicTime::EPOCH;
+        let hours = |h: u64| start + Duration::from_secs(h * 3600);
+        let mut analysis = ReliabilityAnalysis::new(start);
+        let delay = |h: u64, delay_name: &str, event: DelayEvent| {
+            let (delay_name, category) = (delay_name.to_string(), DelayCategory::Unplanned);
+            log(hours(h), match event {
+                DelayEvent::Start => VectorProcessLogType::DelayStart { delay_name, category },
+                DelayEvent::Suspend => VectorProcessLogType::DelaySuspend { delay_name, category },
+                DelayEvent::Resume => VectorProcessLogType::DelayResume { delay_name, category },
+                DelayEvent::End => VectorProcessLogType::DelayEnd { delay_name, category },
+            })
+        };
+        // Under `DelayPolicy::Overlap`, Major takes over from Minor, and Other starts and ends during Major
+        let logs = [
+            delay(0, "Minor", DelayEvent::Start),
+            delay(2, "Minor", DelayEvent::Suspend),
+            delay(2, "Major", DelayEvent::Start),
+            delay(3, "Other", DelayEvent::Start),
+            delay(3, "Other", DelayEvent::Suspend),
+            delay(4, "Other", DelayEvent::End),
+            delay(5, "Minor", DelayEvent::End),
+            delay(7, "Major", DelayEvent::End),
+            delay(8, "Minor", DelayEvent::Start),
+            delay(9, "Minor", DelayEvent::Suspend),
+        ];
+        for log in logs.iter() {
+            analysis.add_vector_process_log(log).unwrap();
+        }
+        let summary = analysis.summarise(hours(10));
+
+        // Each delay is one event, and the 7h of the first three delays' downtime is not double counted
+        let component = &summary.components[0];
+        assert_eq!(component.events, 4);
+        assert_eq!(component.downtime, Duration::from_secs(8 * 3600));
+        assert_eq!(component.mttr, Some(Duration::from_secs(7 * 3600 / 3)));
+        let figures: Vec<_> = summary.delay_modes.iter().map(|x| (x.delay_name.as_str(), x.events, x.downtime.as_secs() / 3600)).collect();
+        assert_eq!(figures, vec![("Minor", 2, 3), ("Major", 1, 5), ("Other", 1, 0)]);
+    }
+}
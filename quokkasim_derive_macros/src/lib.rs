@@ -11,6 +11,7 @@ pub fn derive_with_methods(input: TokenStream) -> TokenStream {
     
     let mut methods = Vec::new();
     methods.push(generate_new_struct_method());
+    let mut trait_impls = Vec::new();
 
     if let Data::Struct(data_struct) = &input.data {
         if let Fields::Named(fields_named) = &data_struct.fields {
@@ -65,6 +66,22 @@ pub fn derive_with_methods(input: TokenStream) -> TokenStream {
                             pub fn with_delay_mode_inplace(&mut self, delay_mode_change: DelayModeChange) {
                                 self.delay_modes.modify(delay_mode_change);
                             }
+
+                            pub fn with_delay_policy(mut self, delay_policy: DelayPolicy) -> Self {
+                                self.delay_modes.policy = delay_policy;
+                                self
+                            }
+
+                            pub fn with_delay_policy_inplace(&mut self, delay_policy: DelayPolicy) {
+                                self.delay_modes.policy = delay_policy;
+                            }
+                        });
+                        trait_impls.push(quote! {
+                            impl #impl_generics HasDelayModes for #name #ty_generics #where_clause {
+                                fn delay_modes_mut(&mut self) -> &mut DelayModes {
+                                    &mut self.delay_modes
+                                }
+                            }
                         });
                     },
                     "split_ratios" => {
@@ -76,6 +93,9 @@ pub fn derive_with_methods(input: TokenStream) -> TokenStream {
                     "process_time_distr" => {
                         methods.push(generate_with_and_inplace_method("process_time_distr", field_name, field_type));
                     },
+                    "time_unit" => {
+                        methods.push(generate_with_and_inplace_method("time_unit", field_name, field_type));
+                    },
                     "process_quantity_ratio_distr" => {
                         methods.push(generate_with_and_inplace_method("process_quantity_ratio_distr", field_name, field_type));
                     },
@@ -88,9 +108,21 @@ pub fn derive_with_methods(input: TokenStream) -> TokenStream {
                     "max_capacity" => {
                         methods.push(generate_with_and_inplace_method("max_capacity", field_name, field_type));
                     },
+                    "queue_discipline" => {
+                        methods.push(generate_with_and_inplace_method("queue_discipline", field_name, field_type));
+                    },
+                    "upstream_request" => {
+                        methods.push(generate_with_and_inplace_method("upstream_request", field_name, field_type));
+                    },
                     "item_factory" => {
                         methods.push(generate_with_and_inplace_method("item_factory", field_name, field_type));
                     },
+                    "crew_size" => {
+                        methods.push(generate_with_and_inplace_method("crew_size", field_name, field_type));
+                    },
+                    "env_stop_policy" => {
+                        methods.push(generate_with_and_inplace_method("env_stop_policy", field_name, field_type));
+                    },
                     _ => {
                         // // Generate a generic with_fieldname method for any other field
                         // let method_name = format!("with_{}", field_name_str);
@@ -113,6 +145,8 @@ pub fn derive_with_methods(input: TokenStream) -> TokenStream {
         impl #impl_generics #name #ty_generics #where_clause {
             #(#methods)*
         }
+
+        #(#trait_impls)*
     };
     
     TokenStream::from(expanded)
@@ -134,10 +134,8 @@ impl CustomLoggerConnection for ComponentLogger {
 
 fn main() {
 
-    let mut df = DistributionFactory {
-        base_seed: 12345,
-        next_seed: 0,
-    };
+    let mut df = DistributionFactory::new(12345);
+    df.next_seed = 0;
 
     let mut source = ComponentModel::ProtoCarSource(DiscreteSource::new()
         .with_name("Source")
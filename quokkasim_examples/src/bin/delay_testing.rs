@@ -46,7 +46,7 @@ fn main() {
     //         .with_code("P1")
     //         .with_process_quantity_distr(Distribution::Constant(10.))
     //         .with_process_time_distr(Distribution::Constant(10.))
-    //         .with_delay_mode(DelayModeChange::Add(DelayMode { name: "SplitterDelay".into(), until_delay_distr: Distribution::Constant(7.), until_fix_distr: Distribution::Constant(2.) })),
+    //         .with_delay_mode(DelayModeChange::Add(DelayMode::new("SplitterDelay", Distribution::Constant(7.), Distribution::Constant(2.)))),
     //     Mailbox::new()
     // );
     let mut process = ComponentModel::F64Process(
@@ -55,7 +55,7 @@ fn main() {
             .with_code("P1")
             .with_process_quantity_distr(Distribution::Constant(10.))
             .with_process_time_distr(Distribution::Constant(10.))
-            .with_delay_mode(DelayModeChange::Add(DelayMode { name: "ProcessDelay".into(), until_delay_distr: Distribution::Constant(7.), until_fix_distr: Distribution::Constant(2.) })),
+            .with_delay_mode(DelayModeChange::Add(DelayMode::new("ProcessDelay", Distribution::Constant(7.), Distribution::Constant(2.)))),
         Mailbox::new()
     );
 
@@ -117,6 +117,10 @@ fn main() {
     create_scheduled_event!(&mut sched, &event_time, &sched_event, &env_addr, &mut df);
 
     let event_time = MonotonicTime::EPOCH + Duration::from_secs(24);
+    let sched_event = ScheduledEvent::SetEnvironmentState(BasicEnvironmentState::Reduced(0.5));
+    create_scheduled_event!(&mut sched, &event_time, &sched_event, &env_addr, &mut df);
+
+    let event_time = MonotonicTime::EPOCH + Duration::from_secs(32);
     let sched_event = ScheduledEvent::SetEnvironmentState(BasicEnvironmentState::Normal);
     create_scheduled_event!(&mut sched, &event_time, &sched_event, &env_addr, &mut df);
 
@@ -30,10 +30,8 @@ impl CustomLoggerConnection for ComponentLogger {
 
 fn main() {
 
-    let mut df = DistributionFactory {
-        base_seed: 1234,
-        next_seed: 0,
-    };
+    let mut df = DistributionFactory::new(1234);
+    df.next_seed = 0;
 
     let mut source = ComponentModel::StringSource(DiscreteSource::new().with_name("Source").with_process_time_distr(Distribution::Constant(3.)), Mailbox::new());
 
@@ -153,10 +153,8 @@ struct Car {
 fn main() {
     // Declarations
 
-    let mut df = DistributionFactory {
-        base_seed: 1234,
-        next_seed: 0,
-    };
+    let mut df = DistributionFactory::new(1234);
+    df.next_seed = 0;
 
     let car_arrival_generator =  CarGenerator {
         fuel_level_distr: df.create(DistributionConfig::Uniform { min: 5., max: 25. }).unwrap(),
@@ -34,10 +34,7 @@ fn main() {
 
     let mut process_logger = ComponentLogger::Vector3ProcessLogger(VectorProcessLogger::new("ProcessLogger".into()));
     let mut stock_logger = ComponentLogger::Vector3StockLogger(VectorStockLogger::new("StockLogger".into()));
-    let df = DistributionFactory {
-        base_seed: 1234,
-        next_seed: 0,
-    };
+    let df = DistributionFactory::new(1234);
 
     let mut stockpile_1 = ComponentModel::Vector3Stock(
         VectorStock::new()
@@ -56,6 +56,7 @@ fn main() {
             .with_process_time_distr(Distribution::Constant(1.)),
         Mailbox::new()
     );
+    let process_addr = process.get_address();
 
     let mut process_logger = ComponentLogger::VectorProcessLoggerF64(VectorProcessLogger::new("ProcessLogger".into()));
     let mut stock_logger = ComponentLogger::VectorStockLoggerF64(VectorStockLogger::new("StockLogger".into()));
@@ -81,14 +82,17 @@ fn main() {
 
     let capacity_change = ScheduledEventConfig::SetLowCapacity(10.);
 
-    let mut df = DistributionFactory {
-        base_seed: 1234,
-        next_seed: 0,
-    };
+    let mut df = DistributionFactory::new(1234);
+    df.next_seed = 0;
 
     let event_time = start_time + Duration::from_secs(60);
     
     create_scheduled_event!(&mut scheduler, &event_time, &capacity_change, &stock_1_addr, &mut df).unwrap();
+
+    // Inject a breakdown of the process
+    let breakdown = ScheduledEventConfig::ForceDelay { name: "Breakdown".into(), duration: Duration::from_secs(10) };
+    let breakdown_time = start_time + Duration::from_secs(90);
+    create_scheduled_event!(&mut scheduler, &breakdown_time, &breakdown, &process_addr, &mut df).unwrap();
     simu.step_until(start_time + Duration::from_secs(120)).unwrap();
 
     let output_dir = "outputs/scheduled_event";
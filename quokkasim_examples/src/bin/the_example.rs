@@ -86,11 +86,11 @@ fn main() {
             .with_process_quantity_distr(Distribution::Constant(60.))
             .with_split_ratios([2./3., 1./3.])
             .with_delay_mode(
-                DelayModeChange::Add(DelayMode {
-                    name: "Short Delay".to_string(),
-                    until_delay_distr: df.create(DistributionConfig::Uniform { min: 60., max: 180. }).unwrap(),
-                    until_fix_distr: df.create(DistributionConfig::Uniform { min: 10., max: 60. }).unwrap(),
-                })
+                DelayModeChange::Add(DelayMode::new(
+                    "Short Delay",
+                    df.create(DistributionConfig::Uniform { min: 60., max: 180. }).unwrap(),
+                    df.create(DistributionConfig::Uniform { min: 10., max: 60. }).unwrap(),
+                ))
             ),
         Mailbox::new()
     );
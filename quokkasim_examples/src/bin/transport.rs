@@ -54,16 +54,16 @@ fn main() {
             .with_name("Process")
             .with_process_quantity_distr(Distribution::Constant(1.))
             .with_process_time_distr(Distribution::Constant(1.))
-            .with_delay_mode(DelayModeChange::Add(DelayMode {
-                name: "ShortDelay".into(), 
-                until_delay_distr: df.create(DistributionConfig::Uniform { min: 5., max: 10. }).unwrap(),
-                until_fix_distr: Distribution::Constant(1.),
-            }))
-            .with_delay_mode(DelayModeChange::Add(DelayMode {
-                name: "LongDelay".into(), 
-                until_delay_distr: df.create(DistributionConfig::Uniform { min: 40., max: 60. }).unwrap(),
-                until_fix_distr: Distribution::Constant(5.),
-            })),
+            .with_delay_mode(DelayModeChange::Add(DelayMode::new(
+                "ShortDelay",
+                df.create(DistributionConfig::Uniform { min: 5., max: 10. }).unwrap(),
+                Distribution::Constant(1.),
+            )))
+            .with_delay_mode(DelayModeChange::Add(DelayMode::new(
+                "LongDelay",
+                df.create(DistributionConfig::Uniform { min: 40., max: 60. }).unwrap(),
+                Distribution::Constant(5.),
+            ))),
         Mailbox::new()
     );
     let mut stock_2 = ComponentModel::Vector3Stock(